@@ -1,18 +1,100 @@
 //! Module for managing PSP22 token data and events.
 
+use crate::client::PSP22Ref;
+use crate::storage::{Map, OnChain, StorageBackend};
 use crate::PSP22Error;
 use ink::prelude::string::String;
 use ink::{
     prelude::{vec, vec::Vec},
     primitives::AccountId,
-    storage::Mapping,
 };
-use ink::env::call::{build_call, ExecutionInput, Selector};
-use ink::env::DefaultEnvironment;
+
+/// Denominator of fees expressed in basis points (1 basis point = 0.01%).
+pub const FEE_DENOMINATOR: u128 = 10_000;
+
+/// Calculates the fee charged on a transfer of `value` tokens.
+///
+/// The fee is rounded down and `fee_bps` above `FEE_DENOMINATOR` is treated as 100%,
+/// so the result never exceeds `value`. The calculation cannot overflow for any `u128` value.
+pub fn calculate_fee(value: u128, fee_bps: u16) -> u128 {
+    let fee_bps = u128::from(fee_bps).min(FEE_DENOMINATOR);
+    // `value` is split into `quotient * FEE_DENOMINATOR + remainder`, so that
+    // neither of the products below can exceed `value` or `FEE_DENOMINATOR^2`.
+    let quotient = value / FEE_DENOMINATOR;
+    let remainder = value % FEE_DENOMINATOR;
+    quotient
+        .saturating_mul(fee_bps)
+        .saturating_add(remainder.saturating_mul(fee_bps) / FEE_DENOMINATOR)
+}
+
+/// Converts `amount` of the underlying token of a wrapper into wrapped token units.
+///
+/// `decimals_offset` is the number of decimals of the wrapped token minus the number
+/// of decimals of the underlying token. The result is rounded down.
+pub fn to_wrapped_amount(amount: u128, decimals_offset: i8) -> Result<u128, PSP22Error> {
+    scale_amount(amount, i16::from(decimals_offset))
+}
+
+/// Converts `amount` of wrapped tokens into units of the underlying token of a wrapper.
+///
+/// The inverse of `to_wrapped_amount`, the result is rounded down.
+pub fn to_underlying_amount(amount: u128, decimals_offset: i8) -> Result<u128, PSP22Error> {
+    scale_amount(amount, -i16::from(decimals_offset))
+}
+
+/// Multiplies `amount` by `10^exponent`, rounding down for negative exponents.
+fn scale_amount(amount: u128, exponent: i16) -> Result<u128, PSP22Error> {
+    let factor = 10u128.checked_pow(u32::from(exponent.unsigned_abs()));
+    match factor {
+        Some(factor) if exponent >= 0 => amount
+            .checked_mul(factor)
+            .ok_or(PSP22Error::Custom(String::from("Scaled amount exceeds u128"))),
+        Some(factor) => Ok(amount / factor),
+        None if exponent < 0 || amount == 0 => Ok(0),
+        None => Err(PSP22Error::Custom(String::from("Scaled amount exceeds u128"))),
+    }
+}
+
+/// Sums the values of a batch sent by `from`, skipping transfers which `credit_batch` skips.
+///
+/// Returns `None` if the total exceeds u128.MAX. Fails if the fee of a transfer exceeds its value.
+fn batch_total(from: AccountId, transfers: &[(AccountId, u128, Option<Fee>)]) -> Result<Option<u128>, PSP22Error> {
+    let mut total = Some(0u128);
+    for (to, value, fee) in transfers {
+        if *to == from {
+            continue;
+        }
+        check_fee(*value, *fee)?;
+        total = total.and_then(|total| total.checked_add(*value));
+    }
+    Ok(total)
+}
+
+/// Attaches no fee to the transfers of a batch.
+fn without_fees(transfers: Vec<(AccountId, u128)>) -> Vec<(AccountId, u128, Option<Fee>)> {
+    transfers.into_iter().map(|(to, value)| (to, value, None)).collect()
+}
+
+/// Fails if the `fee` charged on a transfer exceeds the transferred `value`.
+fn check_fee(value: u128, fee: Option<Fee>) -> Result<(), PSP22Error> {
+    if fee.is_some_and(|fee| fee.amount > value) {
+        return Err(PSP22Error::Custom(String::from("Fee exceeds transferred value")));
+    }
+    Ok(())
+}
+
+/// Share of a transfer credited to the `treasury` account instead of the recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fee {
+    pub treasury: AccountId,
+    pub amount: u128,
+}
 
 /// Represents events emitted during PSP22 operations.
 ///
 /// This enum is used to track state changes in the `PSP22Data` struct.
+/// The contract emits them as its `Transfer` and `Approval` events, see `events.rs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PSP22Event {
     Transfer {
         from: Option<AccountId>,
@@ -38,23 +120,34 @@ pub enum PSP22Event {
 /// (compared to transactions defined by the PSP22 standard or the PSP22 trait).
 ///
 /// `lib.rs` contains an implementation of a smart contract using this class.
+///
+/// The state is kept by the storage backend `B`, the contract storage by default.
+/// `PSP22Data<InMemory>` runs the same code in plain Rust, see `storage.rs`.
 #[ink::storage_item]
 #[derive(Debug, Default)]
-pub struct PSP22Data {
+pub struct PSP22Data<B: StorageBackend = OnChain> {
     total_supply: u128,
-    balances: Mapping<AccountId, u128>,
-    allowances: Mapping<(AccountId, AccountId), u128>,
+    balances: Map<AccountId, u128, B>,
+    allowances: Map<(AccountId, AccountId), u128, B>,
+    #[cfg(feature = "allowance_expiry")]
+    allowance_expiries: Map<(AccountId, AccountId), u64, B>,
+    #[cfg(feature = "enumerable")]
+    enumeration: enumeration::AccountSets<B>,
 }
 
-impl PSP22Data {
+impl<B: StorageBackend> PSP22Data<B> {
     /// Creates a token with `supply` balance, initially held by the `creator` account.
-    pub fn new(supply: u128, creator: AccountId) -> PSP22Data {
+    pub fn new(supply: u128, creator: AccountId) -> PSP22Data<B> {
         let mut data = PSP22Data {
             total_supply: supply,
             balances: Default::default(),
             allowances: Default::default(),
+            #[cfg(feature = "allowance_expiry")]
+            allowance_expiries: Default::default(),
+            #[cfg(feature = "enumerable")]
+            enumeration: Default::default(),
         };
-        data.balances.insert(creator, &supply);
+        data.set_balance(creator, supply);
         data
     }
 
@@ -90,10 +183,57 @@ impl PSP22Data {
     /// # Returns
     ///
     /// The number of tokens still available for the spender.
+    /// With the `allowance_expiry` feature, zero once the deadline of the allowance has passed.
     pub fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+        #[cfg(feature = "allowance_expiry")]
+        if self.is_allowance_expired(owner, spender) {
+            return 0;
+        }
         self.allowances.get((owner, spender)).unwrap_or_default()
     }
 
+    /// Returns at most `limit` token holders, skipping the first `offset` ones.
+    #[cfg(feature = "enumerable")]
+    pub fn holders(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+        self.enumeration.page(None, offset, limit)
+    }
+
+    /// Returns the number of accounts holding a non-zero balance.
+    #[cfg(feature = "enumerable")]
+    pub fn holder_count(&self) -> u32 {
+        self.enumeration.count(None)
+    }
+
+    /// Returns at most `limit` spenders holding a non-zero allowance from `owner`,
+    /// skipping the first `offset` ones.
+    #[cfg(feature = "enumerable")]
+    pub fn approved_spenders(&self, owner: AccountId, offset: u32, limit: u32) -> Vec<AccountId> {
+        self.enumeration.page(Some(owner), offset, limit)
+    }
+
+    /// Returns the number of spenders holding a non-zero allowance from `owner`.
+    #[cfg(feature = "enumerable")]
+    pub fn approved_spender_count(&self, owner: AccountId) -> u32 {
+        self.enumeration.count(Some(owner))
+    }
+
+    /// Gets the allowance granted by `owner` to `spender` together with its deadline.
+    ///
+    /// The amount is reported as zero if the deadline has passed.
+    #[cfg(feature = "allowance_expiry")]
+    pub fn allowance_info(&self, owner: AccountId, spender: AccountId) -> (u128, Option<u64>) {
+        (self.allowance(owner, spender), self.allowance_expiries.get((owner, spender)))
+    }
+
+    /// Returns `true` if the allowance granted by `owner` to `spender` has a deadline
+    /// which has passed at the current timestamp of the backend.
+    #[cfg(feature = "allowance_expiry")]
+    fn is_allowance_expired(&self, owner: AccountId, spender: AccountId) -> bool {
+        self.allowance_expiries
+            .get((owner, spender))
+            .is_some_and(|deadline| B::now() > deadline)
+    }
+
     /// Transfers `value` tokens from `caller` to `to`.
     pub fn transfer(
         &mut self,
@@ -109,16 +249,9 @@ impl PSP22Data {
             return Err(PSP22Error::InsufficientBalance);
         }
 
-        if from_balance == value {
-            self.balances.remove(caller);
-        } else {
-            self.balances
-                .insert(caller, &(from_balance.saturating_sub(value)));
-        }
-        let to_balance = self.balance_of(to);
+        self.set_balance(caller, from_balance.saturating_sub(value));
         // Total supply is limited by u128.MAX so no overflow is possible
-        self.balances
-            .insert(to, &(to_balance.saturating_add(value)));
+        self.set_balance(to, self.balance_of(to).saturating_add(value));
         Ok(vec![PSP22Event::Transfer {
             from: Some(caller),
             to: Some(to),
@@ -151,23 +284,11 @@ impl PSP22Data {
             return Err(PSP22Error::InsufficientBalance);
         }
 
-        if allowance == value {
-            self.allowances.remove((from, caller));
-        } else {
-            self.allowances
-                .insert((from, caller), &(allowance.saturating_sub(value)));
-        }
+        self.set_allowance(from, caller, allowance.saturating_sub(value));
 
-        if from_balance == value {
-            self.balances.remove(from);
-        } else {
-            self.balances
-                .insert(from, &(from_balance.saturating_sub(value)));
-        }
-        let to_balance = self.balance_of(to);
+        self.set_balance(from, from_balance.saturating_sub(value));
         // Total supply is limited by u128.MAX so no overflow is possible
-        self.balances
-            .insert(to, &(to_balance.saturating_add(value)));
+        self.set_balance(to, self.balance_of(to).saturating_add(value));
         Ok(vec![
             PSP22Event::Approval {
                 owner: from,
@@ -182,6 +303,103 @@ impl PSP22Data {
         ])
     }
 
+    /// Transfers tokens from `caller` to every recipient in `transfers`.
+    ///
+    /// The batch is atomic: if the total exceeds the balance of `caller`, nothing is transferred.
+    /// The balance of `caller` is read and written only once.
+    pub fn batch_transfer(
+        &mut self,
+        caller: AccountId,
+        transfers: Vec<(AccountId, u128)>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.batch_transfer_with_fee(caller, without_fees(transfers))
+    }
+
+    /// Transfers tokens from `from` to every recipient in `transfers`, but using
+    /// the allowance granted by `from` to `caller`.
+    ///
+    /// The batch is atomic, the allowance and the balance of `from` are read and written only once.
+    pub fn batch_transfer_from(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        transfers: Vec<(AccountId, u128)>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        self.batch_transfer_from_with_fee(caller, from, without_fees(transfers))
+    }
+
+    /// Like `batch_transfer`, but each transfer carries the fee credited to its treasury
+    /// account, see `transfer_with_fee`.
+    pub fn batch_transfer_with_fee(
+        &mut self,
+        caller: AccountId,
+        transfers: Vec<(AccountId, u128, Option<Fee>)>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        let total = batch_total(caller, &transfers)?
+            .ok_or(PSP22Error::Custom(String::from("Batch total exceeds u128")))?;
+        if total == 0 {
+            return Ok(vec![]);
+        }
+        let from_balance = self.balance_of(caller);
+        if from_balance < total {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+
+        self.set_balance(caller, from_balance.saturating_sub(total));
+        Ok(self.credit_batch(caller, transfers))
+    }
+
+    /// Like `batch_transfer_from`, but each transfer carries the fee credited to its treasury
+    /// account, see `transfer_from_with_fee`.
+    pub fn batch_transfer_from_with_fee(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        transfers: Vec<(AccountId, u128, Option<Fee>)>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if caller == from {
+            return self.batch_transfer_with_fee(caller, transfers);
+        }
+        let total = batch_total(from, &transfers)?;
+        if total == Some(0) {
+            return Ok(vec![]);
+        }
+        // A total above u128.MAX exceeds every allowance
+        let allowance = self.allowance(from, caller);
+        let total = match total {
+            Some(total) if total <= allowance => total,
+            _ => return Err(PSP22Error::InsufficientAllowance),
+        };
+        let from_balance = self.balance_of(from);
+        if from_balance < total {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+
+        self.set_allowance(from, caller, allowance.saturating_sub(total));
+        self.set_balance(from, from_balance.saturating_sub(total));
+        let mut events = vec![PSP22Event::Approval {
+            owner: from,
+            spender: caller,
+            amount: allowance.saturating_sub(total),
+        }];
+        events.extend(self.credit_batch(from, transfers));
+        Ok(events)
+    }
+
+    /// Credits recipients of a batch whose total has already been deducted from `from`.
+    ///
+    /// Transfers to `from` itself and zero-value transfers are skipped, like in `transfer`.
+    fn credit_batch(&mut self, from: AccountId, transfers: Vec<(AccountId, u128, Option<Fee>)>) -> Vec<PSP22Event> {
+        let mut events = Vec::with_capacity(transfers.len());
+        for (to, value, fee) in transfers {
+            if to == from || value == 0 {
+                continue;
+            }
+            events.extend(self.credit_with_fee(from, to, value, fee));
+        }
+        events
+    }
+
     /// Sets a new `value` for allowance granted by `owner` to `spender`.
     /// Overwrites the previously granted value.
     pub fn approve(
@@ -193,10 +411,35 @@ impl PSP22Data {
         if owner == spender {
             return Ok(vec![]);
         }
-        if value == 0 {
-            self.allowances.remove((owner, spender));
-        } else {
-            self.allowances.insert((owner, spender), &value);
+        self.set_allowance(owner, spender, value);
+        #[cfg(feature = "allowance_expiry")]
+        self.allowance_expiries.remove((owner, spender));
+        Ok(vec![PSP22Event::Approval {
+            owner,
+            spender,
+            amount: value,
+        }])
+    }
+
+    /// Sets a new `value` for allowance granted by `owner` to `spender`, valid until
+    /// the `deadline` timestamp (inclusive). Overwrites the previously granted value and deadline.
+    #[cfg(feature = "allowance_expiry")]
+    pub fn approve_with_expiry(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        deadline: u64,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if owner == spender {
+            return Ok(vec![]);
+        }
+        if deadline < B::now() {
+            return Err(PSP22Error::Custom(String::from("Deadline already passed")));
+        }
+        self.set_allowance(owner, spender, value);
+        if value > 0 {
+            self.allowance_expiries.insert((owner, spender), &deadline);
         }
         Ok(vec![PSP22Event::Approval {
             owner,
@@ -217,7 +460,7 @@ impl PSP22Data {
         }
         let allowance = self.allowance(owner, spender);
         let amount = allowance.saturating_add(delta_value);
-        self.allowances.insert((owner, spender), &amount);
+        self.set_allowance(owner, spender, amount);
         Ok(vec![PSP22Event::Approval {
             owner,
             spender,
@@ -240,11 +483,7 @@ impl PSP22Data {
             return Err(PSP22Error::InsufficientAllowance);
         }
         let amount = allowance.saturating_sub(delta_value);
-        if amount == 0 {
-            self.allowances.remove((owner, spender));
-        } else {
-            self.allowances.insert((owner, spender), &amount);
-        }
+        self.set_allowance(owner, spender, amount);
         Ok(vec![PSP22Event::Approval {
             owner,
             spender,
@@ -265,7 +504,7 @@ impl PSP22Data {
             )))?;
         self.total_supply = new_supply;
         let new_balance = self.balance_of(to).saturating_add(value);
-        self.balances.insert(to, &new_balance);
+        self.set_balance(to, new_balance);
         Ok(vec![PSP22Event::Transfer {
             from: None,
             to: Some(to),
@@ -283,11 +522,7 @@ impl PSP22Data {
         if balance < value {
             return Err(PSP22Error::InsufficientBalance);
         }
-        if balance == value {
-            self.balances.remove(from);
-        } else {
-            self.balances.insert(from, &(balance.saturating_sub(value)));
-        }
+        self.set_balance(from, balance.saturating_sub(value));
         self.total_supply = self.total_supply.saturating_sub(value);
         Ok(vec![PSP22Event::Transfer {
             from: Some(from),
@@ -314,17 +549,8 @@ impl PSP22Data {
             return Err(PSP22Error::InsufficientBalance);
         }
 
-        if allowance == value {
-            self.allowances.remove((from, caller));
-        } else {
-            self.allowances
-                .insert((from, caller), &(allowance.saturating_sub(value)));
-        }
-        if balance == value {
-            self.balances.remove(from);
-        } else {
-            self.balances.insert(from, &(balance.saturating_sub(value)));
-        }
+        self.set_allowance(from, caller, allowance.saturating_sub(value));
+        self.set_balance(from, balance.saturating_sub(value));
         self.total_supply = self.total_supply.saturating_sub(value);
         Ok(vec![PSP22Event::Transfer {
             from: Some(from),
@@ -333,6 +559,157 @@ impl PSP22Data {
         }])
     }
 
+    /// Transfers `value` tokens from `caller` to `to`, of which the `fee` tokens
+    /// are credited to its treasury account instead of `to`.
+    ///
+    /// Emits two `Transfer` events: `value - fee` tokens to `to` and the fee to the
+    /// treasury. An event carrying zero tokens is skipped. Without a fee this is `transfer`.
+    pub fn transfer_with_fee(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        value: u128,
+        fee: Option<Fee>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if fee.is_none() {
+            return self.transfer(caller, to, value);
+        }
+        if caller == to || value == 0 {
+            return Ok(vec![]);
+        }
+        check_fee(value, fee)?;
+        let from_balance = self.balance_of(caller);
+        if from_balance < value {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+
+        self.set_balance(caller, from_balance.saturating_sub(value));
+        Ok(self.credit_with_fee(caller, to, value, fee))
+    }
+
+    /// Transfers `value` tokens from `from` to `to` using the allowance granted
+    /// by `from` to `caller`, of which the `fee` tokens are credited to its treasury account.
+    ///
+    /// The whole `value` is deducted from the allowance. Without a fee this is `transfer_from`.
+    pub fn transfer_from_with_fee(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        fee: Option<Fee>,
+    ) -> Result<Vec<PSP22Event>, PSP22Error> {
+        if fee.is_none() {
+            return self.transfer_from(caller, from, to, value);
+        }
+        if from == to || value == 0 {
+            return Ok(vec![]);
+        }
+        if caller == from {
+            return self.transfer_with_fee(caller, to, value, fee);
+        }
+        check_fee(value, fee)?;
+
+        let allowance = self.allowance(from, caller);
+        if allowance < value {
+            return Err(PSP22Error::InsufficientAllowance);
+        }
+        let from_balance = self.balance_of(from);
+        if from_balance < value {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+
+        self.set_allowance(from, caller, allowance.saturating_sub(value));
+
+        self.set_balance(from, from_balance.saturating_sub(value));
+        let mut events = vec![PSP22Event::Approval {
+            owner: from,
+            spender: caller,
+            amount: allowance.saturating_sub(value),
+        }];
+        events.extend(self.credit_with_fee(from, to, value, fee));
+        Ok(events)
+    }
+
+    /// Credits `value` tokens minus the `fee` to `to` and the `fee` to its treasury account.
+    /// The tokens must already be deducted from `from`.
+    fn credit_with_fee(&mut self, from: AccountId, to: AccountId, value: u128, fee: Option<Fee>) -> Vec<PSP22Event> {
+        let fee_amount = fee.map_or(0, |fee| fee.amount);
+        let net_value = value.saturating_sub(fee_amount);
+        let mut events = vec![];
+        // Total supply is limited by u128.MAX so no overflow is possible
+        if net_value > 0 {
+            self.set_balance(to, self.balance_of(to).saturating_add(net_value));
+            events.push(PSP22Event::Transfer {
+                from: Some(from),
+                to: Some(to),
+                value: net_value,
+            });
+        }
+        if let Some(Fee { treasury, amount }) = fee.filter(|fee| fee.amount > 0) {
+            self.set_balance(treasury, self.balance_of(treasury).saturating_add(amount));
+            events.push(PSP22Event::Transfer {
+                from: Some(from),
+                to: Some(treasury),
+                value: amount,
+            });
+        }
+        events
+    }
+
+    /// Returns `true` if a balance of `account` is kept in the storage.
+    #[cfg(any(test, feature = "fuzz"))]
+    #[allow(dead_code)] // Only `model` calls it, and generated crates skip `model`
+    pub(crate) fn stores_balance(&self, account: AccountId) -> bool {
+        self.balances.contains(account)
+    }
+
+    /// Returns `true` if an allowance granted by `owner` to `spender` is kept in the storage.
+    #[cfg(any(test, feature = "fuzz"))]
+    #[allow(dead_code)] // Only `model` calls it, and generated crates skip `model`
+    pub(crate) fn stores_allowance(&self, owner: AccountId, spender: AccountId) -> bool {
+        self.allowances.contains((owner, spender))
+    }
+
+    /// Sets the balance of `account`. Zero balances are removed from the storage.
+    fn set_balance(&mut self, account: AccountId, value: u128) {
+        if value == 0 {
+            self.balances.remove(account);
+            #[cfg(feature = "enumerable")]
+            self.enumeration.remove(None, account);
+        } else {
+            self.balances.insert(account, &value);
+            #[cfg(feature = "enumerable")]
+            self.enumeration.insert(None, account);
+        }
+    }
+
+    /// Sets the allowance granted by `owner` to `spender`. Zero allowances are removed
+    /// from the storage.
+    fn set_allowance(&mut self, owner: AccountId, spender: AccountId, value: u128) {
+        if value == 0 {
+            self.remove_allowance(owner, spender);
+        } else {
+            // An allowance replacing an expired one does not inherit its deadline
+            #[cfg(feature = "allowance_expiry")]
+            if self.is_allowance_expired(owner, spender) {
+                self.allowance_expiries.remove((owner, spender));
+            }
+            self.allowances.insert((owner, spender), &value);
+            #[cfg(feature = "enumerable")]
+            self.enumeration.insert(Some(owner), spender);
+        }
+    }
+
+    /// Removes the allowance granted by `owner` to `spender` along with its deadline.
+    fn remove_allowance(&mut self, owner: AccountId, spender: AccountId) {
+        self.allowances.remove((owner, spender));
+        #[cfg(feature = "allowance_expiry")]
+        self.allowance_expiries.remove((owner, spender));
+        #[cfg(feature = "enumerable")]
+        self.enumeration.remove(Some(owner), spender);
+    }
+
     /// Deposits a specified amount of tokens from the `underlying` token contract to this contract.
     ///
     /// This method transfers tokens from `sender` to the `contract` account (the current contract),
@@ -340,7 +717,7 @@ impl PSP22Data {
     ///
     /// # Arguments
     ///
-    /// * `underlying` - The AccountId of the underlying token contract.
+    /// * `underlying` - Reference to the underlying token contract.
     /// * `sender` - The AccountId of the sender who is depositing tokens.
     /// * `contract` - The AccountId of this contract, which will receive the tokens.
     /// * `value` - The amount of tokens to be deposited.
@@ -349,26 +726,12 @@ impl PSP22Data {
     ///
     /// A `Result<(), PSP22Error>` indicating the success or failure of the operation.
     pub fn deposit(&mut self,
-                   underlying: AccountId,
+                   underlying: &mut PSP22Ref,
                    sender: AccountId,
                    contract: AccountId,
                    value: u128
     ) -> Result<(), PSP22Error> {
-        pub const TRANSFER_FROM_SELECTOR: [u8; 4] = [84, 179, 199, 110];
-
-        build_call::<DefaultEnvironment>()
-            .call(underlying)
-            .gas_limit(0)
-            .transferred_value(0)
-            .exec_input(
-                ExecutionInput::new(Selector::new(TRANSFER_FROM_SELECTOR))
-                    .push_arg(sender)
-                    .push_arg(contract)
-                    .push_arg(value)
-                    .push_arg(Vec::<u8>::new())
-            )
-            .returns::<Result<(), PSP22Error>>()
-            .invoke()
+        underlying.transfer_from(sender, contract, value, Vec::new())
     }
 
     /// Withdraws a specified amount of tokens from this contract to a specified account.
@@ -378,7 +741,7 @@ impl PSP22Data {
     ///
     /// # Arguments
     ///
-    /// * `underlying` - The AccountId of the underlying token contract.
+    /// * `underlying` - Reference to the underlying token contract.
     /// * `account` - The AccountId where tokens will be withdrawn to.
     /// * `value` - The amount of tokens to be withdrawn.
     ///
@@ -386,23 +749,87 @@ impl PSP22Data {
     ///
     /// A `Result<(), PSP22Error>` indicating the success or failure of the operation.
     pub fn withdraw(&mut self,
-                    underlying: AccountId,
+                    underlying: &mut PSP22Ref,
                     account: AccountId,
                     value: u128
     ) -> Result<(), PSP22Error> {
-        pub const TRANSFER_SELECTOR: [u8; 4] = [219, 32, 249, 245];
-
-        build_call::<DefaultEnvironment>()
-            .call(underlying)
-            .gas_limit(0)
-            .transferred_value(0)
-            .exec_input(
-                ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
-                    .push_arg(account)
-                    .push_arg(value)
-                    .push_arg(Vec::<u8>::new())
-            )
-            .returns::<Result<(), PSP22Error>>()
-            .invoke()
+        underlying.transfer(account, value, Vec::new())
+    }
+}
+
+/// Bookkeeping of token holders and approved spenders.
+///
+/// The sets are only kept in sync with the `enumerable` feature, as doing so
+/// costs additional storage operations on every balance and allowance change.
+pub mod enumeration {
+    use ink::{prelude::vec::Vec, primitives::AccountId};
+
+    use crate::storage::{Map, OnChain, StorageBackend};
+
+    /// Key of the account at a position in a set: the key of the set and the position.
+    type MemberKey = (Option<AccountId>, u32);
+
+    /// Key of the position of an account in a set: the key of the set and the account.
+    type PositionKey = (Option<AccountId>, AccountId);
+
+    /// Sets of accounts with O(1) insertion and removal.
+    ///
+    /// The set under the `None` key holds all token holders, the set under
+    /// `Some(owner)` holds the spenders approved by `owner`.
+    #[ink::storage_item]
+    #[derive(Debug, Default)]
+    pub struct AccountSets<B: StorageBackend = OnChain> {
+        members: Map<MemberKey, AccountId, B>,
+        positions: Map<PositionKey, u32, B>,
+        sizes: Map<Option<AccountId>, u32, B>,
+    }
+
+    impl<B: StorageBackend> AccountSets<B> {
+        /// Returns the number of accounts in the set under `key`.
+        pub fn count(&self, key: Option<AccountId>) -> u32 {
+            self.sizes.get(key).unwrap_or_default()
+        }
+
+        /// Returns at most `limit` accounts of the set under `key`, skipping the first `offset`.
+        ///
+        /// The order of accounts is not stable, removing an account moves the last one in its place.
+        pub fn page(&self, key: Option<AccountId>, offset: u32, limit: u32) -> Vec<AccountId> {
+            let end = offset.saturating_add(limit).min(self.count(key));
+            (offset..end)
+                .filter_map(|position| self.members.get((key, position)))
+                .collect()
+        }
+
+        /// Adds `account` to the set under `key`. No-op if it is already there.
+        pub fn insert(&mut self, key: Option<AccountId>, account: AccountId) {
+            if self.positions.contains((key, account)) {
+                return;
+            }
+            let size = self.count(key);
+            self.members.insert((key, size), &account);
+            self.positions.insert((key, account), &size);
+            self.sizes.insert(key, &size.saturating_add(1));
+        }
+
+        /// Removes `account` from the set under `key`. No-op if it is not there.
+        pub fn remove(&mut self, key: Option<AccountId>, account: AccountId) {
+            let Some(position) = self.positions.get((key, account)) else {
+                return;
+            };
+            let last = self.count(key).saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.members.get((key, last)) {
+                    self.members.insert((key, position), &moved);
+                    self.positions.insert((key, moved), &position);
+                }
+            }
+            self.members.remove((key, last));
+            self.positions.remove((key, account));
+            if last == 0 {
+                self.sizes.remove(key);
+            } else {
+                self.sizes.insert(key, &last);
+            }
+        }
     }
 }
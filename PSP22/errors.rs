@@ -23,3 +23,19 @@ pub enum PSP22Error {
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum OwnableError {
 }
+
+/// Errors returned by the `ReentrancyGuard`.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ReentrancyError {
+    /// A guarded message was called while another one was being executed.
+    ReentrantCall,
+}
+
+impl From<ReentrancyError> for PSP22Error {
+    fn from(error: ReentrancyError) -> Self {
+        match error {
+            ReentrancyError::ReentrantCall => PSP22Error::Custom(String::from("Reentrant call")),
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Main module for the PSP22 token implementation.
+//!
+//! This module defines the main `Token` struct and re-exports key components from other modules.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+pub mod client;
+pub mod data;
+pub mod errors;
+pub mod events;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod security;
+pub mod storage;
+pub mod traits;
+mod unit_tests;
+pub use client::PSP22Ref;
+pub use data::{Fee, PSP22Data, PSP22Event};
+pub use errors::PSP22Error;
+pub use traits::PSP22;
+/// PSP22 token implementation.
+///
+/// This struct represents a PSP22 compliant fungible token.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod token {
+    use ink::prelude::vec::Vec;
+    use crate::{Fee, PSP22, PSP22Data, PSP22Error};
+    use crate::traits::PSP22AllowanceExpiry;
+    use crate::traits::PSP22Burnable;
+    #[ink(storage)]
+    pub struct Token {
+        pub data: PSP22Data,
+    }
+    impl Token {
+        /// Creates a new PSP22 token with a specified initial supply.
+        ///
+        /// # Arguments
+        ///
+        /// * `supply` - The total number of tokens to be issued initially.
+        ///
+        /// # Returns
+        ///
+        /// A new instance of `Token`.
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            if let Err(error) = Self::before_initial_mint(supply) {
+                panic!("{:?}", error);
+            }
+            Self {
+                data: PSP22Data::new(supply, Self::env().caller()),
+            }
+        }
+        /// Hook called by the constructor before the initial `supply` is minted to the caller.
+        ///
+        /// Returning an error makes the deployment fail.
+        #[allow(dead_code, unused_variables)]
+        fn before_initial_mint(supply: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook called before `value` tokens are moved from `from` to `to`.
+        ///
+        /// Extensions add their checks here, returning an error aborts the transfer.
+        #[allow(dead_code, unused_variables)]
+        fn before_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook setting the `fee` charged on a transfer of `value` tokens from `from` to `to`.
+        ///
+        /// No fee is charged unless an extension sets one, the fee is part of `value`.
+        #[allow(dead_code, unused_variables)]
+        fn transfer_fee(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            fee: &mut Option<Fee>,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook called after `value` tokens were moved from `from` to `to`.
+        ///
+        /// Returning an error reverts the transfer.
+        #[allow(dead_code, unused_variables)]
+        fn after_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook called before `value` tokens are minted to `to`.
+        #[allow(dead_code, unused_variables)]
+        fn before_mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook called before `value` tokens of `from` are burned.
+        #[allow(dead_code, unused_variables)]
+        fn before_burn(
+            &mut self,
+            from: AccountId,
+            value: u128,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+        /// Hook called before the allowance of `spender` over the tokens of `owner` is set to `value`.
+        #[allow(dead_code, unused_variables)]
+        fn before_approve(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: u128,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+    }
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub spender: AccountId,
+        pub amount: u128,
+    }
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        pub from: Option<AccountId>,
+        #[ink(topic)]
+        pub to: Option<AccountId>,
+        pub value: u128,
+    }
+    crate::psp22_events!(Token);
+    impl PSP22 for Token {
+        /// Returns the total supply of tokens.
+        ///
+        /// # Returns
+        ///
+        /// The total number of tokens in existence.
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+        /// Gets the balance of the specified address.
+        ///
+        /// # Arguments
+        ///
+        /// * `owner` - The address to query the balance of.
+        ///
+        /// # Returns
+        ///
+        /// Number of tokens owned by the given address.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+        /// Gets the amount of tokens that an owner allowed to a spender.
+        ///
+        /// # Arguments
+        ///
+        /// * `owner` - The address which owns the funds.
+        /// * `spender` - The address which will spend the funds.
+        ///
+        /// # Returns
+        ///
+        /// The number of tokens still available for the spender.
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+        /// Transfers tokens to a specified address.
+        ///
+        /// This method moves the `value` amount of tokens from the caller's account
+        /// to the `to` account.
+        ///
+        /// # Arguments
+        ///
+        /// * `to` - The address of the recipient.
+        /// * `value` - The amount of tokens to be transferred.
+        /// * `_data` - Additional data passed with the transfer.
+        ///
+        /// # Returns
+        ///
+        /// An `Ok(())` if the transfer is successful, otherwise a `PSP22Error`.
+        ///
+        /// # Events
+        ///
+        /// Emits a `Transfer` event on successful transfer.
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.before_transfer(caller, to, value)?;
+            let mut fee = None;
+            self.transfer_fee(caller, to, value, &mut fee)?;
+            let events = self.data.transfer_with_fee(caller, to, value, fee)?;
+            self.emit_events(events);
+            self.after_transfer(caller, to, value)?;
+            Ok(())
+        }
+        /// Transfers tokens from one address to another.
+        ///
+        /// This method moves the `value` amount of tokens from the `from` account
+        /// to the `to` account using the allowance mechanism. The caller must
+        /// have an allowance from `from` for at least `value` tokens.
+        ///
+        /// # Arguments
+        ///
+        /// * `from` - The address of the sender.
+        /// * `to` - The address of the recipient.
+        /// * `value` - The amount of tokens to be transferred.
+        /// * `_data` - Additional data passed with the transfer.
+        ///
+        /// # Returns
+        ///
+        /// An `Ok(())` if the transfer is successful, otherwise a `PSP22Error`.
+        ///
+        /// # Events
+        ///
+        /// Emits a `Transfer` event and potentially an `Approval` event on successful transfer.
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.before_transfer(from, to, value)?;
+            let mut fee = None;
+            self.transfer_fee(from, to, value, &mut fee)?;
+            let events = self
+                .data
+                .transfer_from_with_fee(self.env().caller(), from, to, value, fee)?;
+            self.emit_events(events);
+            self.after_transfer(from, to, value)?;
+            Ok(())
+        }
+        /// Approves the passed address to spend the specified amount of tokens on behalf of the caller.
+        ///
+        /// # Arguments
+        ///
+        /// * `spender` - The address which will spend the funds.
+        /// * `value` - The amount of tokens to be spent.
+        ///
+        /// # Returns
+        ///
+        /// An `Ok(())` if the approval is successful, otherwise a `PSP22Error`.
+        ///
+        /// # Events
+        ///
+        /// Emits an `Approval` event on successful approval.
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            spender: AccountId,
+            value: u128,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.before_approve(caller, spender, value)?;
+            let events = self.data.approve(caller, spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+        /// Increases the allowance granted to a spender.
+        ///
+        /// This method adds the `delta_value` to the allowance the caller has granted
+        /// to the `spender`.
+        ///
+        /// # Arguments
+        ///
+        /// * `spender` - The address which will spend the funds.
+        /// * `delta_value` - The amount by which the allowance is to be increased.
+        ///
+        /// # Returns
+        ///
+        /// An `Ok(())` if the increase is successful, otherwise a `PSP22Error`.
+        ///
+        /// # Events
+        ///
+        /// Emits an `Approval` event with the new allowance amount.
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let value = self.allowance(caller, spender).saturating_add(delta_value);
+            self.before_approve(caller, spender, value)?;
+            let events = self.data.increase_allowance(caller, spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+        /// Decreases the allowance granted to a spender.
+        ///
+        /// This method subtracts the `delta_value` from the allowance the caller has
+        /// granted to the `spender`.
+        ///
+        /// # Arguments
+        ///
+        /// * `spender` - The address which will spend the funds.
+        /// * `delta_value` - The amount by which the allowance is to be decreased.
+        ///
+        /// # Returns
+        ///
+        /// An `Ok(())` if the decrease is successful, otherwise a `PSP22Error`.
+        ///
+        /// # Events
+        ///
+        /// Emits an `Approval` event with the new allowance amount.
+        ///
+        /// # Errors
+        ///
+        /// Reverts with `InsufficientAllowance` if the `delta_value` exceeds the current allowance.
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let value = self.allowance(caller, spender).saturating_sub(delta_value);
+            self.before_approve(caller, spender, value)?;
+            let events = self.data.decrease_allowance(caller, spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+    impl PSP22AllowanceExpiry for Token {
+        #[ink(message)]
+        fn approve_with_expiry(
+            &mut self,
+            spender: AccountId,
+            value: u128,
+            deadline: u64,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.before_approve(caller, spender, value)?;
+            let events = self
+                .data
+                .approve_with_expiry(caller, spender, value, deadline)?;
+            self.emit_events(events);
+            Ok(())
+        }
+        #[ink(message)]
+        fn allowance_info(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+        ) -> (u128, Option<u64>) {
+            self.data.allowance_info(owner, spender)
+        }
+    }
+    impl PSP22Burnable for Token {
+        #[ink(message)]
+        fn burn(&mut self, amount: u128) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.before_burn(caller, amount)?;
+            let events = self.data.burn(caller, amount)?;
+            self.emit_events(events);
+            Ok(())
+        }
+        #[ink(message)]
+        fn burn_from(
+            &mut self,
+            account: AccountId,
+            amount: u128,
+        ) -> Result<(), PSP22Error> {
+            self.before_burn(account, amount)?;
+            let events = self.data.burn_from(self.env().caller(), account, amount)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use psp22_allowance_expiry_tests::token::*;
+use psp22_allowance_expiry_tests::PSP22;
+use psp22_allowance_expiry_tests::traits::{PSP22AllowanceExpiry, PSP22Burnable};
+use psp22_allowance_expiry_tests::PSP22Error;
+
+psp22_allowance_expiry_tests::psp22_tests!(Token, Token::new);
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{set_block_timestamp, set_caller}};
+    use super::*;
+
+    #[ink::test]
+    fn allowance_before_deadline_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+        assert_eq!(token.allowance(owner, spender), 5);
+        assert_eq!(token.allowance_info(owner, spender), (5, Some(200)));
+
+        // The deadline itself is still valid
+        set_block_timestamp::<DefaultEnvironment>(200);
+        set_caller::<DefaultEnvironment>(spender);
+        token.transfer_from(owner, recipient, 2, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(recipient), 2);
+        assert_eq!(token.allowance_info(owner, spender), (3, Some(200)));
+    }
+
+    #[ink::test]
+    fn transfer_from_after_deadline_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+
+        set_block_timestamp::<DefaultEnvironment>(201);
+        assert_eq!(token.allowance(owner, spender), 0);
+        assert_eq!(token.allowance_info(owner, spender), (0, Some(200)));
+        set_caller::<DefaultEnvironment>(spender);
+        assert_eq!(
+            token.transfer_from(owner, recipient, 1, Vec::new()),
+            Err(PSP22Error::InsufficientAllowance)
+        );
+        assert_eq!(token.balance_of(recipient), 0);
+    }
+
+    #[ink::test]
+    fn burn_from_after_deadline_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(spender);
+        token.burn_from(owner, 1).expect("Success expected");
+        set_block_timestamp::<DefaultEnvironment>(300);
+        assert_eq!(token.burn_from(owner, 1), Err(PSP22Error::InsufficientAllowance));
+        assert_eq!(token.total_supply(), 9);
+    }
+
+    #[ink::test]
+    fn approve_clears_deadline() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+        token.approve(spender, 7).expect("Success expected");
+        assert_eq!(token.allowance_info(owner, spender), (7, None));
+
+        set_block_timestamp::<DefaultEnvironment>(1_000);
+        assert_eq!(token.allowance(owner, spender), 7);
+    }
+
+    #[ink::test]
+    fn increase_allowance_after_deadline_starts_from_zero() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+
+        set_block_timestamp::<DefaultEnvironment>(201);
+        token.increase_allowance(spender, 1).expect("Success expected");
+        assert_eq!(token.allowance_info(owner, spender), (1, None));
+    }
+
+    #[ink::test]
+    fn spending_whole_allowance_clears_deadline() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        token.approve_with_expiry(spender, 5, 200).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(spender);
+        token.transfer_from(owner, recipient, 5, Vec::new()).expect("Success expected");
+        assert_eq!(token.allowance_info(owner, spender), (0, None));
+    }
+
+    #[ink::test]
+    fn approve_with_past_deadline_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        set_block_timestamp::<DefaultEnvironment>(100);
+        let mut token = Token::new(10);
+        assert!(token.approve_with_expiry(spender, 5, 99).is_err());
+        assert_eq!(token.allowance_info(owner, spender), (0, None));
+    }
+
+    #[test]
+    fn in_memory_data_checks_deadlines_against_its_clock() {
+        use psp22_allowance_expiry_tests::storage::InMemory;
+        use psp22_allowance_expiry_tests::PSP22Data;
+
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        InMemory::set_now(100);
+        let mut data = PSP22Data::<InMemory>::new(10, owner);
+        assert!(data.approve_with_expiry(owner, spender, 5, 99).is_err());
+        data.approve_with_expiry(owner, spender, 5, 200).expect("Success expected");
+
+        InMemory::set_now(200);
+        data.transfer_from(spender, owner, recipient, 2).expect("Success expected");
+        assert_eq!(data.allowance_info(owner, spender), (3, Some(200)));
+
+        InMemory::set_now(201);
+        assert_eq!(data.allowance(owner, spender), 0);
+        assert_eq!(
+            data.transfer_from(spender, owner, recipient, 1),
+            Err(PSP22Error::InsufficientAllowance)
+        );
+    }
+}
@@ -0,0 +1,353 @@
+/// Inserts a suite of ink! unit tests checking that a contract conforms to the PSP22 standard,
+/// as documented in the `PSP22` trait.
+/// `$contract` argument should be the name of the contract struct.
+/// `$constructor` argument should be a function or closure, which takes the initial supply
+/// and initializes `$contract` with the whole supply owned by the caller.
+/// The contract must declare the `Transfer` and `Approval` events documented in `events.rs`
+/// and emit them with `psp22_events!`.
+#[macro_export]
+macro_rules! psp22_tests {
+    ($contract:ident, $constructor:expr) => {
+        #[cfg(test)]
+        mod psp22_unit_tests {
+            use super::*;
+            use ink::env::{test::*, DefaultEnvironment as E};
+            use ink::primitives::AccountId;
+            use $crate::events::{decode_event, field_topic, signature_topic};
+            use $crate::{PSP22, PSP22Error, PSP22Event};
+
+            const SUPPLY: u128 = 1000;
+
+            // Creates the contract with `SUPPLY` tokens owned by Alice, who stays the caller
+            fn setup() -> ($contract, DefaultAccounts<E>) {
+                let accounts = default_accounts::<E>();
+                set_caller(accounts.alice);
+                let token = ($constructor)(SUPPLY);
+                (token, accounts)
+            }
+
+            // Gathers all emitted events, skip `shift` first, decode the rest and return as vector
+            fn decode_events(shift: usize) -> Vec<PSP22Event> {
+                recorded_events()
+                    .skip(shift)
+                    .map(|e| decode_event(&e.topics[0], &e.data).expect("PSP22 event expected"))
+                    .collect()
+            }
+
+            fn events_count() -> usize {
+                recorded_events().count()
+            }
+
+            // Asserts if the given event is a Transfer with particular from, to and value
+            fn assert_transfer(event: &PSP22Event, from: AccountId, to: AccountId, value: u128) {
+                let expected = PSP22Event::Transfer {
+                    from: Some(from),
+                    to: Some(to),
+                    value,
+                };
+                assert_eq!(*event, expected, "Transfer event mismatch");
+            }
+
+            // Asserts if the given event is a Approval with particular owner, spender and amount
+            fn assert_approval(event: &PSP22Event, owner: AccountId, spender: AccountId, amount: u128) {
+                let expected = PSP22Event::Approval {
+                    owner,
+                    spender,
+                    amount,
+                };
+                assert_eq!(*event, expected, "Approval event mismatch");
+            }
+
+            fn set_caller(sender: AccountId) {
+                ink::env::test::set_caller::<E>(sender);
+            }
+
+            #[ink::test]
+            fn constructor_assigns_supply_to_caller() {
+                let (token, accounts) = setup();
+                assert_eq!(token.total_supply(), SUPPLY);
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(token.balance_of(accounts.bob), 0);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            }
+
+            #[ink::test]
+            fn transfer_works() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.transfer(accounts.bob, 10, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY - 10);
+                assert_eq!(token.balance_of(accounts.bob), 10);
+                assert_eq!(token.total_supply(), SUPPLY);
+
+                let events = decode_events(start);
+                assert_eq!(events.len(), 1);
+                assert_transfer(&events[0], accounts.alice, accounts.bob, 10);
+            }
+
+            #[ink::test]
+            fn events_are_indexed_by_topics() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.transfer(accounts.bob, 10, vec![]), Ok(()));
+                assert_eq!(token.approve(accounts.charlie, 20), Ok(()));
+
+                let events: Vec<_> = recorded_events().skip(start).collect();
+                assert_eq!(events.len(), 2);
+                let transfer_topics = [
+                    signature_topic("Transfer"),
+                    field_topic("Transfer", "from", &Some(accounts.alice)),
+                    field_topic("Transfer", "to", &Some(accounts.bob)),
+                ];
+                assert_eq!(events[0].topics, transfer_topics.map(Vec::from));
+                let approval_topics = [
+                    signature_topic("Approval"),
+                    field_topic("Approval", "owner", &accounts.alice),
+                    field_topic("Approval", "spender", &accounts.charlie),
+                ];
+                assert_eq!(events[1].topics, approval_topics.map(Vec::from));
+            }
+
+            #[ink::test]
+            fn transfer_whole_balance_works() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.transfer(accounts.bob, SUPPLY, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), 0);
+                assert_eq!(token.balance_of(accounts.bob), SUPPLY);
+            }
+
+            #[ink::test]
+            fn transfer_to_self_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                // Succeeds even if the value exceeds the balance
+                assert_eq!(token.transfer(accounts.alice, SUPPLY + 1, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_of_zero_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                set_caller(accounts.bob);
+                // Succeeds even if the caller has no tokens
+                assert_eq!(token.transfer(accounts.charlie, 0, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.charlie), 0);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_with_insufficient_balance_fails() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(
+                    token.transfer(accounts.bob, SUPPLY + 1, vec![]),
+                    Err(PSP22Error::InsufficientBalance)
+                );
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(token.balance_of(accounts.bob), 0);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_from_own_account_needs_no_allowance() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.transfer_from(accounts.alice, accounts.bob, 10, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY - 10);
+                assert_eq!(token.balance_of(accounts.bob), 10);
+
+                // No Approval event, as no allowance was spent
+                let events = decode_events(start);
+                assert_eq!(events.len(), 1);
+                assert_transfer(&events[0], accounts.alice, accounts.bob, 10);
+            }
+
+            #[ink::test]
+            fn transfer_from_spends_allowance() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 100), Ok(()));
+                let start = events_count();
+                set_caller(accounts.bob);
+                assert_eq!(token.transfer_from(accounts.alice, accounts.charlie, 40, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY - 40);
+                assert_eq!(token.balance_of(accounts.charlie), 40);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 60);
+
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                assert_approval(&events[0], accounts.alice, accounts.bob, 60);
+                assert_transfer(&events[1], accounts.alice, accounts.charlie, 40);
+            }
+
+            #[ink::test]
+            fn transfer_from_whole_allowance_works() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 100), Ok(()));
+                set_caller(accounts.bob);
+                assert_eq!(token.transfer_from(accounts.alice, accounts.bob, 100, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.bob), 100);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            }
+
+            #[ink::test]
+            fn transfer_from_to_same_account_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                set_caller(accounts.bob);
+                // Succeeds even without any allowance
+                assert_eq!(token.transfer_from(accounts.alice, accounts.alice, 10, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_from_of_zero_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                set_caller(accounts.bob);
+                // Succeeds even without any allowance
+                assert_eq!(token.transfer_from(accounts.alice, accounts.charlie, 0, vec![]), Ok(()));
+                assert_eq!(token.balance_of(accounts.charlie), 0);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_from_with_insufficient_allowance_fails() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+                let start = events_count();
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.transfer_from(accounts.alice, accounts.charlie, 11, vec![]),
+                    Err(PSP22Error::InsufficientAllowance)
+                );
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn transfer_from_with_insufficient_balance_fails() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, SUPPLY + 1), Ok(()));
+                let start = events_count();
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.transfer_from(accounts.alice, accounts.charlie, SUPPLY + 1, vec![]),
+                    Err(PSP22Error::InsufficientBalance)
+                );
+                assert_eq!(token.balance_of(accounts.alice), SUPPLY);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), SUPPLY + 1);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn insufficient_allowance_takes_precedence_over_insufficient_balance() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+                set_caller(accounts.bob);
+                assert_eq!(
+                    token.transfer_from(accounts.alice, accounts.charlie, SUPPLY + 1, vec![]),
+                    Err(PSP22Error::InsufficientAllowance)
+                );
+            }
+
+            #[ink::test]
+            fn approve_works() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.approve(accounts.bob, 100), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 100);
+                // Successive calls overwrite the previous value
+                assert_eq!(token.approve(accounts.bob, 30), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 30);
+                assert_eq!(token.approve(accounts.bob, 0), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+
+                let events = decode_events(start);
+                assert_eq!(events.len(), 3);
+                assert_approval(&events[0], accounts.alice, accounts.bob, 100);
+                assert_approval(&events[1], accounts.alice, accounts.bob, 30);
+                assert_approval(&events[2], accounts.alice, accounts.bob, 0);
+            }
+
+            #[ink::test]
+            fn approve_self_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.approve(accounts.alice, 100), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.alice), 0);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn increase_allowance_works() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                assert_eq!(token.increase_allowance(accounts.bob, 10), Ok(()));
+                assert_eq!(token.increase_allowance(accounts.bob, 5), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 15);
+
+                // Events carry the new allowance amount
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                assert_approval(&events[0], accounts.alice, accounts.bob, 10);
+                assert_approval(&events[1], accounts.alice, accounts.bob, 15);
+            }
+
+            #[ink::test]
+            fn increase_allowance_for_self_or_by_zero_is_noop() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+                let start = events_count();
+                assert_eq!(token.increase_allowance(accounts.alice, 10), Ok(()));
+                assert_eq!(token.increase_allowance(accounts.bob, 0), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.alice), 0);
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn decrease_allowance_works() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 15), Ok(()));
+                let start = events_count();
+                assert_eq!(token.decrease_allowance(accounts.bob, 5), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+                assert_eq!(token.decrease_allowance(accounts.bob, 10), Ok(()));
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+
+                // Events carry the new allowance amount
+                let events = decode_events(start);
+                assert_eq!(events.len(), 2);
+                assert_approval(&events[0], accounts.alice, accounts.bob, 10);
+                assert_approval(&events[1], accounts.alice, accounts.bob, 0);
+            }
+
+            #[ink::test]
+            fn decrease_allowance_below_zero_fails() {
+                let (mut token, accounts) = setup();
+                assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+                let start = events_count();
+                assert_eq!(
+                    token.decrease_allowance(accounts.bob, 11),
+                    Err(PSP22Error::InsufficientAllowance)
+                );
+                assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+                assert_eq!(decode_events(start).len(), 0);
+            }
+
+            #[ink::test]
+            fn decrease_allowance_for_self_or_by_zero_is_noop() {
+                let (mut token, accounts) = setup();
+                let start = events_count();
+                // Succeeds even though no allowance was granted
+                assert_eq!(token.decrease_allowance(accounts.alice, 10), Ok(()));
+                assert_eq!(token.decrease_allowance(accounts.bob, 0), Ok(()));
+                assert_eq!(decode_events(start).len(), 0);
+            }
+        }
+    };
+}
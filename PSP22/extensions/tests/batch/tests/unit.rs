@@ -0,0 +1,174 @@
+use psp22_batch_tests::token::*;
+use psp22_batch_tests::PSP22;
+use psp22_batch_tests::traits::PSP22Batch;
+
+psp22_batch_tests::psp22_tests!(Token, Token::new);
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{recorded_events, set_caller}};
+    use psp22_batch_tests::PSP22Error;
+    use super::*;
+
+    // Decodes the event at `index` as a `Transfer`
+    fn decode_transfer(index: usize) -> (Option<AccountId>, Option<AccountId>, u128) {
+        let event = recorded_events().nth(index).expect("Event expected");
+        match psp22_batch_tests::events::decode_event(&event.topics[0], &event.data) {
+            Some(psp22_batch_tests::PSP22Event::Transfer { from, to, value }) => (from, to, value),
+            _ => panic!("Transfer event expected"),
+        }
+    }
+
+    #[ink::test]
+    fn batch_transfer_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        let start = recorded_events().count();
+
+        token.batch_transfer(vec![(alice, 10), (bob, 20), (alice, 5)]).expect("Success expected");
+        assert_eq!(token.balance_of(owner), 65);
+        assert_eq!(token.balance_of(alice), 15);
+        assert_eq!(token.balance_of(bob), 20);
+        assert_eq!(token.total_supply(), 100);
+
+        assert_eq!(recorded_events().count(), start + 3);
+        assert_eq!(decode_transfer(start), (Some(owner), Some(alice), 10));
+        assert_eq!(decode_transfer(start + 1), (Some(owner), Some(bob), 20));
+        assert_eq!(decode_transfer(start + 2), (Some(owner), Some(alice), 5));
+    }
+
+    #[ink::test]
+    fn batch_transfer_is_atomic() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        let start = recorded_events().count();
+
+        let result = token.batch_transfer(vec![(alice, 60), (bob, 41)]);
+        assert_eq!(result, Err(PSP22Error::InsufficientBalance));
+        assert_eq!(token.balance_of(owner), 100);
+        assert_eq!(token.balance_of(alice), 0);
+        assert_eq!(token.balance_of(bob), 0);
+        assert_eq!(recorded_events().count(), start);
+    }
+
+    #[ink::test]
+    fn batch_transfer_with_overflowing_total_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(u128::MAX);
+
+        let result = token.batch_transfer(vec![(alice, u128::MAX), (alice, 1)]);
+        assert_eq!(result, Err(PSP22Error::Custom(String::from("Batch total exceeds u128"))));
+        assert_eq!(token.balance_of(owner), u128::MAX);
+    }
+
+    #[ink::test]
+    fn batch_transfer_skips_empty_and_self_transfers() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        let start = recorded_events().count();
+
+        // Transfers to self are not counted towards the total
+        token.batch_transfer(vec![(owner, 100), (alice, 0), (alice, 100)]).expect("Success expected");
+        assert_eq!(token.balance_of(owner), 0);
+        assert_eq!(token.balance_of(alice), 100);
+        assert_eq!(recorded_events().count(), start + 1);
+
+        token.batch_transfer(Vec::new()).expect("Success expected");
+        assert_eq!(recorded_events().count(), start + 1);
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let alice = AccountId::from([0x3; 32]);
+        let bob = AccountId::from([0x4; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        token.approve(spender, 50).expect("Success expected");
+        let start = recorded_events().count();
+
+        set_caller::<DefaultEnvironment>(spender);
+        token.batch_transfer_from(owner, vec![(alice, 10), (bob, 30)]).expect("Success expected");
+        assert_eq!(token.balance_of(owner), 60);
+        assert_eq!(token.balance_of(alice), 10);
+        assert_eq!(token.balance_of(bob), 30);
+        assert_eq!(token.allowance(owner, spender), 10);
+
+        // A single approval followed by the transfers
+        assert_eq!(recorded_events().count(), start + 3);
+        assert_eq!(decode_transfer(start + 1), (Some(owner), Some(alice), 10));
+        assert_eq!(decode_transfer(start + 2), (Some(owner), Some(bob), 30));
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_with_insufficient_allowance_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let alice = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        token.approve(spender, 50).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(spender);
+        let result = token.batch_transfer_from(owner, vec![(alice, 30), (alice, 21)]);
+        assert_eq!(result, Err(PSP22Error::InsufficientAllowance));
+        assert_eq!(token.balance_of(owner), 100);
+        assert_eq!(token.allowance(owner, spender), 50);
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_with_insufficient_balance_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let alice = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        token.approve(spender, 500).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(spender);
+        let result = token.batch_transfer_from(owner, vec![(alice, 100), (alice, 1)]);
+        assert_eq!(result, Err(PSP22Error::InsufficientBalance));
+        assert_eq!(token.balance_of(owner), 100);
+        assert_eq!(token.allowance(owner, spender), 500);
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_checks_allowance_before_balance() {
+        let owner = AccountId::from([0x1; 32]);
+        let spender = AccountId::from([0x2; 32]);
+        let alice = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+        token.approve(spender, 50).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(spender);
+        let result = token.batch_transfer_from(owner, vec![(alice, 100), (alice, 1)]);
+        assert_eq!(result, Err(PSP22Error::InsufficientAllowance));
+        let result = token.batch_transfer_from(owner, vec![(alice, u128::MAX), (alice, 1)]);
+        assert_eq!(result, Err(PSP22Error::InsufficientAllowance));
+        assert_eq!(token.allowance(owner, spender), 50);
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_own_account_needs_no_allowance() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(100);
+
+        token.batch_transfer_from(owner, vec![(alice, 40)]).expect("Success expected");
+        assert_eq!(token.balance_of(alice), 40);
+    }
+}
@@ -0,0 +1,167 @@
+//! In-memory PSP22 tokens answering the calls of `PSP22Ref` in unit tests.
+//!
+//! The off-chain environment of ink! cannot call other contracts, so with the `mock` feature
+//! `PSP22Ref` hands the calls it builds to [`Mock`]. The selector and the arguments of a call
+//! are decoded like a deployed token would, the message is executed against the
+//! `PSP22Data<InMemory>` of the token deployed at the callee and its encoded output is
+//! decoded by the caller. Calls are made on behalf of the contract under test.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use ink::env::call::{Call, CallParams};
+use ink::env::hash::{Blake2x256, HashOutput};
+use ink::env::{DefaultEnvironment, Error as EnvError};
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+use ink::primitives::{AccountId, LangError, MessageResult};
+use scale::{Decode, Encode};
+
+use crate::client::Invoke;
+use crate::storage::InMemory;
+use crate::{PSP22Data, PSP22Error};
+
+/// Denominator of transfer fees, expressed in basis points.
+pub const BASIS_POINTS: u128 = 10_000;
+
+struct Token {
+    data: PSP22Data<InMemory>,
+    fee: u128,
+    paused: bool,
+    trapping: bool,
+}
+
+thread_local! {
+    static TOKENS: RefCell<BTreeMap<AccountId, Token>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+fn with_token<R>(address: AccountId, f: impl FnOnce(&mut Token) -> R) -> R {
+    TOKENS.with(|tokens| f(tokens.borrow_mut().get_mut(&address).expect("Token not deployed")))
+}
+
+/// Deploys a token at `address` with `supply` held by `holder`.
+pub fn deploy(address: AccountId, supply: u128, holder: AccountId) {
+    let token = Token {
+        data: PSP22Data::new(supply, holder),
+        fee: 0,
+        paused: false,
+        trapping: false,
+    };
+    TOKENS.with(|tokens| tokens.borrow_mut().insert(address, token));
+}
+
+/// Burns `fee` basis points of every amount transferred by the token at `address`
+/// from the receiver, like a fee-on-transfer token.
+pub fn set_fee(address: AccountId, fee: u128) {
+    with_token(address, |token| token.fee = fee);
+}
+
+/// Makes every transfer of the token at `address` fail, like a paused token, or succeed again.
+/// Balances can still be read.
+pub fn set_paused(address: AccountId, paused: bool) {
+    with_token(address, |token| token.paused = paused);
+}
+
+/// Makes every call to the token at `address` trap, or stop trapping.
+pub fn set_trapping(address: AccountId, trapping: bool) {
+    with_token(address, |token| token.trapping = trapping);
+}
+
+/// Returns the balance of `owner` in the token at `address`.
+pub fn balance_of(address: AccountId, owner: AccountId) -> u128 {
+    with_token(address, |token| token.data.balance_of(owner))
+}
+
+/// Returns the total supply of the token at `address`.
+pub fn total_supply(address: AccountId) -> u128 {
+    with_token(address, |token| token.data.total_supply())
+}
+
+/// Sets the allowance of `spender` to withdraw from `owner` in the token at `address`.
+pub fn approve(address: AccountId, owner: AccountId, spender: AccountId, value: u128) {
+    with_token(address, |token| token.data.approve(owner, spender, value))
+        .expect("Approval failed");
+}
+
+/// Transfers `value` from `from` to `to` in the token at `address`, charging the fee.
+pub fn transfer(address: AccountId, from: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+    with_token(address, |token| token.transfer(from, to, value))
+}
+
+impl Token {
+    fn transfer(&mut self, from: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+        self.ensure_not_paused()?;
+        self.data.transfer(from, to, value)?;
+        self.charge_fee(to, value)
+    }
+
+    fn transfer_from(
+        &mut self,
+        caller: AccountId,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error> {
+        self.ensure_not_paused()?;
+        self.data.transfer_from(caller, from, to, value)?;
+        self.charge_fee(to, value)
+    }
+
+    fn ensure_not_paused(&self) -> Result<(), PSP22Error> {
+        if self.paused {
+            return Err(PSP22Error::Custom(String::from("Token is paused")));
+        }
+        Ok(())
+    }
+
+    fn charge_fee(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+        self.data.burn(to, value * self.fee / BASIS_POINTS)?;
+        Ok(())
+    }
+
+    /// Executes the message with `selector`, returning its encoded `MessageResult`.
+    /// Anyone may mint.
+    fn call(&mut self, caller: AccountId, selector: &[u8], args: &mut &[u8]) -> Result<Vec<u8>, EnvError> {
+        if self.trapping {
+            return Err(EnvError::CalleeTrapped);
+        }
+        let output = if selector == self::selector("PSP22::balance_of") {
+            let owner = AccountId::decode(args)?;
+            Ok::<_, LangError>(self.data.balance_of(owner)).encode()
+        } else if selector == self::selector("PSP22::transfer") {
+            let (to, value, _data) = <(AccountId, u128, Vec<u8>)>::decode(args)?;
+            Ok::<_, LangError>(self.transfer(caller, to, value)).encode()
+        } else if selector == self::selector("PSP22::transfer_from") {
+            let (from, to, value, _data) = <(AccountId, AccountId, u128, Vec<u8>)>::decode(args)?;
+            Ok::<_, LangError>(self.transfer_from(caller, from, to, value)).encode()
+        } else if selector == self::selector("PSP22Mintable::mint") {
+            let (to, value) = <(AccountId, u128)>::decode(args)?;
+            Ok::<_, LangError>(self.data.mint(to, value).map(|_| ())).encode()
+        } else {
+            Err::<(), _>(LangError::CouldNotReadInput).encode()
+        };
+        Ok(output)
+    }
+}
+
+/// Returns the selector of `message`, given as `<Trait>::<message>`, the way ink! computes it.
+fn selector(message: &str) -> [u8; 4] {
+    let mut hash = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(message.as_bytes(), &mut hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Answers the calls of `PSP22Ref` with the tokens deployed by [`deploy`].
+pub struct Mock;
+
+impl Invoke for Mock {
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError> {
+        let caller = ink::env::account_id::<DefaultEnvironment>();
+        let input = params.exec_input().encode();
+        let (selector, mut args) = input.split_at(4);
+        let output = with_token(*params.callee(), |token| token.call(caller, selector, &mut args))?;
+        Ok(MessageResult::<R>::decode(&mut &output[..])?)
+    }
+}
@@ -1,3 +1,6 @@
+use psp22_burnable_tests::token::*;
+
+psp22_burnable_tests::psp22_tests!(Token, Token::new);
 
 #[cfg(test)]
 mod tests {
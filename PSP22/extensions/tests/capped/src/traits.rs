@@ -0,0 +1,605 @@
+//! Trait definitions for PSP22 token functionalities.
+
+use ink::{
+    prelude::{string::String, vec::Vec},
+    primitives::{AccountId, Hash},
+};
+
+use crate::errors::PSP22Error;
+use crate::errors::OwnableError;
+
+/// Core functionalities of a PSP22 token.
+///
+/// This trait defines methods for querying token supply, balance, and allowance,
+/// and for executing transfers and approvals.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+
+    /// Returns the account balance for the specified `owner`.
+    ///
+    /// Returns `0` if the account is non-existent.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u128;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    ///
+    /// Returns `0` if no allowance has been set.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> u128;
+
+    /// Transfers `value` amount of tokens from the caller's account to account `to`
+    /// with additional `data` in unspecified format.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted.
+    ///
+    /// No-op if the caller and `to` is the same address or `value` is zero, returns success
+    /// and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Transfers `value` tokens on the behalf of `from` to the account `to`
+    /// with additional `data` in unspecified format.
+    ///
+    /// If `from` and the caller are different addresses, the caller must be allowed
+    /// by `from` to spend at least `value` tokens.
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted.
+    ///
+    /// No-op if `from` and `to` is the same address or `value` is zero, returns success
+    /// and no events are emitted.
+    ///
+    /// If `from` and the caller are different addresses, a successful transfer results
+    /// in decreased allowance by `from` to the caller and an `Approval` event with
+    /// the new allowance amount is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the balance of the account
+    /// `from`.
+    ///
+    /// Reverts with `InsufficientAllowance` if `from` and the caller are different addresses and
+    /// the `value` exceeds the allowance granted by `from` to the caller.
+    ///
+    /// If conditions for both `InsufficientBalance` and `InsufficientAllowance` errors are met,
+    /// reverts with `InsufficientAllowance`.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the total amount of `value`.
+    ///
+    /// Successive calls of this method overwrite previous values.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event is emitted.
+    ///
+    /// No-op if the caller and `spender` is the same address, returns success and no events are
+    /// emitted.
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    /// Increases by `delta-value` the allowance granted to `spender` by the caller.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event with the new allowance amount is emitted.
+    ///
+    /// No-op if the caller and `spender` is the same address or `delta-value` is zero, returns
+    /// success and no events are emitted.
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Decreases by `delta-value` the allowance granted to `spender` by the caller.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event with the new allowance amount is emitted.
+    ///
+    /// No-op if the caller and `spender` is the same address or `delta-value` is zero, returns
+    /// success and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientAllowance` if `spender` and the caller are different addresses
+    /// and the `delta-value` exceeds the allowance granted by the caller to `spender`.
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Trait for accessing PSP22 token metadata.
+///
+/// Provides methods to get token name, symbol, and decimals.
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    /// Returns the token name.
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+    /// Returns the token symbol.
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+    /// Returns the token decimals.
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
+/// Trait for managing PSP22 token metadata.
+///
+/// Provides the optional `icon_uri` and `description` fields and allows the owner
+/// to update the metadata until it is locked. Every change emits a `MetadataUpdated` event.
+#[ink::trait_definition]
+pub trait PSP22MetadataManageable {
+    /// Returns the URI of the token icon.
+    #[ink(message)]
+    fn token_icon_uri(&self) -> Option<String>;
+
+    /// Returns the description of the token.
+    #[ink(message)]
+    fn token_description(&self) -> Option<String>;
+
+    /// Returns `true` if the metadata can no longer be changed.
+    #[ink(message)]
+    fn metadata_locked(&self) -> bool;
+
+    /// Replaces the name, symbol, icon URI and description of the token.
+    ///
+    /// Decimals are set once in the constructor, as changing them would change
+    /// the meaning of every balance.
+    ///
+    /// On success a `MetadataUpdated` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (Metadata is locked)` if `lock_metadata` was called.
+    #[ink(message)]
+    fn set_metadata(
+        &mut self,
+        name: Option<String>,
+        symbol: Option<String>,
+        icon_uri: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Locks the metadata permanently.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (Metadata is locked)` if the metadata is already locked.
+    #[ink(message)]
+    fn lock_metadata(&mut self) -> Result<(), PSP22Error>;
+}
+
+/// Trait for PSP22 token burning functionalities.
+///
+/// Allows burning of tokens, reducing the total supply.
+#[ink::trait_definition]
+pub trait PSP22Burnable {
+    /// Burns `value` tokens from senders account.
+    ///
+    /// The selector for this message is `0x7a9da510`
+    /// (first 4 bytes of `blake2b_256("PSP22Burnable::burn")`).
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted with `None` recipient.
+    ///
+    /// No-op if `value` is zero, returns success and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance.
+    #[ink(message)]
+    fn burn(&mut self, value: u128) -> Result<(), PSP22Error>;
+
+    /// Burns `value` tokens from the "account" account id. Spends allowances.
+    ///
+    /// The selector for this message are
+    /// first 4 bytes of `blake2b_256("PSP22Burnable::burn_from")`
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted with `None` recipient.
+    ///
+    /// No-op if `value` is zero, returns success and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the `value` exceeds the caller's balance.
+    #[ink(message)]
+    fn burn_from(&mut self, account: AccountId, value: u128) -> Result<(), PSP22Error>;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Mintable {
+    /// Mints `value` tokens to "to" account id.
+    ///
+    /// The selector for this message is `0xfc3c75d4`
+    /// (first 4 bytes of `blake2b_256("PSP22Mintable::mint")`).
+    ///
+    /// # Events
+    ///
+    /// On success a `Transfer` event is emitted with `None` sender.
+    ///
+    /// No-op if `value` is zero, returns success and no events are emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (max supply exceeded)` if the total supply increased by
+    /// `value` exceeds maximal value of `u128` type.
+    #[ink(message)]
+    fn mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error>;
+}
+
+/// Trait for pausing and unpausing token transfers.
+///
+/// This trait allows the contract owner to pause or unpause token transfers,
+/// which can be useful in emergency situations or during maintenance.
+#[ink::trait_definition]
+pub trait PSP22Pausable {
+    /// Pauses all token transfers.
+    ///
+    /// This method is used to temporarily halt all transfer operations.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    #[ink(message)]
+    fn pause(&mut self) -> Result<(), PSP22Error>;
+
+    /// Unpauses all token transfers.
+    ///
+    /// This method re-enables token transfer operations.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    #[ink(message)]
+    fn unpause(&mut self) -> Result<(), PSP22Error>;
+}
+
+/// Trait for wrapping and unwrapping PSP22 tokens.
+///
+/// This trait provides methods for depositing and withdrawing tokens,
+/// often used in implementations that wrap other token standards.
+#[ink::trait_definition]
+pub trait PSP22Wrapper {
+    /// Deposits tokens into the contract for a specified account.
+    ///
+    /// This method allows a user to add tokens to the contract, which can be used
+    /// for various functionalities like staking or liquidity provision.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account for which the tokens will be deposited.
+    /// * `amount` - The amount of underlying tokens to deposit. If the underlying token has
+    ///   more decimals than the wrapped one, only the part convertible without a remainder
+    ///   is taken from the caller.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating the success or failure of the operation.
+    #[ink(message)]
+    fn deposit_for(&mut self, account: AccountId, amount: u128) -> Result<(), PSP22Error>;
+
+    /// Withdraws tokens from the contract to a specified account.
+    ///
+    /// This method allows users to withdraw their tokens from the contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account to which the tokens will be withdrawn.
+    /// * `amount` - The amount of wrapped tokens to withdraw. If the wrapped token has
+    ///   more decimals than the underlying one, only the part convertible without a remainder
+    ///   is burned from the caller.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating the success or failure of the operation.
+    #[ink(message)]
+    fn withdraw_to(&mut self, account: AccountId, amount: u128) -> Result<(), PSP22Error>;
+
+    /// Mints wrapped tokens for underlying tokens sent to the contract directly,
+    /// instead of through `deposit_for`.
+    ///
+    /// The minted amount is the underlying balance of the contract, converted to wrapped
+    /// token units, minus the total supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account receiving the minted tokens.
+    #[ink(message)]
+    fn recover(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Returns the number of decimals of the wrapped token minus the number of decimals
+    /// of the underlying token.
+    #[ink(message)]
+    fn decimals_offset(&self) -> i8;
+}
+
+/// Trait for wrapping the chain's native currency, like WETH.
+///
+/// Every token is backed by one unit of the native currency held by the contract,
+/// so the total supply never exceeds the contract's native balance.
+#[ink::trait_definition]
+pub trait PSP22NativeWrapper {
+    /// Mints tokens to the caller, 1:1 with the native currency transferred with the call.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event from `None` is emitted. No-op if nothing was transferred.
+    #[ink(message, payable)]
+    fn deposit(&mut self) -> Result<(), PSP22Error>;
+
+    /// Burns `amount` tokens of the caller and transfers the same amount
+    /// of the native currency back to the caller.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event to `None` is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the caller holds less than `amount` tokens.
+    ///
+    /// Reverts with `Custom (native transfer failed)` if the native currency cannot be transferred.
+    #[ink(message)]
+    fn withdraw(&mut self, amount: u128) -> Result<(), PSP22Error>;
+
+    /// Returns `true` if the native balance of the contract covers the total supply.
+    #[ink(message)]
+    fn is_fully_backed(&self) -> bool;
+}
+
+/// Trait for charging a fee on token transfers.
+///
+/// A fee, expressed in basis points, is taken from every `transfer` and `transfer_from`
+/// and credited to the treasury account. Transfers from or to fee-exempt accounts
+/// (and the treasury itself) are free of charge.
+#[ink::trait_definition]
+pub trait PSP22Fees {
+    /// Returns the fee charged on transfers, in basis points (1 basis point = 0.01%).
+    #[ink(message)]
+    fn fee_bps(&self) -> u16;
+
+    /// Returns the account collecting the fees.
+    #[ink(message)]
+    fn treasury(&self) -> AccountId;
+
+    /// Returns `true` if transfers from or to `account` are not charged.
+    #[ink(message)]
+    fn is_fee_exempt(&self, account: AccountId) -> bool;
+
+    /// Sets the fee charged on transfers.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (fee too high)` if `fee_bps` exceeds 10 000 basis points.
+    #[ink(message)]
+    fn set_fee(&mut self, fee_bps: u16) -> Result<(), PSP22Error>;
+
+    /// Sets the account collecting the fees.
+    #[ink(message)]
+    fn set_treasury(&mut self, treasury: AccountId) -> Result<(), PSP22Error>;
+
+    /// Exempts `account` from fees, or revokes the exemption if `exempt` is `false`.
+    #[ink(message)]
+    fn set_fee_exempt(&mut self, account: AccountId, exempt: bool) -> Result<(), PSP22Error>;
+}
+
+/// Trait for allowances limited in time.
+///
+/// An allowance granted with a deadline is treated as zero by `allowance`, `transfer_from`
+/// and every other message spending allowances once the block timestamp passes the deadline.
+/// Requires the `allowance_expiry` feature, which stores the deadlines in `PSP22Data`.
+#[ink::trait_definition]
+pub trait PSP22AllowanceExpiry {
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the total amount of `value`, until the `deadline` timestamp (inclusive).
+    ///
+    /// Successive calls of this method (and of `approve`) overwrite previous values and deadlines.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event is emitted.
+    ///
+    /// No-op if the caller and `spender` is the same address, returns success and no events are
+    /// emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (deadline already passed)` if `deadline` is earlier than
+    /// the current block timestamp.
+    #[ink(message)]
+    fn approve_with_expiry(
+        &mut self,
+        spender: AccountId,
+        value: u128,
+        deadline: u64,
+    ) -> Result<(), PSP22Error>;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`
+    /// and the deadline of the allowance, if there is one.
+    ///
+    /// The amount is `0` if the deadline has passed.
+    #[ink(message)]
+    fn allowance_info(&self, owner: AccountId, spender: AccountId) -> (u128, Option<u64>);
+}
+
+/// Trait for enumerating token holders and approved spenders.
+///
+/// Requires the `enumerable` feature, which keeps the holder and spender sets
+/// in sync with balances and allowances.
+#[ink::trait_definition]
+pub trait PSP22Enumerable {
+    /// Returns at most `limit` accounts holding a non-zero balance, skipping the first `offset`.
+    ///
+    /// The order of accounts is not stable between transactions.
+    #[ink(message)]
+    fn holders(&self, offset: u32, limit: u32) -> Vec<AccountId>;
+
+    /// Returns the number of accounts holding a non-zero balance.
+    #[ink(message)]
+    fn holder_count(&self) -> u32;
+
+    /// Returns at most `limit` spenders holding a non-zero allowance from `owner`,
+    /// skipping the first `offset`.
+    ///
+    /// The order of accounts is not stable between transactions.
+    #[ink(message)]
+    fn approved_spenders(&self, owner: AccountId, offset: u32, limit: u32) -> Vec<AccountId>;
+}
+
+/// Trait for sending tokens to many recipients in a single transaction.
+#[ink::trait_definition]
+pub trait PSP22Batch {
+    /// Transfers the given amounts of tokens from the caller's account to every recipient
+    /// in `transfers`.
+    ///
+    /// The batch is atomic, either all of the transfers succeed or none of them.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event is emitted for every transfer. Transfers of zero tokens and
+    /// transfers to the caller are skipped and no events are emitted for them.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the total of `transfers` exceeds the caller's balance.
+    ///
+    /// Reverts with `Custom` error if the total of `transfers` exceeds u128.MAX.
+    #[ink(message)]
+    fn batch_transfer(&mut self, transfers: Vec<(AccountId, u128)>) -> Result<(), PSP22Error>;
+
+    /// Transfers the given amounts of tokens from `from` to every recipient in `transfers`,
+    /// using the allowance granted by `from` to the caller.
+    ///
+    /// The batch is atomic, either all of the transfers succeed or none of them.
+    ///
+    /// # Events
+    ///
+    /// A single `Approval` event followed by a `Transfer` event for every transfer.
+    /// Transfers of zero tokens and transfers to `from` are skipped and no events are emitted for them.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientAllowance` if the total of `transfers` exceeds the allowance
+    /// granted by `from` to the caller, including a total above u128.MAX.
+    ///
+    /// Reverts with `InsufficientBalance` if the total of `transfers` is within the allowance
+    /// but exceeds the balance of `from`.
+    #[ink(message)]
+    fn batch_transfer_from(
+        &mut self,
+        from: AccountId,
+        transfers: Vec<(AccountId, u128)>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Trait for upgrading the code of a deployed token.
+///
+/// The code is replaced with `set_code_hash`, the storage is kept. The storage layout
+/// version is stored next to the token data, so that the new code converts the storage
+/// of older versions once, on the first call changing the token, and refuses to operate on newer ones.
+#[ink::trait_definition]
+pub trait PSP22Upgradeable {
+    /// Replaces the code of the contract with the code uploaded under `code_hash`.
+    ///
+    /// The new code is used from the next call on. It migrates the storage on the first
+    /// transfer, mint, burn or approval, or when `migrate` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_hash` - The hash of the new code, which must be uploaded already.
+    /// * `target_version` - The storage version of the new code.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    /// Fails with `Custom (Downgrade refused)` if `target_version` is older than the storage
+    /// version of the current code, and with `Custom (Code hash not found)` if no code was
+    /// uploaded under `code_hash`.
+    #[ink(message)]
+    fn set_code_hash(&mut self, code_hash: Hash, target_version: u32) -> Result<(), PSP22Error>;
+
+    /// Returns the version of the layout of the stored data.
+    ///
+    /// # Returns
+    ///
+    /// The storage version, `1` for the layout of the first release.
+    #[ink(message)]
+    fn storage_version(&self) -> u32;
+
+    /// Converts the stored data to the layout of the current code after an upgrade,
+    /// before the first call changing the token would.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    /// Fails with `Custom (Already migrated)` if the storage already has the current layout
+    /// and with `Custom (Downgrade refused)` if it has the layout of a newer code.
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), PSP22Error>;
+}
+
+/// Trait for ownership-related functionalities.
+///
+/// Provides methods for managing ownership of the contract, including
+/// transferring and renouncing ownership.
+#[ink::trait_definition]
+pub trait Ownable {
+    /// Returns the address of the current owner.
+    ///
+    /// # Returns
+    ///
+    /// The `AccountId` of the current owner.
+    #[ink(message)]
+    fn owner(&self) -> Option<AccountId>;
+
+    /// Renounces ownership of the contract.
+    ///
+    /// This method is used to permanently transfer control of the contract
+    /// away from the current owner, leaving it without an owner.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), OwnableError>` indicating whether the operation was successful.
+    #[ink(message)]
+    fn renounce_ownership(&mut self) -> Result<(), OwnableError>;
+
+    /// Transfers ownership of the contract to a new account.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_owner` - The `AccountId` of the new owner.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), OwnableError>` indicating whether the operation was successful.
+    #[ink(message)]
+    fn transfer_ownership(&mut self, new_owner: Option<AccountId>) -> Result<(), OwnableError>;
+}
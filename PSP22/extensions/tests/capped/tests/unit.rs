@@ -1,6 +1,9 @@
 use psp22_capped_tests::token::*;
-use psp22_capped_tests::PSP22;
+use psp22_capped_tests::{PSP22, PSP22Error};
 use psp22_capped_tests::traits::PSP22Mintable;
+
+psp22_capped_tests::psp22_tests!(Token, |supply| Token::new(supply, u128::MAX));
+
 #[cfg(test)]
 mod tests {
     use ink::primitives::AccountId;
@@ -18,12 +21,15 @@ mod tests {
     }
 
     #[ink::test]
-    #[should_panic(expected = "Max cap exceeded")]
     fn mint_above_cap_limit_fails() {
         let sender = AccountId::from([0x2; 32]);
         set_caller::<DefaultEnvironment>(sender);
         let mut token = Token::new(1, 1);
         assert_eq!(token.total_supply(), 1);
-        token.mint(sender, 1).expect("Will fail here");
+        assert_eq!(
+            token.mint(sender, 1),
+            Err(PSP22Error::Custom(String::from("Max cap exceeded")))
+        );
+        assert_eq!(token.total_supply(), 1);
     }
 }
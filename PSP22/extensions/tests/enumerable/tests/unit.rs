@@ -0,0 +1,134 @@
+use psp22_enumerable_tests::token::*;
+use psp22_enumerable_tests::PSP22;
+use psp22_enumerable_tests::traits::{PSP22Burnable, PSP22Enumerable, PSP22Mintable};
+
+psp22_enumerable_tests::psp22_tests!(Token, Token::new);
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::set_caller};
+    use super::*;
+
+    fn sorted(mut accounts: Vec<AccountId>) -> Vec<AccountId> {
+        accounts.sort();
+        accounts
+    }
+
+    #[ink::test]
+    fn creator_is_the_only_holder() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let token = Token::new(10);
+        assert_eq!(token.holder_count(), 1);
+        assert_eq!(token.holders(0, 10), vec![owner]);
+    }
+
+    #[ink::test]
+    fn empty_supply_has_no_holders() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let token = Token::new(0);
+        assert_eq!(token.holder_count(), 0);
+        assert_eq!(token.balance_of(owner), 0);
+        assert!(token.holders(0, 10).is_empty());
+    }
+
+    #[ink::test]
+    fn transfers_keep_holders_in_sync() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(10);
+
+        token.transfer(alice, 4, Vec::new()).expect("Success expected");
+        token.transfer(bob, 6, Vec::new()).expect("Success expected");
+        assert_eq!(token.holder_count(), 2);
+        assert_eq!(sorted(token.holders(0, 10)), sorted(vec![alice, bob]));
+
+        set_caller::<DefaultEnvironment>(alice);
+        token.transfer(bob, 4, Vec::new()).expect("Success expected");
+        assert_eq!(token.holder_count(), 1);
+        assert_eq!(token.holders(0, 10), vec![bob]);
+    }
+
+    #[ink::test]
+    fn mint_and_burn_keep_holders_in_sync() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(0);
+
+        token.mint(alice, 5).expect("Success expected");
+        token.mint(owner, 5).expect("Success expected");
+        assert_eq!(sorted(token.holders(0, 10)), sorted(vec![owner, alice]));
+
+        token.burn(5).expect("Success expected");
+        assert_eq!(token.holders(0, 10), vec![alice]);
+    }
+
+    #[ink::test]
+    fn holders_are_paginated() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(0);
+        let accounts: Vec<AccountId> = (2..7).map(|i| AccountId::from([i; 32])).collect();
+        for account in &accounts {
+            token.mint(*account, 1).expect("Success expected");
+        }
+
+        assert_eq!(token.holder_count(), 5);
+        let mut pages = token.holders(0, 2);
+        assert_eq!(pages.len(), 2);
+        pages.extend(token.holders(2, 2));
+        pages.extend(token.holders(4, 2));
+        assert_eq!(sorted(pages), accounts);
+        assert!(token.holders(5, 2).is_empty());
+        assert!(token.holders(u32::MAX, u32::MAX).is_empty());
+    }
+
+    #[ink::test]
+    fn approvals_keep_spenders_in_sync() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(10);
+
+        token.approve(alice, 3).expect("Success expected");
+        token.increase_allowance(bob, 3).expect("Success expected");
+        assert_eq!(sorted(token.approved_spenders(owner, 0, 10)), sorted(vec![alice, bob]));
+
+        // Spending the whole allowance removes the spender
+        set_caller::<DefaultEnvironment>(alice);
+        token.transfer_from(owner, alice, 3, Vec::new()).expect("Success expected");
+        assert_eq!(token.approved_spenders(owner, 0, 10), vec![bob]);
+
+        set_caller::<DefaultEnvironment>(owner);
+        token.decrease_allowance(bob, 3).expect("Success expected");
+        assert!(token.approved_spenders(owner, 0, 10).is_empty());
+
+        token.approve(alice, 1).expect("Success expected");
+        token.approve(alice, 0).expect("Success expected");
+        assert!(token.approved_spenders(owner, 0, 10).is_empty());
+    }
+
+    #[ink::test]
+    fn spenders_are_tracked_per_owner() {
+        let owner = AccountId::from([0x1; 32]);
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(10);
+        token.approve(bob, 1).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(alice);
+        token.approve(bob, 1).expect("Success expected");
+        token.approve(owner, 1).expect("Success expected");
+
+        assert_eq!(token.approved_spenders(owner, 0, 10), vec![bob]);
+        assert_eq!(token.approved_spenders(alice, 0, 10), vec![bob, owner]);
+        assert_eq!(token.approved_spenders(alice, 1, 10), vec![owner]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Typed client for calling other PSP22 contracts.
+
+use ink::codegen::TraitCallBuilder;
+use ink::env::call::{Call, CallParams};
+use ink::env::{DefaultEnvironment, Error as EnvError};
+use ink::prelude::{format, vec::Vec};
+use ink::primitives::{AccountId, LangError, MessageResult};
+use scale::{Decode, Encode};
+
+use crate::traits::PSP22Mintable;
+use crate::{PSP22Error, PSP22};
+
+/// Performs the calls built by `PSP22Ref`.
+pub trait Invoke {
+    /// Calls the contract with `params`, returning the output of the called message.
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError>;
+}
+
+/// Calls the contracts deployed on chain.
+pub struct OnChain;
+
+impl Invoke for OnChain {
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError> {
+        params.try_invoke()
+    }
+}
+
+// The off-chain environment of ink! cannot call other contracts, so with the `mock` feature
+// the calls are answered by the in-memory tokens of `mock` instead
+#[cfg(not(feature = "mock"))]
+type Backend = OnChain;
+#[cfg(feature = "mock")]
+type Backend = crate::mock::Mock;
+
+/// Reference to a PSP22 token deployed at `account`.
+///
+/// Calls are built from the `PSP22` and `PSP22Mintable` trait definitions, so their selectors
+/// and argument types always match the traits. Failures of the call itself (the callee trapped,
+/// could not be found or did not understand the message) are returned as
+/// `PSP22Error::Custom` instead of trapping the caller.
+pub struct PSP22Ref {
+    token: ink::contract_ref!(PSP22, DefaultEnvironment),
+    mintable: ink::contract_ref!(PSP22Mintable, DefaultEnvironment),
+    gas_limit: u64,
+}
+
+impl PSP22Ref {
+    /// Creates a reference to the token at `account`, with calls allowed
+    /// to use all the remaining gas.
+    pub fn new(account: AccountId) -> PSP22Ref {
+        PSP22Ref {
+            token: account.into(),
+            mintable: account.into(),
+            gas_limit: 0,
+        }
+    }
+
+    /// Limits the gas each call may use. Zero means all the remaining gas.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> PSP22Ref {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Returns the gas each call may use, zero meaning all the remaining gas.
+    pub fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    /// Calls `PSP22::balance_of` of the token.
+    pub fn balance_of(&mut self, owner: AccountId) -> Result<u128, PSP22Error> {
+        let params = self
+            .token
+            .call_mut()
+            .balance_of(owner)
+            .gas_limit(self.gas_limit)
+            .params();
+        map_call_result(Backend::invoke(&params))
+    }
+
+    /// Calls `PSP22::transfer` of the token.
+    pub fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error> {
+        let params = self
+            .token
+            .call_mut()
+            .transfer(to, value, data)
+            .gas_limit(self.gas_limit)
+            .params();
+        map_call_result(Backend::invoke(&params))?
+    }
+
+    /// Calls `PSP22::transfer_from` of the token.
+    pub fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        let params = self
+            .token
+            .call_mut()
+            .transfer_from(from, to, value, data)
+            .gas_limit(self.gas_limit)
+            .params();
+        map_call_result(Backend::invoke(&params))?
+    }
+
+    /// Calls `PSP22Mintable::mint` of the token, which must implement it.
+    pub fn mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+        let params = self
+            .mintable
+            .call_mut()
+            .mint(to, value)
+            .gas_limit(self.gas_limit)
+            .params();
+        map_call_result(Backend::invoke(&params))?
+    }
+}
+
+/// Converts errors of a cross-contract call into `PSP22Error::Custom`, returning
+/// the decoded output of the called message otherwise.
+pub fn map_call_result<R>(result: Result<Result<R, LangError>, EnvError>) -> Result<R, PSP22Error> {
+    match result {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(error)) => Err(PSP22Error::Custom(format!("Call could not be dispatched: {:?}", error))),
+        Err(error) => Err(PSP22Error::Custom(format!("Call failed: {:?}", error))),
+    }
+}
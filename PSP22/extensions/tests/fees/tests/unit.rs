@@ -0,0 +1,265 @@
+use psp22_fees_tests::token::*;
+use psp22_fees_tests::PSP22;
+use psp22_fees_tests::traits::{PSP22Batch, PSP22Fees};
+use psp22_fees_tests::data::calculate_fee;
+
+psp22_fees_tests::psp22_tests!(Token, |supply| Token::new(supply, 0));
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{recorded_events, set_caller}};
+    use super::*;
+
+    // Decodes the event at `index` as a `Transfer`
+    fn decode_transfer(index: usize) -> (Option<AccountId>, Option<AccountId>, u128) {
+        let event = recorded_events().nth(index).expect("Event expected");
+        match psp22_fees_tests::events::decode_event(&event.topics[0], &event.data) {
+            Some(psp22_fees_tests::PSP22Event::Transfer { from, to, value }) => (from, to, value),
+            _ => panic!("Transfer event expected"),
+        }
+    }
+
+    // Deploys a token with `fee_bps` fee, `treasury` collecting fees and `holder` holding `supply`
+    fn setup(supply: u128, fee_bps: u16, treasury: AccountId, holder: AccountId) -> Token {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(supply, fee_bps);
+        // Transfers from the initial treasury (the deployer) are free of charge
+        token.transfer(holder, supply, Vec::new()).expect("Success expected");
+        token.set_treasury(treasury).expect("Success expected");
+        token
+    }
+
+    #[test]
+    fn calculate_fee_edge_cases_work() {
+        assert_eq!(calculate_fee(0, 10_000), 0);
+        assert_eq!(calculate_fee(1, 0), 0);
+        assert_eq!(calculate_fee(1, 9_999), 0);
+        assert_eq!(calculate_fee(1, 10_000), 1);
+        assert_eq!(calculate_fee(10_000, 1), 1);
+        assert_eq!(calculate_fee(19_999, 1), 1);
+        assert_eq!(calculate_fee(u128::MAX, 0), 0);
+        assert_eq!(calculate_fee(u128::MAX, 10_000), u128::MAX);
+        assert_eq!(calculate_fee(u128::MAX, u16::MAX), u128::MAX);
+        assert_eq!(calculate_fee(u128::MAX, 5_000), u128::MAX / 2);
+    }
+
+    #[test]
+    fn calculate_fee_properties_hold() {
+        // xorshift generator, so that the test is deterministic
+        let mut state: u128 = 0x2545_f491_4f6c_dd1d_9e37_79b9_7f4a_7c15;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for round in 0..10_000 {
+            // Every other round uses a small value to cover rounding of the remainder
+            let value = if round % 2 == 0 { next() } else { next() % 1_000_000 };
+            let other = next() >> (next() % 128);
+            let fee_bps = (next() % 10_001) as u16;
+            let fee = calculate_fee(value, fee_bps);
+
+            // The fee never exceeds the transferred value
+            assert!(fee <= value);
+            // The fee is monotonic in the rate
+            assert!(calculate_fee(value, fee_bps.saturating_sub(1)) <= fee);
+            // The fee is rounded down
+            if value <= u128::MAX / 10_000 {
+                assert_eq!(fee, value * u128::from(fee_bps) / 10_000);
+            }
+            // Splitting a transfer in two never pays more than a single transfer,
+            // and saves at most one token due to rounding
+            if let Some(total) = value.checked_add(other) {
+                let split = fee + calculate_fee(other, fee_bps);
+                let single = calculate_fee(total, fee_bps);
+                assert!(split <= single);
+                assert!(single - split <= 1);
+            }
+        }
+    }
+
+    #[ink::test]
+    fn transfer_charges_fee_to_treasury() {
+        let treasury = AccountId::from([0x9; 32]);
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        let mut token = setup(10_000, 250, treasury, sender);
+        let start = recorded_events().count();
+
+        set_caller::<DefaultEnvironment>(sender);
+        token.transfer(recipient, 1_000, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(sender), 9_000);
+        assert_eq!(token.balance_of(recipient), 975);
+        assert_eq!(token.balance_of(treasury), 25);
+        assert_eq!(token.total_supply(), 10_000);
+
+        assert_eq!(recorded_events().count(), start + 2);
+        assert_eq!(decode_transfer(start), (Some(sender), Some(recipient), 975));
+        assert_eq!(decode_transfer(start + 1), (Some(sender), Some(treasury), 25));
+    }
+
+    #[ink::test]
+    fn transfer_from_charges_fee_to_treasury() {
+        let treasury = AccountId::from([0x9; 32]);
+        let holder = AccountId::from([0x2; 32]);
+        let spender = AccountId::from([0x3; 32]);
+        let recipient = AccountId::from([0x4; 32]);
+        let mut token = setup(10_000, 100, treasury, holder);
+
+        set_caller::<DefaultEnvironment>(holder);
+        token.approve(spender, 2_000).expect("Success expected");
+        let start = recorded_events().count();
+        set_caller::<DefaultEnvironment>(spender);
+        token.transfer_from(holder, recipient, 1_500, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(holder), 8_500);
+        assert_eq!(token.balance_of(recipient), 1_485);
+        assert_eq!(token.balance_of(treasury), 15);
+        // The whole value is deducted from the allowance
+        assert_eq!(token.allowance(holder, spender), 500);
+
+        // Approval followed by two transfers
+        assert_eq!(recorded_events().count(), start + 3);
+        assert_eq!(decode_transfer(start + 1), (Some(holder), Some(recipient), 1_485));
+        assert_eq!(decode_transfer(start + 2), (Some(holder), Some(treasury), 15));
+    }
+
+    #[ink::test]
+    fn transfer_from_with_insufficient_allowance_fails() {
+        let treasury = AccountId::from([0x9; 32]);
+        let holder = AccountId::from([0x2; 32]);
+        let spender = AccountId::from([0x3; 32]);
+        let recipient = AccountId::from([0x4; 32]);
+        let mut token = setup(10_000, 100, treasury, holder);
+
+        set_caller::<DefaultEnvironment>(holder);
+        token.approve(spender, 1_000).expect("Success expected");
+        set_caller::<DefaultEnvironment>(spender);
+        assert!(token.transfer_from(holder, recipient, 1_001, Vec::new()).is_err());
+        assert_eq!(token.balance_of(holder), 10_000);
+        assert_eq!(token.balance_of(treasury), 0);
+    }
+
+    #[ink::test]
+    fn transfer_of_dust_is_free_of_charge() {
+        let treasury = AccountId::from([0x9; 32]);
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        let mut token = setup(1_000, 100, treasury, sender);
+        let start = recorded_events().count();
+
+        // 1% of 99 tokens rounds down to zero
+        set_caller::<DefaultEnvironment>(sender);
+        token.transfer(recipient, 99, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(recipient), 99);
+        assert_eq!(token.balance_of(treasury), 0);
+        assert_eq!(recorded_events().count(), start + 1);
+    }
+
+    #[ink::test]
+    fn exempt_accounts_are_not_charged() {
+        let owner = AccountId::from([0x1; 32]);
+        let treasury = AccountId::from([0x9; 32]);
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        let mut token = setup(2_000, 1_000, treasury, sender);
+
+        set_caller::<DefaultEnvironment>(owner);
+        token.set_fee_exempt(recipient, true).expect("Success expected");
+        assert!(token.is_fee_exempt(recipient));
+        set_caller::<DefaultEnvironment>(sender);
+        token.transfer(recipient, 1_000, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(recipient), 1_000);
+        assert_eq!(token.balance_of(treasury), 0);
+
+        set_caller::<DefaultEnvironment>(owner);
+        token.set_fee_exempt(recipient, false).expect("Success expected");
+        assert!(!token.is_fee_exempt(recipient));
+        set_caller::<DefaultEnvironment>(sender);
+        token.transfer(recipient, 1_000, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(recipient), 1_900);
+        assert_eq!(token.balance_of(treasury), 100);
+    }
+
+    #[ink::test]
+    fn full_fee_goes_to_treasury() {
+        let owner = AccountId::from([0x1; 32]);
+        let treasury = AccountId::from([0x9; 32]);
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x3; 32]);
+        let mut token = setup(u128::MAX, 0, treasury, sender);
+
+        set_caller::<DefaultEnvironment>(owner);
+        token.set_fee(10_000).expect("Success expected");
+        assert_eq!(token.fee_bps(), 10_000);
+        set_caller::<DefaultEnvironment>(sender);
+        token.transfer(recipient, u128::MAX, Vec::new()).expect("Success expected");
+        assert_eq!(token.balance_of(sender), 0);
+        assert_eq!(token.balance_of(recipient), 0);
+        assert_eq!(token.balance_of(treasury), u128::MAX);
+    }
+
+    #[ink::test]
+    fn batch_transfer_charges_fee_on_every_transfer() {
+        let owner = AccountId::from([0x1; 32]);
+        let treasury = AccountId::from([0x9; 32]);
+        let sender = AccountId::from([0x2; 32]);
+        let alice = AccountId::from([0x3; 32]);
+        let bob = AccountId::from([0x4; 32]);
+        let mut token = setup(10_000, 100, treasury, sender);
+        set_caller::<DefaultEnvironment>(owner);
+        token.set_fee_exempt(bob, true).expect("Success expected");
+        let start = recorded_events().count();
+
+        set_caller::<DefaultEnvironment>(sender);
+        token.batch_transfer(vec![(alice, 1_000), (bob, 500), (alice, 200)]).expect("Success expected");
+        assert_eq!(token.balance_of(sender), 8_300);
+        assert_eq!(token.balance_of(alice), 990 + 198);
+        assert_eq!(token.balance_of(bob), 500);
+        assert_eq!(token.balance_of(treasury), 12);
+
+        assert_eq!(recorded_events().count(), start + 5);
+        assert_eq!(decode_transfer(start), (Some(sender), Some(alice), 990));
+        assert_eq!(decode_transfer(start + 1), (Some(sender), Some(treasury), 10));
+        assert_eq!(decode_transfer(start + 2), (Some(sender), Some(bob), 500));
+    }
+
+    #[ink::test]
+    fn batch_transfer_from_charges_fee_and_deducts_whole_value() {
+        let treasury = AccountId::from([0x9; 32]);
+        let holder = AccountId::from([0x2; 32]);
+        let spender = AccountId::from([0x3; 32]);
+        let recipient = AccountId::from([0x4; 32]);
+        let mut token = setup(10_000, 100, treasury, holder);
+
+        set_caller::<DefaultEnvironment>(holder);
+        token.approve(spender, 3_000).expect("Success expected");
+        set_caller::<DefaultEnvironment>(spender);
+        token.batch_transfer_from(holder, vec![(recipient, 1_000), (recipient, 1_500)]).expect("Success expected");
+        assert_eq!(token.balance_of(holder), 7_500);
+        assert_eq!(token.balance_of(recipient), 990 + 1_485);
+        assert_eq!(token.balance_of(treasury), 25);
+        assert_eq!(token.allowance(holder, spender), 500);
+    }
+
+    #[ink::test]
+    fn set_fee_above_limit_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(1, 0);
+        assert!(token.set_fee(10_001).is_err());
+        assert_eq!(token.fee_bps(), 0);
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can set fee")]
+    fn set_fee_by_non_owner_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new(1, 0);
+        set_caller::<DefaultEnvironment>(AccountId::from([0x2; 32]));
+        token.set_fee(1).expect("Will fail");
+    }
+}
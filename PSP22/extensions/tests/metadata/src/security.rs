@@ -0,0 +1,67 @@
+//! Building blocks for securing contracts which make external calls.
+
+use ink::storage::{traits::ManualKey, Lazy};
+
+use crate::errors::ReentrancyError;
+
+/// Storage key of the reentrancy lock.
+///
+/// The key is fixed, so that there is a single lock per contract, no matter
+/// how many guards are declared or where the guard is placed in the storage.
+pub const REENTRANCY_GUARD_KEY: u32 = 0x5245_4e54;
+
+type LockCell = Lazy<bool, ManualKey<REENTRANCY_GUARD_KEY>>;
+
+/// Protects messages of a contract from being re-entered through external calls.
+///
+/// The lock is written to the contract storage immediately, instead of at the end
+/// of the message like the rest of the contract state, so a nested call of the contract
+/// made by a callee sees it.
+///
+/// ```ignore
+/// fn withdraw_to(&mut self, account: AccountId, amount: u128) -> Result<(), PSP22Error> {
+///     let _lock = self.reentrancy_guard.enter()?;
+///     // External calls made here cannot re-enter guarded messages
+/// }
+/// ```
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct ReentrancyGuard {
+    locked: LockCell,
+}
+
+impl ReentrancyGuard {
+    /// Returns `true` if a guarded message is being executed.
+    pub fn is_locked(&self) -> bool {
+        self.locked.get().unwrap_or_default()
+    }
+
+    /// Locks the guard until the returned `ReentrancyLock` is dropped,
+    /// which normally happens at the end of the guarded message.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReentrantCall` if the guard is already locked.
+    pub fn enter(&mut self) -> Result<ReentrancyLock, ReentrancyError> {
+        if self.is_locked() {
+            return Err(ReentrancyError::ReentrantCall);
+        }
+        self.locked.set(&true);
+        Ok(ReentrancyLock { _private: () })
+    }
+}
+
+/// Unlocks the `ReentrancyGuard` when dropped.
+///
+/// If the guarded message fails, the lock is reverted together with the rest of the state.
+#[must_use = "the guard is unlocked as soon as the lock is dropped"]
+#[derive(Debug)]
+pub struct ReentrancyLock {
+    _private: (),
+}
+
+impl Drop for ReentrancyLock {
+    fn drop(&mut self) {
+        LockCell::new().set(&false);
+    }
+}
@@ -0,0 +1,101 @@
+use psp22_metadata_tests::token::*;
+use psp22_metadata_tests::PSP22Error;
+use psp22_metadata_tests::traits::{PSP22Metadata, PSP22MetadataManageable};
+
+psp22_metadata_tests::psp22_tests!(Token, |supply| Token::new(
+    supply,
+    String::from("Beaver"),
+    String::from("BVR"),
+    18
+));
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{recorded_events, set_caller}};
+    use ink::reflect::ContractEventBase;
+    use scale::Decode;
+    use super::*;
+
+    type Event = <Token as ContractEventBase>::Type;
+
+    fn token() -> Token {
+        Token::new(100, String::from("Beaver"), String::from("BVR"), 18)
+    }
+
+    fn locked() -> PSP22Error {
+        PSP22Error::Custom(String::from("Metadata is locked"))
+    }
+
+    #[ink::test]
+    fn constructor_sets_metadata() {
+        let token = token();
+        assert_eq!(token.token_name(), Some(String::from("Beaver")));
+        assert_eq!(token.token_symbol(), Some(String::from("BVR")));
+        assert_eq!(token.token_decimals(), 18);
+        assert_eq!(token.token_icon_uri(), None);
+        assert_eq!(token.token_description(), None);
+        assert!(!token.metadata_locked());
+    }
+
+    #[ink::test]
+    fn set_metadata_works() {
+        let mut token = token();
+        let start = recorded_events().count();
+        token
+            .set_metadata(
+                Some(String::from("Smart Beaver")),
+                None,
+                Some(String::from("ipfs://icon")),
+                Some(String::from("A token")),
+            )
+            .expect("Success expected");
+        assert_eq!(token.token_name(), Some(String::from("Smart Beaver")));
+        assert_eq!(token.token_symbol(), None);
+        assert_eq!(token.token_icon_uri(), Some(String::from("ipfs://icon")));
+        assert_eq!(token.token_description(), Some(String::from("A token")));
+
+        let events: Vec<_> = recorded_events().skip(start).collect();
+        assert_eq!(events.len(), 1);
+        // Events of extensions keep the signature topic ink! derives from the contract name
+        let mut signature = [0u8; 32];
+        signature[1..23].copy_from_slice(b"Token::MetadataUpdated");
+        assert_eq!(events[0].topics[0], signature);
+        let Ok(Event::MetadataUpdated(event)) = Event::decode(&mut &events[0].data[..]) else {
+            panic!("MetadataUpdated event expected");
+        };
+        assert_eq!(event.name, Some(String::from("Smart Beaver")));
+        assert_eq!(event.symbol, None);
+        assert_eq!(event.decimals, 18);
+        assert_eq!(event.icon_uri, Some(String::from("ipfs://icon")));
+        assert_eq!(event.description, Some(String::from("A token")));
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can set metadata")]
+    fn set_metadata_by_non_owner_fails() {
+        let mut token = token();
+        set_caller::<DefaultEnvironment>(AccountId::from([0x3; 32]));
+        let _ = token.set_metadata(None, None, None, None);
+    }
+
+    #[ink::test]
+    fn locked_metadata_cannot_be_changed() {
+        let mut token = token();
+        token.lock_metadata().expect("Success expected");
+        assert!(token.metadata_locked());
+        let start = recorded_events().count();
+        assert_eq!(token.set_metadata(None, None, None, None), Err(locked()));
+        assert_eq!(token.lock_metadata(), Err(locked()));
+        assert_eq!(token.token_name(), Some(String::from("Beaver")));
+        assert_eq!(recorded_events().count(), start);
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can lock metadata")]
+    fn lock_metadata_by_non_owner_fails() {
+        let mut token = token();
+        set_caller::<DefaultEnvironment>(AccountId::from([0x3; 32]));
+        let _ = token.lock_metadata();
+    }
+}
@@ -1,6 +1,9 @@
 use psp22_mintable_tests::token::*;
 use psp22_mintable_tests::PSP22;
 use psp22_mintable_tests::traits::PSP22Mintable;
+
+psp22_mintable_tests::psp22_tests!(Token, Token::new);
+
 #[cfg(test)]
 mod tests {
     use ink::primitives::AccountId;
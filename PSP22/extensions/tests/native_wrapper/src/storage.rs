@@ -0,0 +1,320 @@
+//! Storage backends of the data layer.
+//!
+//! `PSP22Data` keeps its balances and allowances in [`Map`]s, which delegate to the map type
+//! of a [`StorageBackend`]:
+//!
+//! * [`OnChain`], the default, stores entries in the contract storage with ink!'s `Mapping`,
+//! * [`InMemory`] (with the `std` feature) keeps them in a `BTreeMap`, so the same code of
+//!   `data.rs` runs in plain Rust simulations, backtests and property tests, without the
+//!   off-chain environment of ink!.
+//!
+//! A backend also tells the data layer the current time, against which allowance deadlines
+//! are checked: the timestamp of the block for `OnChain`, a clock set with
+//! [`InMemory::set_now`] for `InMemory`.
+//!
+//! As in the contract storage, entries are identified by the SCALE encoding of their key,
+//! so a `Map` accepts every key type which encodes like its own (e.g. `&AccountId`).
+
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ink::primitives::Key;
+use ink::storage::traits::{AutoKey, Packed, Storable, StorableHint, StorageKey};
+use ink::storage::Mapping;
+use scale::{Encode, EncodeLike, Error, Input, Output};
+
+/// Operations of a key-value map used by the data layer.
+pub trait StorageMap<K: Encode, V> {
+    /// Returns the value stored under `key`, if any.
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V>;
+
+    /// Stores `value` under `key`, replacing the previous one.
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V);
+
+    /// Removes the value stored under `key`, if any.
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q);
+
+    /// Returns `true` if a value is stored under `key`.
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool;
+}
+
+/// A family of maps in which the data layer keeps its state.
+///
+/// `KeyType` is the storage key assigned by `#[ink::storage_item]` to the field
+/// holding the map, backends which do not use the contract storage ignore it.
+pub trait StorageBackend: Debug + Default {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey>: StorageMap<K, V> + Default;
+
+    /// Returns the current timestamp, in milliseconds.
+    fn now() -> u64;
+}
+
+/// Backend storing the entries in the contract storage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct OnChain;
+
+impl StorageBackend for OnChain {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey> = Mapping<K, V, KeyType>;
+
+    fn now() -> u64 {
+        ink::env::block_timestamp::<ink::env::DefaultEnvironment>()
+    }
+}
+
+impl<K, V, KeyType> StorageMap<K, V> for Mapping<K, V, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike,
+    KeyType: StorageKey,
+{
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        Mapping::get(self, key)
+    }
+
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        Mapping::insert(self, key, value);
+    }
+
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        Mapping::remove(self, key)
+    }
+
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        Mapping::contains(self, key)
+    }
+}
+
+/// Backend keeping the entries in memory, for running the data layer outside of a contract.
+///
+/// Nothing is written to the contract storage: a `PSP22Data<InMemory>` used as a
+/// contract field would lose its entries between calls.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale_info::TypeInfo)]
+pub struct InMemory;
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static IN_MEMORY_NOW: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+}
+
+#[cfg(feature = "std")]
+impl InMemory {
+    /// Sets the timestamp returned by [`StorageBackend::now`] on the current thread, zero
+    /// until set.
+    pub fn set_now(timestamp: u64) {
+        IN_MEMORY_NOW.with(|now| now.set(timestamp));
+    }
+}
+
+#[cfg(feature = "std")]
+impl StorageBackend for InMemory {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey> = std::collections::BTreeMap<std::vec::Vec<u8>, V>;
+
+    fn now() -> u64 {
+        IN_MEMORY_NOW.with(|now| now.get())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Encode, V: Clone> StorageMap<K, V> for std::collections::BTreeMap<std::vec::Vec<u8>, V> {
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        self.get(&key.encode()).cloned()
+    }
+
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        self.insert(key.encode(), value.clone());
+    }
+
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        self.remove(&key.encode());
+    }
+
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        self.contains_key(&key.encode())
+    }
+}
+
+/// A map from `K` to `V` kept by the backend `B`, used like ink!'s `Mapping`.
+///
+/// With the `OnChain` backend it is stored exactly like a `Mapping` declared in its place,
+/// so switching a storage item from `Mapping` to `Map` keeps its storage layout.
+pub struct Map<K, V, B = OnChain, KeyType = AutoKey>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    entries: B::Map<K, V, KeyType>,
+}
+
+impl<K, V, B, KeyType> Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    /// Returns the value stored under `key`, if any.
+    pub fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        self.entries.get(key)
+    }
+
+    /// Stores `value` under `key`, replacing the previous one.
+    pub fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        self.entries.insert(key, value)
+    }
+
+    /// Removes the value stored under `key`, if any.
+    pub fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        self.entries.remove(key)
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    pub fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        self.entries.contains(key)
+    }
+}
+
+impl<K, V, B, KeyType> Default for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn default() -> Self {
+        Map {
+            entries: Default::default(),
+        }
+    }
+}
+
+impl<K, V, B, KeyType> Debug for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Map").field("key", &KeyType::KEY).finish()
+    }
+}
+
+// The storage traits below mirror the ones of `Mapping`: the map is not part of the
+// encoded storage item, its entries live under their own keys.
+
+impl<K, V, B, KeyType> Storable for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn encode<T: Output + ?Sized>(&self, _dest: &mut T) {}
+
+    fn decode<I: Input>(_input: &mut I) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+}
+
+impl<K, V, B, Key, InnerKey> StorableHint<Key> for Map<K, V, B, InnerKey>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    Key: StorageKey,
+    InnerKey: StorageKey,
+{
+    type Type = Map<K, V, B, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<K, V, B, KeyType> StorageKey for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use ink::metadata::layout::{Layout, LayoutKey, RootLayout};
+    use ink::storage::traits::StorageLayout;
+    use scale_info::{Type, TypeInfo};
+
+    impl<K, V, B, KeyType> TypeInfo for Map<K, V, B, KeyType>
+    where
+        K: Encode + TypeInfo + 'static,
+        V: Packed + EncodeLike + Clone + TypeInfo + 'static,
+        B: StorageBackend,
+        KeyType: StorageKey + TypeInfo + 'static,
+    {
+        type Identity = <Mapping<K, V, KeyType> as TypeInfo>::Identity;
+
+        fn type_info() -> Type {
+            <Mapping<K, V, KeyType> as TypeInfo>::type_info()
+        }
+    }
+
+    impl<K, V, B, KeyType> StorageLayout for Map<K, V, B, KeyType>
+    where
+        K: Encode + scale_info::TypeInfo + 'static,
+        V: Packed + EncodeLike + Clone + StorageLayout + scale_info::TypeInfo + 'static,
+        B: StorageBackend,
+        KeyType: StorageKey + scale_info::TypeInfo + 'static,
+    {
+        fn layout(_: &Key) -> Layout {
+            Layout::Root(RootLayout::new(
+                LayoutKey::from(&KeyType::KEY),
+                <V as StorageLayout>::layout(&KeyType::KEY),
+            ))
+        }
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use ink::env::DefaultEnvironment;
+    use ink::primitives::AccountId;
+    use ink::storage::traits::ManualKey;
+
+    use super::*;
+
+    #[test]
+    fn on_chain_entries_are_stored_like_mapping() {
+        ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+            let mut map: Map<AccountId, u128, OnChain, ManualKey<42>> = Map::default();
+            let mapping: Mapping<AccountId, u128, ManualKey<42>> = Mapping::default();
+            let account = AccountId::from([1; 32]);
+            map.insert(account, &7);
+            assert_eq!(mapping.get(account), Some(7));
+            map.remove(account);
+            assert!(!mapping.contains(account));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn in_memory_entries_are_keyed_by_encoding() {
+        let mut map: Map<(AccountId, AccountId), u128, InMemory> = Map::default();
+        let (owner, spender) = (AccountId::from([1; 32]), AccountId::from([2; 32]));
+        map.insert((owner, spender), &7);
+        assert_eq!(map.get((&owner, &spender)), Some(7));
+        assert!(!map.contains((spender, owner)));
+        map.remove((owner, spender));
+        assert_eq!(map.get((owner, spender)), None);
+    }
+
+    #[test]
+    fn in_memory_clock_is_set_explicitly() {
+        assert_eq!(InMemory::now(), 0);
+        InMemory::set_now(1_000);
+        assert_eq!(InMemory::now(), 1_000);
+    }
+}
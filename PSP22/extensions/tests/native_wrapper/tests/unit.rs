@@ -0,0 +1,140 @@
+use ink::primitives::AccountId;
+use ink::env::{DefaultEnvironment, test::{
+    callee, get_account_balance, recorded_events, set_account_balance, set_caller,
+    set_value_transferred,
+}};
+use psp22_native_wrapper_tests::token::*;
+use psp22_native_wrapper_tests::{PSP22, PSP22Error};
+use psp22_native_wrapper_tests::traits::PSP22NativeWrapper;
+
+// The constructor rejects an initial supply, so the supply of the common tests is deposited by the caller
+psp22_native_wrapper_tests::psp22_tests!(Token, |supply| {
+    let mut token = Token::new(0);
+    deposit(&mut token, ink::env::caller::<DefaultEnvironment>(), supply).expect("Success expected");
+    token
+});
+
+// Calls `deposit` as `account` with `value` of native currency
+// (the off-chain environment does not move the transferred value itself)
+fn deposit(token: &mut Token, account: AccountId, value: u128) -> Result<(), PSP22Error> {
+    let contract = callee::<DefaultEnvironment>();
+    let balance = get_account_balance::<DefaultEnvironment>(contract).unwrap_or_default();
+    set_account_balance::<DefaultEnvironment>(contract, balance + value);
+    set_caller::<DefaultEnvironment>(account);
+    set_value_transferred::<DefaultEnvironment>(value);
+    let result = token.deposit();
+    set_value_transferred::<DefaultEnvironment>(0);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Decodes the event at `index` as a `Transfer`
+    fn decode_transfer(index: usize) -> (Option<AccountId>, Option<AccountId>, u128) {
+        let event = recorded_events().nth(index).expect("Event expected");
+        match psp22_native_wrapper_tests::events::decode_event(&event.topics[0], &event.data) {
+            Some(psp22_native_wrapper_tests::PSP22Event::Transfer { from, to, value }) => (from, to, value),
+            _ => panic!("Transfer event expected"),
+        }
+    }
+
+    // Deploys a token without supply, held by a contract without native balance
+    fn setup() -> Token {
+        set_account_balance::<DefaultEnvironment>(callee::<DefaultEnvironment>(), 0);
+        Token::new(0)
+    }
+
+    fn native_balance(account: AccountId) -> u128 {
+        get_account_balance::<DefaultEnvironment>(account).unwrap_or_default()
+    }
+
+    #[ink::test]
+    fn deposit_mints_transferred_value() {
+        let alice = AccountId::from([0x2; 32]);
+        let mut token = setup();
+        let start = recorded_events().count();
+
+        deposit(&mut token, alice, 100).expect("Success expected");
+        assert_eq!(token.balance_of(alice), 100);
+        assert_eq!(token.total_supply(), 100);
+        assert!(token.is_fully_backed());
+        assert_eq!(recorded_events().count(), start + 1);
+        assert_eq!(decode_transfer(start), (None, Some(alice), 100));
+    }
+
+    #[ink::test]
+    fn deposit_without_value_is_noop() {
+        let alice = AccountId::from([0x2; 32]);
+        let mut token = setup();
+        let start = recorded_events().count();
+
+        deposit(&mut token, alice, 0).expect("Success expected");
+        assert_eq!(token.total_supply(), 0);
+        assert_eq!(recorded_events().count(), start);
+    }
+
+    #[ink::test]
+    fn withdraw_burns_and_returns_native_currency() {
+        let alice = AccountId::from([0x2; 32]);
+        let mut token = setup();
+        deposit(&mut token, alice, 100).expect("Success expected");
+        set_account_balance::<DefaultEnvironment>(alice, 0);
+        let start = recorded_events().count();
+
+        token.withdraw(40).expect("Success expected");
+        assert_eq!(token.balance_of(alice), 60);
+        assert_eq!(token.total_supply(), 60);
+        assert_eq!(native_balance(alice), 40);
+        assert_eq!(native_balance(callee::<DefaultEnvironment>()), 60);
+        assert!(token.is_fully_backed());
+        assert_eq!(decode_transfer(start), (Some(alice), None, 40));
+    }
+
+    #[ink::test]
+    fn withdraw_more_than_balance_fails() {
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        let mut token = setup();
+        deposit(&mut token, alice, 100).expect("Success expected");
+        deposit(&mut token, bob, 10).expect("Success expected");
+
+        assert_eq!(token.withdraw(11), Err(PSP22Error::InsufficientBalance));
+        assert_eq!(token.total_supply(), 110);
+        assert_eq!(native_balance(callee::<DefaultEnvironment>()), 110);
+    }
+
+    #[ink::test]
+    fn transferred_tokens_can_be_withdrawn() {
+        let alice = AccountId::from([0x2; 32]);
+        let bob = AccountId::from([0x3; 32]);
+        let mut token = setup();
+        deposit(&mut token, alice, 100).expect("Success expected");
+        token.transfer(bob, 30, Vec::new()).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(bob);
+        token.withdraw(30).expect("Success expected");
+        assert_eq!(token.total_supply(), 70);
+        assert_eq!(native_balance(callee::<DefaultEnvironment>()), 70);
+    }
+
+    #[ink::test]
+    fn supply_not_covered_by_native_balance_is_reported() {
+        let alice = AccountId::from([0x2; 32]);
+        let mut token = setup();
+        deposit(&mut token, alice, 100).expect("Success expected");
+        assert!(token.is_fully_backed());
+
+        set_account_balance::<DefaultEnvironment>(callee::<DefaultEnvironment>(), 99);
+        assert!(!token.is_fully_backed());
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Initial supply must be zero")]
+    fn constructor_rejects_initial_supply() {
+        let alice = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(alice);
+        Token::new(1_000);
+    }
+}
@@ -1,13 +1,20 @@
 use psp22_pausable_tests::token::*;
-use psp22_pausable_tests::PSP22;
-use psp22_pausable_tests::traits::PSP22Mintable;
-use psp22_pausable_tests::traits::PSP22Pausable;
+use psp22_pausable_tests::{PSP22, PSP22Error};
+use psp22_pausable_tests::traits::{PSP22Burnable, PSP22Mintable};
+use psp22_pausable_tests::traits::{PSP22Batch, PSP22Pausable};
+
+psp22_pausable_tests::psp22_tests!(Token, |supply| Token::new(supply, false));
 
 #[cfg(test)]
 mod tests {
     use ink::primitives::AccountId;
     use ink::env::{DefaultEnvironment, test::set_caller};
     use super::*;
+
+    fn paused() -> PSP22Error {
+        PSP22Error::Custom(String::from("Token is paused"))
+    }
+
     #[ink::test]
     fn mint_unpaused_works() {
         let sender = AccountId::from([0x2; 32]);
@@ -20,14 +27,14 @@ mod tests {
     }
 
     #[ink::test]
-    #[should_panic(expected = "Token is paused")]
     fn mint_paused_fails() {
         let sender = AccountId::from([0x2; 32]);
         set_caller::<DefaultEnvironment>(sender);
         let mut token = Token::new(1, false);
         token.pause().expect("Should not fail");
         assert_eq!(token.total_supply(), 1);
-        token.mint(sender, 1).expect("ok");
+        assert_eq!(token.mint(sender, 1), Err(paused()));
+        assert_eq!(token.total_supply(), 1);
     }
 
     #[ink::test]
@@ -42,14 +49,35 @@ mod tests {
     }
 
     #[ink::test]
-    #[should_panic(expected = "Token is paused")]
     fn transfer_paused_fails() {
         let sender = AccountId::from([0x2; 32]);
         let recipient = AccountId::from([0x1; 32]);
         set_caller::<DefaultEnvironment>(sender);
         let mut token = Token::new(1, true);
         assert_eq!(token.balance_of(recipient), 0);
-        token.transfer(recipient, 1, Vec::from([])).expect("Will fail");
+        assert_eq!(token.transfer(recipient, 1, Vec::from([])), Err(paused()));
+        assert_eq!(token.balance_of(recipient), 0);
+    }
+
+    #[ink::test]
+    fn batch_transfer_paused_fails() {
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(sender);
+        let mut token = Token::new(1, true);
+        assert_eq!(token.batch_transfer(vec![(sender, 1), (recipient, 1)]), Err(paused()));
+        assert_eq!(token.balance_of(recipient), 0);
+    }
+
+    #[ink::test]
+    fn batch_of_no_op_transfers_paused_works() {
+        let sender = AccountId::from([0x2; 32]);
+        let recipient = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(sender);
+        let mut token = Token::new(1, true);
+        // Nothing is moved, so the hooks are not called
+        token.batch_transfer(vec![(sender, 1), (recipient, 0)]).expect("Success expected");
+        assert_eq!(token.balance_of(sender), 1);
     }
 
     #[ink::test]
@@ -67,7 +95,6 @@ mod tests {
     }
 
     #[ink::test]
-    #[should_panic(expected = "Token is paused")]
     fn transfer_from_paused_fails() {
         let owner = AccountId::from([0x1; 32]);
         set_caller::<DefaultEnvironment>(owner);
@@ -76,6 +103,16 @@ mod tests {
         token.approve(sender, 3).expect("Success expected");
         let recipient = AccountId::from([0x3; 32]);
         set_caller::<DefaultEnvironment>(sender);
-        token.transfer_from(owner, recipient, 1, Vec::from([])).expect("Will fail");
+        assert_eq!(token.transfer_from(owner, recipient, 1, Vec::from([])), Err(paused()));
+        assert_eq!(token.allowance(owner, sender), 3);
+    }
+
+    #[ink::test]
+    fn burn_paused_fails() {
+        let sender = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(sender);
+        let mut token = Token::new(1, true);
+        assert_eq!(token.burn(1), Err(paused()));
+        assert_eq!(token.total_supply(), 1);
     }
 }
@@ -0,0 +1,123 @@
+use psp22_reentrancy_guard_tests::token::*;
+use psp22_reentrancy_guard_tests::PSP22;
+use psp22_reentrancy_guard_tests::traits::PSP22Wrapper;
+
+// The constructor rejects an initial supply and the off-chain environment cannot call an underlying
+// token, so the supply of the common tests is minted to the caller as a deposit would
+psp22_reentrancy_guard_tests::psp22_tests!(Token, |supply| {
+    let mut token = Token::new(0, None, 0, 0);
+    token.data.mint(ink::env::caller::<ink::env::DefaultEnvironment>(), supply).expect("Success expected");
+    token
+});
+
+#[cfg(test)]
+mod tests {
+    use ink::codegen::Env;
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::set_caller};
+    use psp22_reentrancy_guard_tests::errors::ReentrancyError;
+    use psp22_reentrancy_guard_tests::security::ReentrancyGuard;
+    use psp22_reentrancy_guard_tests::PSP22Error;
+    use super::*;
+
+    /// A contract receiving control flow during an external call made by the token.
+    trait Receiver {
+        fn on_call(&mut self, token: &mut Token) -> Result<(), PSP22Error>;
+    }
+
+    /// Tries to withdraw the same tokens again before the first withdrawal is finished.
+    struct MaliciousReceiver {
+        attacker: AccountId,
+    }
+
+    impl Receiver for MaliciousReceiver {
+        fn on_call(&mut self, token: &mut Token) -> Result<(), PSP22Error> {
+            set_caller::<DefaultEnvironment>(self.attacker);
+            token.withdraw_to(self.attacker, 5)
+        }
+    }
+
+    /// Only reads the state of the token.
+    struct HonestReceiver;
+
+    impl Receiver for HonestReceiver {
+        fn on_call(&mut self, token: &mut Token) -> Result<(), PSP22Error> {
+            assert_eq!(token.total_supply(), 10);
+            Ok(())
+        }
+    }
+
+    // Emulates a guarded message of the token handing control over to `receiver`,
+    // as the off-chain environment cannot execute real cross-contract calls
+    fn external_call(token: &mut Token, receiver: &mut dyn Receiver) -> Result<(), PSP22Error> {
+        let _lock = token.reentrancy_guard.enter()?;
+        receiver.on_call(token)
+    }
+
+    fn setup() -> Token {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let underlying = Token::new(0, None, 0, 0);
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 0, 0);
+        token.data.mint(owner, 10).expect("Success expected");
+        token
+    }
+
+    #[ink::test]
+    fn reentrant_withdrawal_fails() {
+        let attacker = AccountId::from([0x1; 32]);
+        let mut token = setup();
+
+        let result = external_call(&mut token, &mut MaliciousReceiver { attacker });
+        assert_eq!(result, Err(PSP22Error::Custom("Reentrant call".into())));
+        assert_eq!(token.balance_of(attacker), 10);
+        assert_eq!(token.total_supply(), 10);
+    }
+
+    #[ink::test]
+    fn reentrant_deposit_and_recover_fail() {
+        let owner = AccountId::from([0x1; 32]);
+        let mut token = setup();
+
+        let _lock = token.reentrancy_guard.enter().expect("Success expected");
+        let error = Err(PSP22Error::Custom("Reentrant call".into()));
+        assert_eq!(token.deposit_for(owner, 0), error);
+        assert_eq!(token.recover(owner), error);
+    }
+
+    #[ink::test]
+    fn guard_is_released_after_the_call() {
+        let account = AccountId::from([0x2; 32]);
+        let mut token = setup();
+
+        external_call(&mut token, &mut HonestReceiver).expect("Success expected");
+        assert!(!token.reentrancy_guard.is_locked());
+        // Deposit of zero tokens makes no external calls
+        token.deposit_for(account, 0).expect("Success expected");
+        assert!(!token.reentrancy_guard.is_locked());
+    }
+
+    #[ink::test]
+    fn guard_is_released_after_failed_call() {
+        let attacker = AccountId::from([0x1; 32]);
+        let mut token = setup();
+
+        assert!(external_call(&mut token, &mut MaliciousReceiver { attacker }).is_err());
+        assert!(!token.reentrancy_guard.is_locked());
+    }
+
+    #[ink::test]
+    fn lock_is_shared_through_contract_storage() {
+        let mut guard = ReentrancyGuard::default();
+        let lock = guard.enter().expect("Success expected");
+
+        // A nested call loads a fresh instance of the contract storage
+        let mut nested = ReentrancyGuard::default();
+        assert!(nested.is_locked());
+        assert_eq!(nested.enter().err(), Some(ReentrancyError::ReentrantCall));
+
+        drop(lock);
+        assert!(!nested.is_locked());
+        let _lock = nested.enter().expect("Success expected");
+    }
+}
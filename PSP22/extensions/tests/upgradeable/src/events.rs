@@ -0,0 +1,210 @@
+//! PSP22 events emitted by contracts embedding `PSP22Data`.
+//!
+//! ink! 4 only supports events declared inside a contract, so every contract declares
+//! the `Transfer` and `Approval` events itself, see `lib.rs`, with these fields in this order:
+//!
+//! - `Transfer { #[ink(topic)] from: Option<AccountId>, #[ink(topic)] to: Option<AccountId>, value: u128 }`
+//! - `Approval { #[ink(topic)] owner: AccountId, #[ink(topic)] spender: AccountId, amount: u128 }`
+//!
+//! and generates the method emitting them with [`psp22_events!`](crate::psp22_events).
+//! The payload of an event is the one ink! would emit (the index of the event among the events
+//! of the contract followed by its fields), so it is decoded from the contract metadata as usual.
+//! The topics however do not depend on the name of the contract: the signature topic is
+//! `PSP22::Transfer` or `PSP22::Approval`, followed by a topic for every indexed field prefixed
+//! with `PSP22::<Event>::<field>`. Indexers can recognise a PSP22 event of any contract.
+
+use ink::env::hash::Blake2x256;
+use ink::env::topics::{state, PrefixedValue, TopicsBuilder, TopicsBuilderBackend};
+use ink::env::{Environment, Topics};
+use ink::prelude::format;
+use ink::primitives::AccountId;
+use scale::{DecodeAll, Encode, Output};
+
+use crate::PSP22Event;
+
+/// Signature of the `Transfer` event.
+pub const TRANSFER: &[u8; 15] = b"PSP22::Transfer";
+
+/// Signature of the `Approval` event.
+pub const APPROVAL: &[u8; 15] = b"PSP22::Approval";
+
+/// Generates `fn emit_events(&self, events: Vec<PSP22Event>)` of the `$contract` storage struct.
+///
+/// Invoked inside the `#[ink::contract]` module, after the `Transfer` and `Approval` events
+/// described in [`events`](crate::events). The events are emitted with the PSP22 topics.
+/// Compilation fails if one of them lost an `#[ink(topic)]` attribute.
+#[macro_export]
+macro_rules! psp22_events {
+    ($contract:ident) => {
+        // Never called, only type-checked against the topics of the events
+        #[allow(dead_code)]
+        const _: () = {
+            fn transfer_topics(
+                topics: <Transfer as ::ink::codegen::EventLenTopics>::LenTopics,
+            ) -> ::ink::codegen::EventTopics<2> {
+                topics
+            }
+            fn approval_topics(
+                topics: <Approval as ::ink::codegen::EventLenTopics>::LenTopics,
+            ) -> ::ink::codegen::EventTopics<2> {
+                topics
+            }
+        };
+
+        impl $contract {
+            /// Emits `events` returned by the methods of `PSP22Data`.
+            fn emit_events(&self, events: ::ink::prelude::vec::Vec<$crate::PSP22Event>) {
+                type Event = <$contract as ::ink::reflect::ContractEventBase>::Type;
+                type Env = <$contract as ::ink::env::ContractEnv>::Env;
+                for event in events {
+                    match event {
+                        $crate::PSP22Event::Transfer { from, to, value } => {
+                            ::ink::env::emit_event::<Env, _>($crate::events::TransferTopics {
+                                from,
+                                to,
+                                event: <Event as ::core::convert::From<_>>::from(Transfer { from, to, value }),
+                            })
+                        }
+                        $crate::PSP22Event::Approval {
+                            owner,
+                            spender,
+                            amount,
+                        } => ::ink::env::emit_event::<Env, _>($crate::events::ApprovalTopics {
+                            owner,
+                            spender,
+                            event: <Event as ::core::convert::From<_>>::from(Approval {
+                                owner,
+                                spender,
+                                amount,
+                            }),
+                        }),
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// A `Transfer` `event` of a contract, emitted with the PSP22 topics by `psp22_events!`.
+#[doc(hidden)]
+pub struct TransferTopics<T> {
+    pub from: Option<AccountId>,
+    pub to: Option<AccountId>,
+    pub event: T,
+}
+
+impl<T: Encode> Encode for TransferTopics<T> {
+    fn size_hint(&self) -> usize {
+        self.event.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.event.encode_to(dest)
+    }
+}
+
+impl<T> Topics for TransferTopics<T> {
+    type RemainingTopics = [state::HasRemainingTopics; 3];
+
+    fn topics<E, B>(
+        &self,
+        builder: TopicsBuilder<state::Uninit, E, B>,
+    ) -> <B as TopicsBuilderBackend<E>>::Output
+    where
+        E: Environment,
+        B: TopicsBuilderBackend<E>,
+    {
+        builder
+            .build::<Self>()
+            .push_topic(&PrefixedValue { prefix: b"", value: TRANSFER })
+            .push_topic(&PrefixedValue { prefix: b"PSP22::Transfer::from", value: &self.from })
+            .push_topic(&PrefixedValue { prefix: b"PSP22::Transfer::to", value: &self.to })
+            .finish()
+    }
+}
+
+/// An `Approval` `event` of a contract, emitted with the PSP22 topics by `psp22_events!`.
+#[doc(hidden)]
+pub struct ApprovalTopics<T> {
+    pub owner: AccountId,
+    pub spender: AccountId,
+    pub event: T,
+}
+
+impl<T: Encode> Encode for ApprovalTopics<T> {
+    fn size_hint(&self) -> usize {
+        self.event.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.event.encode_to(dest)
+    }
+}
+
+impl<T> Topics for ApprovalTopics<T> {
+    type RemainingTopics = [state::HasRemainingTopics; 3];
+
+    fn topics<E, B>(
+        &self,
+        builder: TopicsBuilder<state::Uninit, E, B>,
+    ) -> <B as TopicsBuilderBackend<E>>::Output
+    where
+        E: Environment,
+        B: TopicsBuilderBackend<E>,
+    {
+        builder
+            .build::<Self>()
+            .push_topic(&PrefixedValue { prefix: b"", value: APPROVAL })
+            .push_topic(&PrefixedValue { prefix: b"PSP22::Approval::owner", value: &self.owner })
+            .push_topic(&PrefixedValue { prefix: b"PSP22::Approval::spender", value: &self.spender })
+            .finish()
+    }
+}
+
+/// Returns the signature topic of `event`, e.g. `signature_topic("Transfer")`.
+pub fn signature_topic(event: &str) -> [u8; 32] {
+    // The signature is encoded as a byte array, without a length prefix
+    let mut encoded = b"".as_slice().encode();
+    encoded.extend_from_slice(format!("PSP22::{event}").as_bytes());
+    padded_or_hashed(&encoded)
+}
+
+/// Returns the topic of the indexed `field` of `event` with `value`,
+/// e.g. `field_topic("Transfer", "to", &Some(account))` for transfers to `account`.
+pub fn field_topic<T: Encode>(event: &str, field: &str, value: &T) -> [u8; 32] {
+    let prefix = format!("PSP22::{event}::{field}");
+    padded_or_hashed(&PrefixedValue { prefix: prefix.as_bytes(), value }.encode())
+}
+
+/// Decodes a `Transfer` or `Approval` event from its first topic and its payload.
+///
+/// Returns `None` for events of other kinds or malformed payloads.
+pub fn decode_event(signature: &[u8], data: &[u8]) -> Option<PSP22Event> {
+    // The payload starts with the index of the event among the events of the contract
+    let fields = &mut data.get(1..)?;
+    if signature == signature_topic("Transfer") {
+        let (from, to, value) = <(Option<AccountId>, Option<AccountId>, u128)>::decode_all(fields).ok()?;
+        Some(PSP22Event::Transfer { from, to, value })
+    } else if signature == signature_topic("Approval") {
+        let (owner, spender, amount) = <(AccountId, AccountId, u128)>::decode_all(fields).ok()?;
+        Some(PSP22Event::Approval {
+            owner,
+            spender,
+            amount,
+        })
+    } else {
+        None
+    }
+}
+
+/// Computes a topic from its encoding the way the contracts environment does:
+/// encodings up to 32 bytes are zero-padded, longer ones are hashed with Blake2x256.
+fn padded_or_hashed(encoded: &[u8]) -> [u8; 32] {
+    let mut topic = [0u8; 32];
+    if encoded.len() <= topic.len() {
+        topic[..encoded.len()].copy_from_slice(encoded);
+    } else {
+        ink::env::hash_bytes::<Blake2x256>(encoded, &mut topic);
+    }
+    topic
+}
@@ -0,0 +1,51 @@
+use psp22_upgradeable_tests::token::*;
+use psp22_upgradeable_tests::PSP22Error;
+use psp22_upgradeable_tests::traits::PSP22Upgradeable;
+
+psp22_upgradeable_tests::psp22_tests!(Token, Token::new);
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::{AccountId, Hash};
+    use ink::env::{DefaultEnvironment, test::set_caller};
+    use super::*;
+
+    #[ink::test]
+    fn storage_version_is_current() {
+        let token = Token::new(100);
+        assert_eq!(token.storage_version(), STORAGE_VERSION);
+    }
+
+    #[ink::test]
+    fn migrate_without_upgrade_fails() {
+        let mut token = Token::new(100);
+        assert_eq!(token.migrate(), Err(PSP22Error::Custom(String::from("Already migrated"))));
+        assert_eq!(token.storage_version(), STORAGE_VERSION);
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can migrate")]
+    fn migrate_by_non_owner_fails() {
+        let mut token = Token::new(100);
+        set_caller::<DefaultEnvironment>(AccountId::from([0x3; 32]));
+        let _ = token.migrate();
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can set code hash")]
+    fn set_code_hash_by_non_owner_fails() {
+        let mut token = Token::new(100);
+        set_caller::<DefaultEnvironment>(AccountId::from([0x3; 32]));
+        let _ = token.set_code_hash(Hash::from([0x1; 32]), STORAGE_VERSION);
+    }
+
+    #[ink::test]
+    fn set_code_hash_of_older_version_is_refused() {
+        let mut token = Token::new(100);
+        assert_eq!(
+            token.set_code_hash(Hash::from([0x1; 32]), STORAGE_VERSION - 1),
+            Err(PSP22Error::Custom(String::from("Downgrade refused")))
+        );
+        assert_eq!(token.storage_version(), STORAGE_VERSION);
+    }
+}
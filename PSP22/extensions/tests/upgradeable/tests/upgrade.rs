@@ -0,0 +1,174 @@
+//! Upgrades a token to a second version of its code, which adds a field to the storage.
+//!
+//! The off-chain environment cannot replace the code of a contract, so the upgrade is
+//! performed the way the chain sees it: the storage written by the first version of the
+//! code is read and migrated by the second one, and then read again by the first one.
+
+use ink::env::DefaultEnvironment;
+use ink::env::test::set_caller;
+use ink::primitives::AccountId;
+use ink::storage::traits::{Storable, StorageKey};
+
+use psp22_upgradeable_tests::token::Token;
+use psp22_upgradeable_tests::traits::PSP22Upgradeable;
+use psp22_upgradeable_tests::{PSP22, PSP22Error};
+
+use token_v2::TokenV2;
+
+/// The second version of the token code.
+///
+/// Only its storage and the messages used below are defined, as a struct rather than
+/// a second `#[ink::contract]`, which cannot be linked next to the first one.
+mod token_v2 {
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::string::String;
+    use ink::primitives::AccountId;
+    use ink::storage::Lazy;
+
+    use psp22_upgradeable_tests::{PSP22Data, PSP22Error};
+
+    pub const STORAGE_VERSION: u32 = 2;
+
+    /// The storage of the first version followed by the new `decimals` field.
+    /// New fields must be `Lazy` (or `Mapping`): they are stored under their own keys,
+    /// so the root value written by the first version still decodes.
+    #[ink::storage_item]
+    pub struct TokenV2 {
+        data: PSP22Data,
+        storage_version: u32,
+        owner: Option<AccountId>,
+        decimals: Lazy<u8>,
+    }
+
+    impl TokenV2 {
+        fn caller() -> AccountId {
+            ink::env::caller::<DefaultEnvironment>()
+        }
+
+        fn migrate_storage(&mut self, version: u32) -> Result<(), PSP22Error> {
+            if version < 2 {
+                self.decimals.set(&18);
+            }
+            Ok(())
+        }
+
+        fn ensure_migrated(&mut self) -> Result<(), PSP22Error> {
+            if self.storage_version > STORAGE_VERSION {
+                return Err(PSP22Error::Custom(String::from("Downgrade refused")));
+            }
+            if self.storage_version < STORAGE_VERSION {
+                self.migrate_storage(self.storage_version)?;
+                self.storage_version = STORAGE_VERSION;
+            }
+            Ok(())
+        }
+
+        pub fn balance_of(&self, owner: AccountId) -> u128 {
+            self.data.balance_of(owner)
+        }
+
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.data.allowance(owner, spender)
+        }
+
+        pub fn transfer(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            self.ensure_migrated()?;
+            // Events are declared by contracts only, so they are not emitted by this version
+            self.data.transfer(Self::caller(), to, value)?;
+            Ok(())
+        }
+
+        pub fn decimals(&self) -> u8 {
+            self.decimals.get().unwrap_or_default()
+        }
+
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        /// Checks the version like the first version of the code does, the off-chain
+        /// environment cannot replace the code itself.
+        pub fn set_code_hash(&mut self, target_version: u32) -> Result<(), PSP22Error> {
+            assert_eq!(Some(Self::caller()), self.owner, "Only owner can set code hash");
+            if target_version < STORAGE_VERSION {
+                return Err(PSP22Error::Custom(String::from("Downgrade refused")));
+            }
+            self.ensure_migrated()
+        }
+
+        pub fn migrate(&mut self) -> Result<(), PSP22Error> {
+            assert_eq!(Some(Self::caller()), self.owner, "Only owner can migrate");
+            if self.storage_version == STORAGE_VERSION {
+                return Err(PSP22Error::Custom(String::from("Already migrated")));
+            }
+            self.ensure_migrated()
+        }
+    }
+}
+
+/// Writes the root of the contract storage, as the chain does at the end of every call.
+fn store<T: Storable>(contract: &T) {
+    ink::env::set_contract_storage(&<Token as StorageKey>::KEY, contract);
+}
+
+/// Reads the root of the contract storage, as the chain does at the start of every call.
+fn load<T: Storable>() -> T {
+    ink::env::get_contract_storage(&<Token as StorageKey>::KEY)
+        .expect("storage decodes")
+        .expect("storage exists")
+}
+
+fn downgrade_refused() -> PSP22Error {
+    PSP22Error::Custom(String::from("Downgrade refused"))
+}
+
+#[ink::test]
+fn upgrade_migrates_on_first_change_and_refuses_downgrade() {
+    let alice = AccountId::from([0x1; 32]);
+    let bob = AccountId::from([0x2; 32]);
+    set_caller::<DefaultEnvironment>(alice);
+
+    // Deploy the first version
+    let mut token = Token::new(1000);
+    token.transfer(bob, 300, vec![]).expect("Success expected");
+    token.approve(bob, 50).expect("Success expected");
+    store(&token);
+
+    // Upgrade to the second version, which migrates the storage on the first transfer
+    let mut token: TokenV2 = load();
+    assert_eq!(token.storage_version(), 1);
+    token.transfer(bob, 100).expect("Success expected");
+    assert_eq!(token.storage_version(), 2);
+    assert_eq!(token.decimals(), 18);
+    assert_eq!(token.balance_of(alice), 600);
+    assert_eq!(token.balance_of(bob), 400);
+    assert_eq!(token.allowance(alice, bob), 50);
+
+    // The migration runs once
+    assert_eq!(token.migrate(), Err(PSP22Error::Custom(String::from("Already migrated"))));
+
+    // Going back to the first version is refused before the code is replaced
+    assert_eq!(token.set_code_hash(1), Err(downgrade_refused()));
+    store(&token);
+
+    // Code older than the storage keeps the data readable, but refuses any change
+    let mut token: Token = load();
+    assert_eq!(token.storage_version(), 2);
+    assert_eq!(token.migrate(), Err(downgrade_refused()));
+    assert_eq!(token.transfer(bob, 100, vec![]), Err(downgrade_refused()));
+    assert_eq!(token.balance_of(alice), 600);
+    assert_eq!(token.total_supply(), 1000);
+}
+
+#[ink::test]
+fn owner_can_migrate_before_first_change() {
+    let alice = AccountId::from([0x1; 32]);
+    set_caller::<DefaultEnvironment>(alice);
+    store(&Token::new(1000));
+
+    let mut token: TokenV2 = load();
+    token.migrate().expect("Success expected");
+    assert_eq!(token.storage_version(), 2);
+    assert_eq!(token.decimals(), 18);
+    assert_eq!(token.balance_of(alice), 1000);
+}
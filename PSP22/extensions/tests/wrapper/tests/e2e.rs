@@ -14,9 +14,10 @@ mod tests {
     #[ink_e2e::test]
     #[ignore]
     //@FIXME failing with error: deploy function isn't exported
+    //@FIXME the underlying must be a token other than the wrapper, which rejects an initial supply
     async fn wrapper_works<T>(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
         // 1. Deploying both contracts (underlying and wrapper).
-        let constructor_underlying = TokenRef::new(10, None);
+        let constructor_underlying = TokenRef::new(10, None, 0, 0);
         let contract_acc_underlying_id = client
             .instantiate("psp22_wrapper_tests", &ink_e2e::bob(), constructor_underlying, 0, None)
             .await
@@ -28,7 +29,7 @@ mod tests {
             .call(&ink_e2e::bob(), get_underlying, 0, None)
             .await
             .expect("get_res_underlying failed");
-        let constructor = TokenRef::new(0, Some(contract_acc_underlying_id));
+        let constructor = TokenRef::new(0, Some(contract_acc_underlying_id), 0, 0);
         let contract_acc_id = client
             .instantiate("psp22_wrapper_tests", &ink_e2e::bob(), constructor, 0, None)
             .await
@@ -1,11 +1,21 @@
 use psp22_wrapper_tests::token::*;
+use psp22_wrapper_tests::data::{to_underlying_amount, to_wrapped_amount};
+
+// The constructor rejects an initial supply and the off-chain environment cannot call an underlying
+// token, so the supply of the common tests is minted to the caller as a deposit would
+psp22_wrapper_tests::psp22_tests!(Token, |supply| {
+    let mut token = Token::new(0, None, 0, 0);
+    token.data.mint(ink::env::caller::<ink::env::DefaultEnvironment>(), supply).expect("Success expected");
+    token
+});
 
 #[cfg(test)]
 mod tests {
     use ink::codegen::Env;
     use ink::primitives::AccountId;
-    use ink::env::{DefaultEnvironment, test::set_caller};
+    use ink::env::{DefaultEnvironment, test::{recorded_events, set_caller}};
     use psp22_wrapper_tests::traits::PSP22Wrapper;
+    use psp22_wrapper_tests::PSP22;
     use super::*;
 
     #[ink::test]
@@ -14,9 +24,9 @@ mod tests {
         let owner = AccountId::from([0x1; 32]);
         let deposit_for = AccountId::from([0x2; 32]);
         set_caller::<DefaultEnvironment>(owner);
-        let underlying = Token::new(10, None);
+        let underlying = Token::new(0, None, 0, 0);
 
-        let mut token = Token::new(10, Some(underlying.env().account_id()));
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 0, 0);
         token.deposit_for(deposit_for, 5).expect("Will fail for unit tests...");
     }
 
@@ -26,9 +36,98 @@ mod tests {
         let owner = AccountId::from([0x1; 32]);
         let account = AccountId::from([0x2; 32]);
         set_caller::<DefaultEnvironment>(owner);
-        let underlying = Token::new(10, None);
+        let underlying = Token::new(0, None, 0, 0);
 
-        let mut token = Token::new(10, Some(underlying.env().account_id()));
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 0, 0);
+        token.data.mint(owner, 10).expect("Success expected");
         token.withdraw_to(account, 5).expect("Will fail for unit tests...");
     }
+
+    #[test]
+    fn amount_conversion_works() {
+        // Wrapping a 6 decimals token into an 18 decimals one
+        assert_eq!(to_wrapped_amount(1_000_000, 12), Ok(1_000_000_000_000_000_000));
+        assert_eq!(to_underlying_amount(1_000_000_000_000_000_000, 12), Ok(1_000_000));
+        assert_eq!(to_underlying_amount(1_999_999_999_999, 12), Ok(1));
+        assert!(to_wrapped_amount(u128::MAX, 12).is_err());
+
+        // Wrapping an 18 decimals token into a 6 decimals one
+        assert_eq!(to_wrapped_amount(1_999_999_999_999, -12), Ok(1));
+        assert_eq!(to_underlying_amount(1, -12), Ok(1_000_000_000_000));
+
+        assert_eq!(to_wrapped_amount(123, 0), Ok(123));
+        assert_eq!(to_underlying_amount(123, 0), Ok(123));
+        assert_eq!(to_wrapped_amount(u128::MAX, i8::MIN), Ok(0));
+        assert_eq!(to_wrapped_amount(0, i8::MAX), Ok(0));
+        assert!(to_wrapped_amount(1, i8::MAX).is_err());
+        assert_eq!(to_underlying_amount(u128::MAX, i8::MAX), Ok(0));
+    }
+
+    #[test]
+    fn amount_conversion_round_trip_never_creates_tokens() {
+        for offset in -40..=40i8 {
+            for amount in [0, 1, 9, 10, 11, 999_999, 1_000_001, u64::MAX as u128, u128::MAX] {
+                if let Ok(wrapped) = to_wrapped_amount(amount, offset) {
+                    let underlying = to_underlying_amount(wrapped, offset).expect("Inverse fits");
+                    assert!(underlying <= amount);
+                    assert_eq!(to_wrapped_amount(underlying, offset), Ok(wrapped));
+                }
+            }
+        }
+    }
+
+    #[ink::test]
+    fn dust_deposit_and_withdrawal_are_noop() {
+        let owner = AccountId::from([0x1; 32]);
+        let account = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let underlying = Token::new(0, None, 0, 0);
+        let start = recorded_events().count();
+
+        // Less than one unit of the 6 decimals wrapped token
+        let mut token = Token::new(0, Some(underlying.env().account_id()), -12, 0);
+        assert_eq!(token.decimals_offset(), -12);
+        token.deposit_for(account, 999_999_999_999).expect("Success expected");
+        assert_eq!(token.total_supply(), 0);
+
+        // Less than one unit of the 6 decimals underlying token
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 12, 0);
+        token.data.mint(owner, 10_000_000).expect("Success expected");
+        token.withdraw_to(account, 999_999_999_999).expect("Success expected");
+        assert_eq!(token.balance_of(owner), 10_000_000);
+        assert_eq!(recorded_events().count(), start);
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "not implemented: off-chain environment does not support contract invocation")]
+    fn recover_in_unit_tests_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let underlying = Token::new(0, None, 0, 0);
+
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 0, 0);
+        token.recover(owner).expect("Will fail for unit tests...");
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Initial supply must be zero")]
+    fn constructor_rejects_initial_supply() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let underlying = Token::new(0, None, 0, 0);
+
+        Token::new(10, Some(underlying.env().account_id()), 0, 0);
+    }
+
+    #[ink::test]
+    #[should_panic(expected = "Only owner can recover")]
+    fn recover_by_non_owner_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let underlying = Token::new(0, None, 0, 0);
+
+        let mut token = Token::new(0, Some(underlying.env().account_id()), 0, 0);
+        set_caller::<DefaultEnvironment>(AccountId::from([0x2; 32]));
+        token.recover(owner).expect("Will fail");
+    }
 }
@@ -5,11 +5,21 @@
 
 
 
+pub mod client;
 pub mod data;
 pub mod errors;
+pub mod events;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(any(test, feature = "fuzz"))]
+pub mod model;
+pub mod security;
+pub mod storage;
 pub mod traits;
+mod unit_tests;
 
-pub use data::{PSP22Data, PSP22Event};
+pub use client::PSP22Ref;
+pub use data::{Fee, PSP22Data, PSP22Event};
 pub use errors::PSP22Error;
 pub use traits::PSP22;
 
@@ -21,7 +31,7 @@ pub use traits::PSP22;
 pub mod token {
     use ink::prelude::vec::Vec;
 
-    use crate::{PSP22, PSP22Data, PSP22Error, PSP22Event};
+    use crate::{Fee, PSP22, PSP22Data, PSP22Error};
 
     #[ink(storage)]
     pub struct Token {
@@ -42,54 +52,86 @@ pub mod token {
         pub fn new(
             supply: u128,
         ) -> Self {
+            if let Err(error) = Self::before_initial_mint(supply) {
+                panic!("{:?}", error);
+            }
             Self {
                 data: PSP22Data::new(supply, Self::env().caller()),
             }
         }
 
-        /// Emits specified PSP22 events.
+        /// Hook called by the constructor before the initial `supply` is minted to the caller.
         ///
-        /// # Arguments
+        /// Returning an error makes the deployment fail.
+        #[allow(dead_code, unused_variables)]
+        fn before_initial_mint(supply: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook called before `value` tokens are moved from `from` to `to`.
         ///
-        /// * `events` - A vector of `PSP22Event` to be emitted.
-        fn emit_events(&self, events: Vec<PSP22Event>) {
-            for event in events {
-                match event {
-                    PSP22Event::Transfer { from, to, value } => {
-                        self.env().emit_event(Transfer { from, to, value })
-                    }
-                    PSP22Event::Approval {
-                        owner,
-                        spender,
-                        amount,
-                    } => self.env().emit_event(Approval {
-                        owner,
-                        spender,
-                        amount,
-                    }),
-                }
-            }
+        /// Extensions add their checks here, returning an error aborts the transfer.
+        #[allow(dead_code, unused_variables)]
+        fn before_transfer(&mut self, from: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook setting the `fee` charged on a transfer of `value` tokens from `from` to `to`.
+        ///
+        /// No fee is charged unless an extension sets one, the fee is part of `value`.
+        #[allow(dead_code, unused_variables)]
+        fn transfer_fee(&self, from: AccountId, to: AccountId, value: u128, fee: &mut Option<Fee>) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook called after `value` tokens were moved from `from` to `to`.
+        ///
+        /// Returning an error reverts the transfer.
+        #[allow(dead_code, unused_variables)]
+        fn after_transfer(&mut self, from: AccountId, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook called before `value` tokens are minted to `to`.
+        #[allow(dead_code, unused_variables)]
+        fn before_mint(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook called before `value` tokens of `from` are burned.
+        #[allow(dead_code, unused_variables)]
+        fn before_burn(&mut self, from: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        /// Hook called before the allowance of `spender` over the tokens of `owner` is set to `value`.
+        #[allow(dead_code, unused_variables)]
+        fn before_approve(&mut self, owner: AccountId, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
         }
     }
 
+    // The fields and their order are the layout documented in `events.rs`
     #[ink(event)]
     pub struct Approval {
         #[ink(topic)]
-        owner: AccountId,
+        pub owner: AccountId,
         #[ink(topic)]
-        spender: AccountId,
-        amount: u128,
+        pub spender: AccountId,
+        pub amount: u128,
     }
 
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
-        from: Option<AccountId>,
+        pub from: Option<AccountId>,
         #[ink(topic)]
-        to: Option<AccountId>,
-        value: u128,
+        pub to: Option<AccountId>,
+        pub value: u128,
     }
 
+    crate::psp22_events!(Token);
+
     impl PSP22 for Token {
         /// Returns the total supply of tokens.
         ///
@@ -155,8 +197,13 @@ pub mod token {
             value: u128,
             _data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
-            let events = self.data.transfer(self.env().caller(), to, value)?;
+            let caller = self.env().caller();
+            self.before_transfer(caller, to, value)?;
+            let mut fee = None;
+            self.transfer_fee(caller, to, value, &mut fee)?;
+            let events = self.data.transfer_with_fee(caller, to, value, fee)?;
             self.emit_events(events);
+            self.after_transfer(caller, to, value)?;
             Ok(())
         }
 
@@ -188,10 +235,14 @@ pub mod token {
             value: u128,
             _data: Vec<u8>,
         ) -> Result<(), PSP22Error> {
+            self.before_transfer(from, to, value)?;
+            let mut fee = None;
+            self.transfer_fee(from, to, value, &mut fee)?;
             let events = self
                 .data
-                .transfer_from(self.env().caller(), from, to, value)?;
+                .transfer_from_with_fee(self.env().caller(), from, to, value, fee)?;
             self.emit_events(events);
+            self.after_transfer(from, to, value)?;
             Ok(())
         }
 
@@ -211,7 +262,9 @@ pub mod token {
         /// Emits an `Approval` event on successful approval.
         #[ink(message)]
         fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
-            let events = self.data.approve(self.env().caller(), spender, value)?;
+            let caller = self.env().caller();
+            self.before_approve(caller, spender, value)?;
+            let events = self.data.approve(caller, spender, value)?;
             self.emit_events(events);
             Ok(())
         }
@@ -239,9 +292,12 @@ pub mod token {
             spender: AccountId,
             delta_value: u128,
         ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let value = self.allowance(caller, spender).saturating_add(delta_value);
+            self.before_approve(caller, spender, value)?;
             let events = self
                 .data
-                .increase_allowance(self.env().caller(), spender, delta_value)?;
+                .increase_allowance(caller, spender, delta_value)?;
             self.emit_events(events);
             Ok(())
         }
@@ -273,9 +329,12 @@ pub mod token {
             spender: AccountId,
             delta_value: u128,
         ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let value = self.allowance(caller, spender).saturating_sub(delta_value);
+            self.before_approve(caller, spender, value)?;
             let events = self
                 .data
-                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+                .decrease_allowance(caller, spender, delta_value)?;
             self.emit_events(events);
             Ok(())
         }
@@ -0,0 +1,384 @@
+//! Model-based testing of `PSP22Data`.
+//!
+//! Sequences of operations are decoded from raw bytes and applied both to `PSP22Data`
+//! and to a plain reference implementation of the PSP22 rules. After every step the
+//! outcomes and the resulting states are compared, and the invariants of the token
+//! are checked. Used by the property tests below and by the `cargo fuzz` target in `fuzz/`.
+
+use std::collections::BTreeMap;
+use std::mem::discriminant;
+
+use ink::env::DefaultEnvironment;
+use ink::primitives::AccountId;
+
+use crate::storage::{InMemory, OnChain, StorageBackend};
+use crate::{PSP22Data, PSP22Error};
+
+/// Number of accounts taking part in the operations.
+pub const ACCOUNTS: u8 = 4;
+
+/// A single operation on the token. Accounts are indices below `ACCOUNTS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Mint { to: u8, value: u128 },
+    Burn { from: u8, value: u128 },
+    Transfer { caller: u8, to: u8, value: u128 },
+    Approve { owner: u8, spender: u8, value: u128 },
+    TransferFrom { caller: u8, from: u8, to: u8, value: u128 },
+}
+
+impl Operation {
+    /// Decodes a sequence of operations from `bytes`, ignoring an incomplete one at the end.
+    ///
+    /// Every byte is meaningful, so that any fuzzer input is a valid sequence.
+    pub fn decode_all(bytes: &[u8]) -> Vec<Operation> {
+        let mut bytes = bytes.iter().copied();
+        let mut operations = Vec::new();
+        while let Some(operation) = Self::decode(&mut bytes) {
+            operations.push(operation);
+        }
+        operations
+    }
+
+    fn decode(bytes: &mut impl Iterator<Item = u8>) -> Option<Operation> {
+        let operation = match decode_account(bytes.next()?) {
+            0 => Operation::Mint {
+                to: decode_account(bytes.next()?),
+                value: decode_value(bytes.next()?),
+            },
+            1 => Operation::Burn {
+                from: decode_account(bytes.next()?),
+                value: decode_value(bytes.next()?),
+            },
+            2 => Operation::Transfer {
+                caller: decode_account(bytes.next()?),
+                to: decode_account(bytes.next()?),
+                value: decode_value(bytes.next()?),
+            },
+            // The last tag is shared, so the next byte picks the operation
+            _ if bytes.next()? % 2 == 0 => Operation::Approve {
+                owner: decode_account(bytes.next()?),
+                spender: decode_account(bytes.next()?),
+                value: decode_value(bytes.next()?),
+            },
+            _ => Operation::TransferFrom {
+                caller: decode_account(bytes.next()?),
+                from: decode_account(bytes.next()?),
+                to: decode_account(bytes.next()?),
+                value: decode_value(bytes.next()?),
+            },
+        };
+        Some(operation)
+    }
+}
+
+/// Maps a byte to the index of an account.
+fn decode_account(byte: u8) -> u8 {
+    byte % ACCOUNTS
+}
+
+/// Maps a byte to a value, mostly small ones so that balances are often exceeded,
+/// with the highest bytes mapped close to `u128::MAX` to reach the supply limit.
+fn decode_value(byte: u8) -> u128 {
+    if byte >= 0xF0 {
+        u128::MAX - u128::from(byte & 0x0F)
+    } else {
+        u128::from(byte)
+    }
+}
+
+/// Returns the account with the given index.
+pub fn account(index: u8) -> AccountId {
+    AccountId::from([index.wrapping_add(1); 32])
+}
+
+/// Plain implementation of the PSP22 rules, which `PSP22Data` is compared against.
+#[derive(Debug, Default)]
+struct Reference {
+    total_supply: u128,
+    balances: BTreeMap<u8, u128>,
+    allowances: BTreeMap<(u8, u8), u128>,
+}
+
+impl Reference {
+    fn balance(&self, account: u8) -> u128 {
+        self.balances.get(&account).copied().unwrap_or_default()
+    }
+
+    fn allowance(&self, owner: u8, spender: u8) -> u128 {
+        self.allowances.get(&(owner, spender)).copied().unwrap_or_default()
+    }
+
+    fn apply(&mut self, operation: &Operation) -> Result<(), PSP22Error> {
+        match *operation {
+            Operation::Mint { to, value } => {
+                self.total_supply = self
+                    .total_supply
+                    .checked_add(value)
+                    .ok_or(PSP22Error::Custom(String::new()))?;
+                self.balances.insert(to, self.balance(to) + value);
+            }
+            Operation::Burn { from, value } => {
+                let balance = self.balance(from);
+                if balance < value {
+                    return Err(PSP22Error::InsufficientBalance);
+                }
+                self.balances.insert(from, balance - value);
+                self.total_supply -= value;
+            }
+            Operation::Transfer { caller, to, value } => self.transfer(caller, to, value)?,
+            Operation::Approve { owner, spender, value } => {
+                if owner != spender {
+                    self.allowances.insert((owner, spender), value);
+                }
+            }
+            Operation::TransferFrom { caller, from, to, value } => {
+                if from == to || value == 0 || caller == from {
+                    return self.transfer(from, to, value);
+                }
+                let allowance = self.allowance(from, caller);
+                if allowance < value {
+                    return Err(PSP22Error::InsufficientAllowance);
+                }
+                self.transfer(from, to, value)?;
+                self.allowances.insert((from, caller), allowance - value);
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer(&mut self, from: u8, to: u8, value: u128) -> Result<(), PSP22Error> {
+        if from == to || value == 0 {
+            return Ok(());
+        }
+        let balance = self.balance(from);
+        if balance < value {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+        self.balances.insert(from, balance - value);
+        self.balances.insert(to, self.balance(to) + value);
+        Ok(())
+    }
+}
+
+/// `PSP22Data` paired with the reference implementation it is checked against.
+#[derive(Debug, Default)]
+pub struct Model<B: StorageBackend = OnChain> {
+    data: PSP22Data<B>,
+    reference: Reference,
+}
+
+impl<B: StorageBackend> Model<B> {
+    /// Creates a model of a token with no supply.
+    ///
+    /// With the `OnChain` backend, must be used inside the off-chain test environment,
+    /// e.g. within `ink::env::test::run_test`.
+    pub fn new() -> Model<B> {
+        Model {
+            data: PSP22Data::new(0, account(0)),
+            reference: Reference::default(),
+        }
+    }
+
+    /// Applies `operation` to both implementations, panics if their outcomes differ
+    /// or any invariant is broken afterwards.
+    pub fn apply(&mut self, operation: &Operation) {
+        let result = match *operation {
+            Operation::Mint { to, value } => self.data.mint(account(to), value),
+            Operation::Burn { from, value } => self.data.burn(account(from), value),
+            Operation::Transfer { caller, to, value } => {
+                self.data.transfer(account(caller), account(to), value)
+            }
+            Operation::Approve { owner, spender, value } => {
+                self.data.approve(account(owner), account(spender), value)
+            }
+            Operation::TransferFrom { caller, from, to, value } => {
+                self.data
+                    .transfer_from(account(caller), account(from), account(to), value)
+            }
+        };
+        let expected = self.reference.apply(operation);
+        match (&result, &expected) {
+            (Ok(_), Ok(())) => {}
+            // Custom errors are compared by kind only
+            (Err(error), Err(expected_error)) => assert_eq!(
+                discriminant(error),
+                discriminant(expected_error),
+                "{operation:?} failed with {error:?} instead of {expected_error:?}"
+            ),
+            _ => panic!(
+                "{operation:?} resulted in {:?} instead of {expected:?}",
+                result.as_ref().map(|_| ())
+            ),
+        }
+        self.check_invariants();
+    }
+
+    /// Panics if the state of `PSP22Data` differs from the reference one or breaks an invariant.
+    pub fn check_invariants(&self) {
+        assert_eq!(self.data.total_supply(), self.reference.total_supply, "total supply mismatch");
+
+        let mut sum: u128 = 0;
+        for index in 0..ACCOUNTS {
+            let balance = self.data.balance_of(account(index));
+            assert_eq!(balance, self.reference.balance(index), "balance mismatch of account {index}");
+            assert_eq!(
+                self.data.stores_balance(account(index)),
+                balance != 0,
+                "zero balance of account {index} is stored"
+            );
+            sum = sum.checked_add(balance).expect("sum of balances overflows");
+
+            for spender in 0..ACCOUNTS {
+                let allowance = self.data.allowance(account(index), account(spender));
+                assert_eq!(
+                    allowance,
+                    self.reference.allowance(index, spender),
+                    "allowance mismatch of {index} to {spender}"
+                );
+                assert_eq!(
+                    self.data.stores_allowance(account(index), account(spender)),
+                    allowance != 0,
+                    "zero allowance of {index} to {spender} is stored"
+                );
+            }
+        }
+        assert_eq!(sum, self.data.total_supply(), "sum of balances differs from total supply");
+
+        #[cfg(feature = "enumerable")]
+        self.check_enumeration();
+    }
+
+    #[cfg(feature = "enumerable")]
+    fn check_enumeration(&self) {
+        let limit = u32::from(ACCOUNTS) + 1;
+        let mut holders = self.data.holders(0, limit);
+        holders.sort();
+        let mut expected: Vec<_> = (0..ACCOUNTS)
+            .filter(|index| self.reference.balance(*index) != 0)
+            .map(account)
+            .collect();
+        expected.sort();
+        assert_eq!(holders, expected, "holders mismatch");
+        assert_eq!(self.data.holder_count() as usize, expected.len(), "holder count mismatch");
+
+        for owner in 0..ACCOUNTS {
+            let mut spenders = self.data.approved_spenders(account(owner), 0, limit);
+            spenders.sort();
+            let mut expected: Vec<_> = (0..ACCOUNTS)
+                .filter(|spender| self.reference.allowance(owner, *spender) != 0)
+                .map(account)
+                .collect();
+            expected.sort();
+            assert_eq!(spenders, expected, "approved spenders of {owner} mismatch");
+            assert_eq!(
+                self.data.approved_spender_count(account(owner)) as usize,
+                expected.len(),
+                "approved spender count of {owner} mismatch"
+            );
+        }
+    }
+}
+
+/// Applies `operations` to a fresh model in a new off-chain environment.
+pub fn run_operations(operations: &[Operation]) {
+    ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+        let mut model = Model::<OnChain>::new();
+        for operation in operations {
+            model.apply(operation);
+        }
+        Ok(())
+    })
+    .expect("off-chain environment failed");
+}
+
+/// Applies `operations` to a fresh model kept in memory, without the off-chain environment.
+pub fn run_operations_in_memory(operations: &[Operation]) {
+    let mut model = Model::<InMemory>::new();
+    for operation in operations {
+        model.apply(operation);
+    }
+}
+
+/// Decodes operations from `bytes` and applies them to a fresh model. Entry point of the fuzz target.
+pub fn run(bytes: &[u8]) {
+    run_operations(&Operation::decode_all(bytes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Xorshift generator, deterministic so that a failing seed can be reproduced.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn bytes(&mut self, len: usize) -> Vec<u8> {
+            (0..len).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    /// Checks `cases` random sequences of `len` bytes, longer runs are left to the fuzzer.
+    fn check_random_sequences(cases: u64, len: usize) {
+        for seed in 1..=cases {
+            let bytes = Rng(seed).bytes(len);
+            let result = std::panic::catch_unwind(|| run(&bytes));
+            assert!(result.is_ok(), "sequence of seed {seed} broke the model");
+        }
+    }
+
+    /// Same as `check_random_sequences` with the in-memory backend, which allows many more cases.
+    fn check_random_sequences_in_memory(cases: u64, len: usize) {
+        for seed in 1..=cases {
+            let operations = Operation::decode_all(&Rng(seed).bytes(len));
+            let result = std::panic::catch_unwind(|| run_operations_in_memory(&operations));
+            assert!(result.is_ok(), "sequence of seed {seed} broke the model");
+        }
+    }
+
+    #[test]
+    fn short_random_sequences_match_model() {
+        check_random_sequences(64, 32);
+    }
+
+    #[test]
+    fn random_sequences_match_model_in_memory() {
+        check_random_sequences_in_memory(512, 256);
+    }
+
+    #[test]
+    fn long_random_sequences_match_model() {
+        check_random_sequences(8, 256);
+    }
+
+    #[test]
+    fn supply_overflow_is_rejected() {
+        run_operations(&[
+            Operation::Mint { to: 0, value: u128::MAX },
+            Operation::Mint { to: 1, value: 1 },
+            Operation::Transfer { caller: 0, to: 1, value: u128::MAX },
+            Operation::Burn { from: 1, value: u128::MAX },
+            Operation::Mint { to: 1, value: 1 },
+        ]);
+    }
+
+    #[test]
+    fn allowances_are_spent_and_removed() {
+        run_operations(&[
+            Operation::Mint { to: 0, value: 10 },
+            Operation::Approve { owner: 0, spender: 1, value: 5 },
+            Operation::TransferFrom { caller: 1, from: 0, to: 2, value: 6 },
+            Operation::TransferFrom { caller: 1, from: 0, to: 2, value: 5 },
+            Operation::Approve { owner: 0, spender: 1, value: 20 },
+            Operation::TransferFrom { caller: 1, from: 0, to: 2, value: 6 },
+            Operation::Approve { owner: 0, spender: 1, value: 0 },
+        ]);
+    }
+}
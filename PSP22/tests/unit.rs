@@ -1,6 +1,8 @@
 use psp22_full::token::*;
 use psp22_full::PSP22;
 
+psp22_full::psp22_tests!(Token, Token::new);
+
 #[cfg(test)]
 mod tests {
     use ink::primitives::AccountId;
@@ -282,4 +284,46 @@ mod tests {
         token.data.burn(owner,1).expect("Burning should be possible");
         assert_eq!(token.total_supply(), 0);
     }
+
+    #[test]
+    fn trait_selectors_match_psp22_standard() {
+        use ink::reflect::{TraitDefinitionRegistry, TraitMessageInfo};
+        use ink::{selector_bytes, selector_id};
+        type PSP22Info = <TraitDefinitionRegistry<DefaultEnvironment> as PSP22>::__ink_TraitInfo;
+
+        // Selectors previously hardcoded in the wrapper, as defined by the PSP22 standard
+        assert_eq!(
+            <PSP22Info as TraitMessageInfo<{ selector_id!("transfer") }>>::SELECTOR,
+            [219, 32, 249, 245]
+        );
+        assert_eq!(
+            <PSP22Info as TraitMessageInfo<{ selector_id!("transfer_from") }>>::SELECTOR,
+            [84, 179, 199, 110]
+        );
+        assert_eq!(
+            <PSP22Info as TraitMessageInfo<{ selector_id!("balance_of") }>>::SELECTOR,
+            selector_bytes!("PSP22::balance_of")
+        );
+    }
+
+    #[test]
+    fn call_errors_are_mapped_to_psp22_errors() {
+        use ink::primitives::LangError;
+        use psp22_full::client::map_call_result;
+        use psp22_full::PSP22Error;
+
+        assert_eq!(map_call_result::<u128>(Ok(Ok(5))), Ok(5));
+        assert_eq!(
+            map_call_result::<u128>(Ok(Err(LangError::CouldNotReadInput))),
+            Err(PSP22Error::Custom("Call could not be dispatched: CouldNotReadInput".into()))
+        );
+        assert_eq!(
+            map_call_result::<u128>(Err(ink::env::Error::CalleeTrapped)),
+            Err(PSP22Error::Custom("Call failed: CalleeTrapped".into()))
+        );
+        // Errors returned by the called message are passed through
+        let returned: Result<Result<(), PSP22Error>, PSP22Error> =
+            map_call_result(Ok(Ok(Err(PSP22Error::InsufficientBalance))));
+        assert_eq!(returned, Ok(Err(PSP22Error::InsufficientBalance)));
+    }
 }
@@ -2,7 +2,7 @@
 
 use ink::{
     prelude::{string::String, vec::Vec},
-    primitives::AccountId,
+    primitives::{AccountId, Hash},
 };
 
 use crate::errors::PSP22Error;
@@ -148,6 +148,52 @@ pub trait PSP22Metadata {
     fn token_decimals(&self) -> u8;
 }
 
+/// Trait for managing PSP22 token metadata.
+///
+/// Provides the optional `icon_uri` and `description` fields and allows the owner
+/// to update the metadata until it is locked. Every change emits a `MetadataUpdated` event.
+#[ink::trait_definition]
+pub trait PSP22MetadataManageable {
+    /// Returns the URI of the token icon.
+    #[ink(message)]
+    fn token_icon_uri(&self) -> Option<String>;
+
+    /// Returns the description of the token.
+    #[ink(message)]
+    fn token_description(&self) -> Option<String>;
+
+    /// Returns `true` if the metadata can no longer be changed.
+    #[ink(message)]
+    fn metadata_locked(&self) -> bool;
+
+    /// Replaces the name, symbol, icon URI and description of the token.
+    ///
+    /// Decimals are set once in the constructor, as changing them would change
+    /// the meaning of every balance.
+    ///
+    /// On success a `MetadataUpdated` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (Metadata is locked)` if `lock_metadata` was called.
+    #[ink(message)]
+    fn set_metadata(
+        &mut self,
+        name: Option<String>,
+        symbol: Option<String>,
+        icon_uri: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Locks the metadata permanently.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (Metadata is locked)` if the metadata is already locked.
+    #[ink(message)]
+    fn lock_metadata(&mut self) -> Result<(), PSP22Error>;
+}
+
 /// Trait for PSP22 token burning functionalities.
 ///
 /// Allows burning of tokens, reducing the total supply.
@@ -250,7 +296,9 @@ pub trait PSP22Wrapper {
     /// # Arguments
     ///
     /// * `account` - The account for which the tokens will be deposited.
-    /// * `amount` - The amount of tokens to deposit.
+    /// * `amount` - The amount of underlying tokens to deposit. If the underlying token has
+    ///   more decimals than the wrapped one, only the part convertible without a remainder
+    ///   is taken from the caller.
     ///
     /// # Returns
     ///
@@ -265,13 +313,257 @@ pub trait PSP22Wrapper {
     /// # Arguments
     ///
     /// * `account` - The account to which the tokens will be withdrawn.
-    /// * `amount` - The amount of tokens to withdraw.
+    /// * `amount` - The amount of wrapped tokens to withdraw. If the wrapped token has
+    ///   more decimals than the underlying one, only the part convertible without a remainder
+    ///   is burned from the caller.
     ///
     /// # Returns
     ///
     /// A `Result<(), PSP22Error>` indicating the success or failure of the operation.
     #[ink(message)]
     fn withdraw_to(&mut self, account: AccountId, amount: u128) -> Result<(), PSP22Error>;
+
+    /// Mints wrapped tokens for underlying tokens sent to the contract directly,
+    /// instead of through `deposit_for`.
+    ///
+    /// The minted amount is the underlying balance of the contract, converted to wrapped
+    /// token units, minus the total supply.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account receiving the minted tokens.
+    #[ink(message)]
+    fn recover(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Returns the number of decimals of the wrapped token minus the number of decimals
+    /// of the underlying token.
+    #[ink(message)]
+    fn decimals_offset(&self) -> i8;
+}
+
+/// Trait for wrapping the chain's native currency, like WETH.
+///
+/// Every token is backed by one unit of the native currency held by the contract,
+/// so the total supply never exceeds the contract's native balance.
+#[ink::trait_definition]
+pub trait PSP22NativeWrapper {
+    /// Mints tokens to the caller, 1:1 with the native currency transferred with the call.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event from `None` is emitted. No-op if nothing was transferred.
+    #[ink(message, payable)]
+    fn deposit(&mut self) -> Result<(), PSP22Error>;
+
+    /// Burns `amount` tokens of the caller and transfers the same amount
+    /// of the native currency back to the caller.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event to `None` is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the caller holds less than `amount` tokens.
+    ///
+    /// Reverts with `Custom (native transfer failed)` if the native currency cannot be transferred.
+    #[ink(message)]
+    fn withdraw(&mut self, amount: u128) -> Result<(), PSP22Error>;
+
+    /// Returns `true` if the native balance of the contract covers the total supply.
+    #[ink(message)]
+    fn is_fully_backed(&self) -> bool;
+}
+
+/// Trait for charging a fee on token transfers.
+///
+/// A fee, expressed in basis points, is taken from every `transfer` and `transfer_from`
+/// and credited to the treasury account. Transfers from or to fee-exempt accounts
+/// (and the treasury itself) are free of charge.
+#[ink::trait_definition]
+pub trait PSP22Fees {
+    /// Returns the fee charged on transfers, in basis points (1 basis point = 0.01%).
+    #[ink(message)]
+    fn fee_bps(&self) -> u16;
+
+    /// Returns the account collecting the fees.
+    #[ink(message)]
+    fn treasury(&self) -> AccountId;
+
+    /// Returns `true` if transfers from or to `account` are not charged.
+    #[ink(message)]
+    fn is_fee_exempt(&self, account: AccountId) -> bool;
+
+    /// Sets the fee charged on transfers.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (fee too high)` if `fee_bps` exceeds 10 000 basis points.
+    #[ink(message)]
+    fn set_fee(&mut self, fee_bps: u16) -> Result<(), PSP22Error>;
+
+    /// Sets the account collecting the fees.
+    #[ink(message)]
+    fn set_treasury(&mut self, treasury: AccountId) -> Result<(), PSP22Error>;
+
+    /// Exempts `account` from fees, or revokes the exemption if `exempt` is `false`.
+    #[ink(message)]
+    fn set_fee_exempt(&mut self, account: AccountId, exempt: bool) -> Result<(), PSP22Error>;
+}
+
+/// Trait for allowances limited in time.
+///
+/// An allowance granted with a deadline is treated as zero by `allowance`, `transfer_from`
+/// and every other message spending allowances once the block timestamp passes the deadline.
+/// Requires the `allowance_expiry` feature, which stores the deadlines in `PSP22Data`.
+#[ink::trait_definition]
+pub trait PSP22AllowanceExpiry {
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the total amount of `value`, until the `deadline` timestamp (inclusive).
+    ///
+    /// Successive calls of this method (and of `approve`) overwrite previous values and deadlines.
+    ///
+    /// # Events
+    ///
+    /// An `Approval` event is emitted.
+    ///
+    /// No-op if the caller and `spender` is the same address, returns success and no events are
+    /// emitted.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `Custom (deadline already passed)` if `deadline` is earlier than
+    /// the current block timestamp.
+    #[ink(message)]
+    fn approve_with_expiry(
+        &mut self,
+        spender: AccountId,
+        value: u128,
+        deadline: u64,
+    ) -> Result<(), PSP22Error>;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`
+    /// and the deadline of the allowance, if there is one.
+    ///
+    /// The amount is `0` if the deadline has passed.
+    #[ink(message)]
+    fn allowance_info(&self, owner: AccountId, spender: AccountId) -> (u128, Option<u64>);
+}
+
+/// Trait for enumerating token holders and approved spenders.
+///
+/// Requires the `enumerable` feature, which keeps the holder and spender sets
+/// in sync with balances and allowances.
+#[ink::trait_definition]
+pub trait PSP22Enumerable {
+    /// Returns at most `limit` accounts holding a non-zero balance, skipping the first `offset`.
+    ///
+    /// The order of accounts is not stable between transactions.
+    #[ink(message)]
+    fn holders(&self, offset: u32, limit: u32) -> Vec<AccountId>;
+
+    /// Returns the number of accounts holding a non-zero balance.
+    #[ink(message)]
+    fn holder_count(&self) -> u32;
+
+    /// Returns at most `limit` spenders holding a non-zero allowance from `owner`,
+    /// skipping the first `offset`.
+    ///
+    /// The order of accounts is not stable between transactions.
+    #[ink(message)]
+    fn approved_spenders(&self, owner: AccountId, offset: u32, limit: u32) -> Vec<AccountId>;
+}
+
+/// Trait for sending tokens to many recipients in a single transaction.
+#[ink::trait_definition]
+pub trait PSP22Batch {
+    /// Transfers the given amounts of tokens from the caller's account to every recipient
+    /// in `transfers`.
+    ///
+    /// The batch is atomic, either all of the transfers succeed or none of them.
+    ///
+    /// # Events
+    ///
+    /// A `Transfer` event is emitted for every transfer. Transfers of zero tokens and
+    /// transfers to the caller are skipped and no events are emitted for them.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientBalance` if the total of `transfers` exceeds the caller's balance.
+    ///
+    /// Reverts with `Custom` error if the total of `transfers` exceeds u128.MAX.
+    #[ink(message)]
+    fn batch_transfer(&mut self, transfers: Vec<(AccountId, u128)>) -> Result<(), PSP22Error>;
+
+    /// Transfers the given amounts of tokens from `from` to every recipient in `transfers`,
+    /// using the allowance granted by `from` to the caller.
+    ///
+    /// The batch is atomic, either all of the transfers succeed or none of them.
+    ///
+    /// # Events
+    ///
+    /// A single `Approval` event followed by a `Transfer` event for every transfer.
+    /// Transfers of zero tokens and transfers to `from` are skipped and no events are emitted for them.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with `InsufficientAllowance` if the total of `transfers` exceeds the allowance
+    /// granted by `from` to the caller, including a total above u128.MAX.
+    ///
+    /// Reverts with `InsufficientBalance` if the total of `transfers` is within the allowance
+    /// but exceeds the balance of `from`.
+    #[ink(message)]
+    fn batch_transfer_from(
+        &mut self,
+        from: AccountId,
+        transfers: Vec<(AccountId, u128)>,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// Trait for upgrading the code of a deployed token.
+///
+/// The code is replaced with `set_code_hash`, the storage is kept. The storage layout
+/// version is stored next to the token data, so that the new code converts the storage
+/// of older versions once, on the first call changing the token, and refuses to operate on newer ones.
+#[ink::trait_definition]
+pub trait PSP22Upgradeable {
+    /// Replaces the code of the contract with the code uploaded under `code_hash`.
+    ///
+    /// The new code is used from the next call on. It migrates the storage on the first
+    /// transfer, mint, burn or approval, or when `migrate` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_hash` - The hash of the new code, which must be uploaded already.
+    /// * `target_version` - The storage version of the new code.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    /// Fails with `Custom (Downgrade refused)` if `target_version` is older than the storage
+    /// version of the current code, and with `Custom (Code hash not found)` if no code was
+    /// uploaded under `code_hash`.
+    #[ink(message)]
+    fn set_code_hash(&mut self, code_hash: Hash, target_version: u32) -> Result<(), PSP22Error>;
+
+    /// Returns the version of the layout of the stored data.
+    ///
+    /// # Returns
+    ///
+    /// The storage version, `1` for the layout of the first release.
+    #[ink(message)]
+    fn storage_version(&self) -> u32;
+
+    /// Converts the stored data to the layout of the current code after an upgrade,
+    /// before the first call changing the token would.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), PSP22Error>` indicating whether the operation was successful.
+    /// Fails with `Custom (Already migrated)` if the storage already has the current layout
+    /// and with `Custom (Downgrade refused)` if it has the layout of a newer code.
+    #[ink(message)]
+    fn migrate(&mut self) -> Result<(), PSP22Error>;
 }
 
 /// Trait for ownership-related functionalities.
@@ -0,0 +1,86 @@
+//! Typed client for calling other PSP34 contracts.
+
+use ink::codegen::TraitCallBuilder;
+use ink::env::call::{Call, CallParams};
+use ink::env::{DefaultEnvironment, Error as EnvError};
+use ink::prelude::{format, vec::Vec};
+use ink::primitives::{AccountId, LangError, MessageResult};
+use scale::{Decode, Encode};
+
+use crate::{Id, PSP34Error, PSP34Metadata, PSP34};
+
+/// Performs the calls built by `PSP34Ref`.
+pub trait Invoke {
+    /// Calls the contract with `params`, returning the output of the called message.
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError>;
+}
+
+/// Calls the contracts deployed on chain.
+pub struct OnChain;
+
+impl Invoke for OnChain {
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError> {
+        params.try_invoke()
+    }
+}
+
+// The off-chain environment of ink! cannot call other contracts, so with the `mock` feature
+// the calls are answered by the in-memory collections of `mock` instead
+#[cfg(not(feature = "mock"))]
+type Backend = OnChain;
+#[cfg(feature = "mock")]
+type Backend = crate::mock::Mock;
+
+/// Reference to a PSP34 collection deployed at `account`.
+///
+/// Calls are built from the `PSP34` and `PSP34Metadata` trait definitions, so their
+/// selectors and argument types always match the traits. Failures of the call itself
+/// (the callee trapped, could not be found or did not understand the message) are
+/// returned as `PSP34Error::Custom` instead of trapping the caller.
+pub struct PSP34Ref {
+    collection: ink::contract_ref!(PSP34, DefaultEnvironment),
+    metadata: ink::contract_ref!(PSP34Metadata, DefaultEnvironment),
+}
+
+impl PSP34Ref {
+    /// Creates a reference to the collection at `account`, with calls allowed
+    /// to use all the remaining gas.
+    pub fn new(account: AccountId) -> PSP34Ref {
+        PSP34Ref {
+            collection: account.into(),
+            metadata: account.into(),
+        }
+    }
+
+    /// Calls `PSP34::owner_of` of the collection.
+    pub fn owner_of(&mut self, id: Id) -> Result<Option<AccountId>, PSP34Error> {
+        let params = self.collection.call_mut().owner_of(id).params();
+        map_call_result(Backend::invoke(&params))
+    }
+
+    /// Calls `PSP34::transfer` of the collection.
+    pub fn transfer(&mut self, to: AccountId, id: Id, data: Vec<u8>) -> Result<(), PSP34Error> {
+        let params = self.collection.call_mut().transfer(to, id, data).params();
+        map_call_result(Backend::invoke(&params))?
+    }
+
+    /// Calls `PSP34Metadata::get_attribute` of the collection, which must implement it.
+    pub fn get_attribute(&mut self, id: Id, key: Vec<u8>) -> Result<Option<Vec<u8>>, PSP34Error> {
+        let params = self.metadata.call_mut().get_attribute(id, key).params();
+        map_call_result(Backend::invoke(&params))
+    }
+}
+
+/// Converts errors of a cross-contract call into `PSP34Error::Custom`, returning
+/// the decoded output of the called message otherwise.
+pub fn map_call_result<R>(result: Result<Result<R, LangError>, EnvError>) -> Result<R, PSP34Error> {
+    match result {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(error)) => Err(PSP34Error::Custom(format!("Call could not be dispatched: {:?}", error))),
+        Err(error) => Err(PSP34Error::Custom(format!("Call failed: {:?}", error))),
+    }
+}
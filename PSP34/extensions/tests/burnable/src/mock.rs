@@ -0,0 +1,133 @@
+//! In-memory PSP34 collections answering the calls of `PSP34Ref` in unit tests.
+//!
+//! The off-chain environment of ink! cannot call other contracts, so with the `mock` feature
+//! `PSP34Ref` hands the calls it builds to [`Mock`]. The selector and the arguments of a call
+//! are decoded like a deployed collection would, the message is executed against the
+//! `PSP34Data<InMemory>` of the collection deployed at the callee, with metadata attributes
+//! next to it, and its encoded output is decoded by the caller. Calls are made on behalf of
+//! the contract under test.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use ink::env::call::{Call, CallParams};
+use ink::env::hash::{Blake2x256, HashOutput};
+use ink::env::{DefaultEnvironment, Error as EnvError};
+use ink::prelude::vec::Vec;
+use ink::primitives::{AccountId, LangError, MessageResult};
+use scale::{Decode, Encode};
+
+use crate::client::Invoke;
+use crate::data::Data;
+use crate::storage::InMemory;
+use crate::{Id, PSP34Data};
+
+#[derive(Default)]
+struct Collection {
+    data: PSP34Data<InMemory>,
+    metadata: Data<InMemory>,
+    trapping: bool,
+}
+
+thread_local! {
+    static COLLECTIONS: RefCell<BTreeMap<AccountId, Collection>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+fn with_collection<R>(address: AccountId, f: impl FnOnce(&mut Collection) -> R) -> R {
+    COLLECTIONS.with(|collections| {
+        f(collections
+            .borrow_mut()
+            .get_mut(&address)
+            .expect("Collection not deployed"))
+    })
+}
+
+/// Deploys an empty collection at `address`.
+pub fn deploy(address: AccountId) {
+    COLLECTIONS.with(|collections| collections.borrow_mut().insert(address, Collection::default()));
+}
+
+/// Makes every call to the collection at `address` trap, or stop trapping.
+pub fn set_trapping(address: AccountId, trapping: bool) {
+    with_collection(address, |collection| collection.trapping = trapping);
+}
+
+/// Mints the token `id` of the collection at `address` to `account`.
+pub fn mint(address: AccountId, account: AccountId, id: Id) {
+    with_collection(address, |collection| collection.data.mint(account, id)).expect("Mint failed");
+}
+
+/// Sets the metadata attribute `key` of the token `id` of the collection at `address`.
+pub fn set_attribute(address: AccountId, id: Id, key: &[u8], value: &[u8]) {
+    with_collection(address, |collection| {
+        collection.metadata.set_attribute(id, key.to_vec(), value.to_vec())
+    })
+    .expect("Setting attribute failed");
+}
+
+/// Approves `operator` to transfer the token `id` of `owner` in the collection at `address`.
+pub fn approve(address: AccountId, owner: AccountId, operator: AccountId, id: Id) {
+    with_collection(address, |collection| collection.data.approve(owner, operator, Some(id), true))
+        .expect("Approval failed");
+}
+
+/// Approves `operator` to transfer all tokens of `owner` in the collection at `address`.
+pub fn approve_all(address: AccountId, owner: AccountId, operator: AccountId) {
+    with_collection(address, |collection| collection.data.approve(owner, operator, None, true))
+        .expect("Approval failed");
+}
+
+/// Transfers the token `id` to `to` on behalf of `caller` in the collection at `address`.
+pub fn transfer(address: AccountId, caller: AccountId, to: AccountId, id: Id) {
+    with_collection(address, |collection| collection.data.transfer(caller, to, id, Vec::new()))
+        .expect("Transfer failed");
+}
+
+/// Returns the owner of the token `id` of the collection at `address`.
+pub fn owner_of(address: AccountId, id: Id) -> Option<AccountId> {
+    with_collection(address, |collection| collection.data.owner_of(&id))
+}
+
+impl Collection {
+    /// Executes the message with `selector`, returning its encoded `MessageResult`.
+    fn call(&mut self, caller: AccountId, selector: &[u8], args: &mut &[u8]) -> Result<Vec<u8>, EnvError> {
+        if self.trapping {
+            return Err(EnvError::CalleeTrapped);
+        }
+        let output = if selector == self::selector("PSP34::owner_of") {
+            let id = Id::decode(args)?;
+            Ok::<_, LangError>(self.data.owner_of(&id)).encode()
+        } else if selector == self::selector("PSP34::transfer") {
+            let (to, id, data) = <(AccountId, Id, Vec<u8>)>::decode(args)?;
+            Ok::<_, LangError>(self.data.transfer(caller, to, id, data).map(|_| ())).encode()
+        } else if selector == self::selector("PSP34Metadata::get_attribute") {
+            let (id, key) = <(Id, Vec<u8>)>::decode(args)?;
+            Ok::<_, LangError>(self.metadata.get_attribute(id, key)).encode()
+        } else {
+            Err::<(), _>(LangError::CouldNotReadInput).encode()
+        };
+        Ok(output)
+    }
+}
+
+/// Returns the selector of `message`, given as `<Trait>::<message>`, the way ink! computes it.
+fn selector(message: &str) -> [u8; 4] {
+    let mut hash = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(message.as_bytes(), &mut hash);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Answers the calls of `PSP34Ref` with the collections deployed by [`deploy`].
+pub struct Mock;
+
+impl Invoke for Mock {
+    fn invoke<Args: Encode, R: Decode>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> Result<MessageResult<R>, EnvError> {
+        let caller = ink::env::account_id::<DefaultEnvironment>();
+        let input = params.exec_input().encode();
+        let (selector, mut args) = input.split_at(4);
+        let output = with_collection(*params.callee(), |collection| collection.call(caller, selector, &mut args))?;
+        Ok(MessageResult::<R>::decode(&mut &output[..])?)
+    }
+}
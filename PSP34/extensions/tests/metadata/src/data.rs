@@ -1,12 +1,12 @@
 use ink::{
     prelude::{string::String, vec, vec::Vec},
     primitives::AccountId,
-    storage::Mapping,
 };
 #[cfg(feature = "std")]
 use ink::storage::traits::StorageLayout;
 
 use crate::data::balance_manager::Balances;
+use crate::storage::{Map, OnChain, StorageBackend};
 use crate::PSP34Error;
 
 /// Type for a PSP34 token id.
@@ -22,10 +22,9 @@ pub enum Id {
     Bytes(Vec<u8>),
 }
 
-/// Temporary type for events emitted during operations that change the
-/// state of PSP34Data struct.
-/// This is meant to be replaced with proper ink! events as soon as the
-/// language allows for event definitions outside contracts.
+/// Events emitted during operations that change the state of PSP34Data struct.
+/// The contract emits them as its `Transfer`, `Approval` and `AttributeSet` events, see `events.rs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PSP34Event {
     Transfer {
         from: Option<AccountId>,
@@ -57,18 +56,21 @@ pub enum PSP34Event {
 /// (compared to transactions defined by the PSP34 standard or the PSP34 trait).
 //
 /// `lib.rs` contains an example implementation of a smart contract using this class.
+///
+/// The state is kept by the storage backend `B`, the contract storage by default.
+/// `PSP34Data<InMemory>` runs the same code in plain Rust, see `storage.rs`.
 #[ink::storage_item]
 #[derive(Debug, Default)]
-pub struct PSP34Data {
-    token_owner: Mapping<Id, AccountId>,
-    operator_approvals: Mapping<(AccountId, AccountId, Option<Id>), ()>,
-    balance: Balances,
+pub struct PSP34Data<B: StorageBackend = OnChain> {
+    token_owner: Map<Id, AccountId, B>,
+    operator_approvals: Map<(AccountId, AccountId, Option<Id>), (), B>,
+    balance: Balances<B>,
 }
 
-impl PSP34Data {
+impl<B: StorageBackend> PSP34Data<B> {
     /// Creates a token with default values for every field.
     /// Initially held by the 'creator' account.
-    pub fn new() -> PSP34Data {
+    pub fn new() -> PSP34Data<B> {
         Default::default()
     }
 
@@ -167,7 +169,7 @@ impl PSP34Data {
         self.balance.increase_balance(&to, &id, false)?;
 
         Ok(vec![PSP34Event::Transfer {
-            from: Some(caller),
+            from: Some(owner),
             to: Some(to),
             id,
         }])
@@ -195,13 +197,15 @@ impl PSP34Data {
         account: AccountId,
         id: Id,
     ) -> Result<Vec<PSP34Event>, PSP34Error> {
-        if self.owner_of(&id).is_none() {
+        // A token held by another account does not exist in the `account` collection
+        if self.owner_of(&id) != Some(account) {
             return Err(PSP34Error::TokenNotExists);
         }
-        if account != caller && !self.allowance(caller, account, None) {
+        if account != caller && !self.allowance(account, caller, Some(&id)) {
             return Err(PSP34Error::NotApproved);
         }
         self.balance.decrease_balance(&account, &id, true);
+        self.operator_approvals.remove((account, caller, Some(&id)));
         self.token_owner.remove(&id);
 
         Ok(vec![PSP34Event::Transfer {
@@ -218,6 +222,13 @@ impl PSP34Data {
     pub fn token_by_index(&self, index: u128) -> Result<Id, PSP34Error> {
         self.balance.token_by_index(index)
     }
+
+    /// Returns `true` if a list of tokens owned by `owner` is kept in the storage.
+    #[cfg(any(test, feature = "fuzz"))]
+    #[allow(dead_code)] // Only `model` calls it, and generated crates skip `model`
+    pub(crate) fn stores_balance(&self, owner: AccountId) -> bool {
+        self.balance.stores_balance(&owner)
+    }
 }
 
 impl Default for Id {
@@ -243,18 +254,19 @@ impl From<Id> for u128 {
 
 
 pub mod balance_manager {
-    use ink::{prelude::vec::Vec, primitives::AccountId, storage::Mapping};
+    use ink::{prelude::vec::Vec, primitives::AccountId};
 
+    use crate::storage::{Map, OnChain, StorageBackend};
     use crate::{data::Id, PSP34Error};
 
     #[ink::storage_item]
     #[derive(Default, Debug)]
-    pub struct Balances {
-        enumerable: Mapping<Option<AccountId>, Vec<Id>>,
+    pub struct Balances<B: StorageBackend = OnChain> {
+        enumerable: Map<Option<AccountId>, Vec<Id>, B>,
     }
 
-    impl Balances {
-        pub fn new() -> Balances {
+    impl<B: StorageBackend> Balances<B> {
+        pub fn new() -> Balances<B> {
             Default::default()
         }
 
@@ -336,16 +348,22 @@ pub mod balance_manager {
         pub fn total_supply(&self) -> u128 {
             self._count(&None)
         }
+
+        #[cfg(any(test, feature = "fuzz"))]
+        #[allow(dead_code)] // Only `model` calls it, and generated crates skip `model`
+        pub(crate) fn stores_balance(&self, owner: &AccountId) -> bool {
+            self.enumerable.contains(Some(owner))
+        }
     }
 }
 
 #[ink::storage_item]
 #[derive(Default, Debug)]
-pub struct Data {
-    attributes: Mapping<(Id, Vec<u8>), Vec<u8>>,
+pub struct Data<B: StorageBackend = OnChain> {
+    attributes: Map<(Id, Vec<u8>), Vec<u8>, B>,
 }
 
-impl Data {
+impl<B: StorageBackend> Data<B> {
     pub fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
         self.attributes.get((&id, &key))
     }
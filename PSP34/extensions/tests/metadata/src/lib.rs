@@ -1,33 +1,34 @@
-
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
-pub use data::{Id, PSP34Data, PSP34Event};
+pub use client::PSP34Ref;
+pub use data::{balance_manager, Id, PSP34Data, PSP34Event};
 pub use errors::PSP34Error;
 pub use traits::{PSP34, PSP34Burnable, PSP34Enumerable, PSP34Metadata, PSP34Mintable};
+pub mod client;
 mod data;
 mod errors;
+pub mod events;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod storage;
 mod traits;
-mod unit_tests;
 mod test_utils;
-
 #[cfg(feature = "contract")]
 #[ink::contract]
 pub mod token {
-    use crate::traits::Ownable;
-    use crate::errors::OwnableError;
-    use crate::traits::PSP34Mintable;
-    use crate::PSP34Metadata;
     use ink::prelude::string::ToString;
     use ink::prelude::vec::Vec;
-    use crate::{Id, PSP34, PSP34Data, PSP34Error, PSP34Event};
+    use crate::{Id, PSP34, PSP34Data, PSP34Error};
     use crate::data::Data;
-
+    use crate::PSP34Metadata;
+    use crate::traits::PSP34Mintable;
+    use crate::errors::OwnableError;
+    use crate::traits::Ownable;
     #[ink(storage)]
     pub struct Token {
         pub data: PSP34Data,
         metadata: Data,
         pub owner: Option<AccountId>,
     }
-
     impl Token {
         #[ink(constructor)]
         pub fn new() -> Self {
@@ -37,30 +38,43 @@ pub mod token {
                 owner: Some(Self::env().caller()),
             }
         }
-
-        fn emit_events(&self, events: ink::prelude::vec::Vec<PSP34Event>) {
-            for event in events {
-                match event {
-                    PSP34Event::Approval { owner, operator, id, approved } => {
-                        self.env()
-                            .emit_event(Approval {
-                                owner,
-                                operator,
-                                id,
-                                approved,
-                            })
-                    }
-                    PSP34Event::Transfer { from, to, id } => {
-                        self.env().emit_event(Transfer { from, to, id })
-                    }
-                    PSP34Event::AttributeSet { id, key, data } => {
-                        self.env().emit_event(AttributeSet { id, key, data })
-                    }
-                }
-            }
+        #[allow(dead_code, unused_variables)]
+        fn before_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: &Id,
+        ) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+        #[allow(dead_code, unused_variables)]
+        fn after_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: &Id,
+        ) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+        #[allow(dead_code, unused_variables)]
+        fn before_mint(&mut self, to: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+        #[allow(dead_code, unused_variables)]
+        fn before_burn(&mut self, from: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+        #[allow(dead_code, unused_variables)]
+        fn before_approve(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<&Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            Ok(())
         }
     }
-
     #[ink(event)]
     pub struct Approval {
         #[ink(topic)]
@@ -71,7 +85,6 @@ pub mod token {
         id: Option<Id>,
         approved: bool,
     }
-
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
@@ -81,30 +94,26 @@ pub mod token {
         #[ink(topic)]
         id: Id,
     }
-
     #[ink(event)]
     pub struct AttributeSet {
         id: Id,
         key: Vec<u8>,
         data: Vec<u8>,
     }
-
+    crate::psp34_events!(Token);
     impl PSP34 for Token {
         #[ink(message)]
         fn collection_id(&self) -> Id {
             self.data.collection_id(self.env().account_id())
         }
-
         #[ink(message)]
         fn total_supply(&self) -> u128 {
             self.data.total_supply()
         }
-
         #[ink(message)]
         fn balance_of(&self, owner: AccountId) -> u32 {
             self.data.balance_of(owner)
         }
-
         #[ink(message)]
         fn allowance(
             &self,
@@ -114,7 +123,6 @@ pub mod token {
         ) -> bool {
             self.data.allowance(owner, operator, id.as_ref())
         }
-
         #[ink(message)]
         fn transfer(
             &mut self,
@@ -122,11 +130,13 @@ pub mod token {
             id: Id,
             data: ink::prelude::vec::Vec<u8>,
         ) -> Result<(), PSP34Error> {
-            let events = self.data.transfer(self.env().caller(), to, id, data)?;
+            let from = self.data.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+            self.before_transfer(from, to, &id)?;
+            let events = self.data.transfer(self.env().caller(), to, id.clone(), data)?;
             self.emit_events(events);
+            self.after_transfer(from, to, &id)?;
             Ok(())
         }
-
         #[ink(message)]
         fn approve(
             &mut self,
@@ -134,24 +144,23 @@ pub mod token {
             id: Option<Id>,
             approved: bool,
         ) -> Result<(), PSP34Error> {
-            let events = self.data.approve(self.env().caller(), operator, id, approved)?;
+            let caller = self.env().caller();
+            self.before_approve(caller, operator, id.as_ref(), approved)?;
+            let events = self.data.approve(caller, operator, id, approved)?;
             self.emit_events(events);
             Ok(())
         }
-
         #[ink(message)]
         fn owner_of(&self, id: Id) -> Option<AccountId> {
             self.data.owner_of(&id)
         }
     }
-
     impl PSP34Metadata for Token {
         #[ink(message)]
         fn get_attribute(&self, id: Id, key: Vec<u8>) -> Option<Vec<u8>> {
             self.metadata.get_attribute(id, key)
         }
     }
-
     impl PSP34Mintable for Token {
         #[ink(message)]
         fn mint(&mut self, id: Id) -> Result<(), PSP34Error> {
@@ -159,18 +168,17 @@ pub mod token {
             if self.owner != Some(caller) {
                 return Err(PSP34Error::Custom(OwnableError::NotAnOwner.to_string()));
             }
+            self.before_mint(caller, &id)?;
             let events = self.data.mint(caller, id)?;
             self.emit_events(events);
             Ok(())
         }
     }
-
     impl Ownable for Token {
         #[ink(message)]
         fn owner(&self) -> Option<AccountId> {
             self.owner
         }
-
         #[ink(message)]
         fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
             assert_eq!(
@@ -180,7 +188,6 @@ pub mod token {
             self.owner = None;
             Ok(())
         }
-
         #[ink(message)]
         fn transfer_ownership(
             &mut self,
@@ -194,4 +201,4 @@ pub mod token {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
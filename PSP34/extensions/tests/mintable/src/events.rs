@@ -0,0 +1,273 @@
+//! PSP34 events emitted by contracts embedding `PSP34Data`.
+//!
+//! ink! 4 only supports events declared inside a contract, so every contract declares
+//! the `Transfer`, `Approval` and `AttributeSet` events itself, see `lib.rs`, with these
+//! fields in this order:
+//!
+//! - `Transfer { #[ink(topic)] from: Option<AccountId>, #[ink(topic)] to: Option<AccountId>, #[ink(topic)] id: Id }`
+//! - `Approval { #[ink(topic)] owner: AccountId, #[ink(topic)] operator: AccountId, #[ink(topic)] id: Option<Id>, approved: bool }`
+//! - `AttributeSet { id: Id, key: Vec<u8>, data: Vec<u8> }`
+//!
+//! and generates the method emitting them with [`psp34_events!`](crate::psp34_events).
+//! The payload of an event is the one ink! would emit (the index of the event among the events
+//! of the contract followed by its fields), so it is decoded from the contract metadata as usual.
+//! The topics however do not depend on the name of the contract: the signature topic is
+//! `PSP34::Transfer`, `PSP34::Approval` or `PSP34::AttributeSet`, followed by a topic for
+//! every indexed field prefixed with `PSP34::<Event>::<field>`.
+
+use ink::env::hash::Blake2x256;
+use ink::env::topics::{state, PrefixedValue, TopicsBuilder, TopicsBuilderBackend};
+use ink::env::{Environment, Topics};
+use ink::prelude::format;
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use scale::{DecodeAll, Encode, Output};
+
+use crate::{Id, PSP34Event};
+
+/// Signature of the `Transfer` event.
+pub const TRANSFER: &[u8; 15] = b"PSP34::Transfer";
+
+/// Signature of the `Approval` event.
+pub const APPROVAL: &[u8; 15] = b"PSP34::Approval";
+
+/// Signature of the `AttributeSet` event.
+pub const ATTRIBUTE_SET: &[u8; 19] = b"PSP34::AttributeSet";
+
+/// Generates `fn emit_events(&self, events: Vec<PSP34Event>)` of the `$contract` storage struct.
+///
+/// Invoked inside the `#[ink::contract]` module, after the `Transfer`, `Approval` and
+/// `AttributeSet` events described in [`events`](crate::events). The events are emitted
+/// with the PSP34 topics. Compilation fails if one of them lost or gained an `#[ink(topic)]` attribute.
+#[macro_export]
+macro_rules! psp34_events {
+    ($contract:ident) => {
+        // Never called, only type-checked against the topics of the events
+        #[allow(dead_code)]
+        const _: () = {
+            fn transfer_topics(
+                topics: <Transfer as ::ink::codegen::EventLenTopics>::LenTopics,
+            ) -> ::ink::codegen::EventTopics<3> {
+                topics
+            }
+            fn approval_topics(
+                topics: <Approval as ::ink::codegen::EventLenTopics>::LenTopics,
+            ) -> ::ink::codegen::EventTopics<3> {
+                topics
+            }
+            fn attribute_set_topics(
+                topics: <AttributeSet as ::ink::codegen::EventLenTopics>::LenTopics,
+            ) -> ::ink::codegen::EventTopics<0> {
+                topics
+            }
+        };
+
+        impl $contract {
+            /// Emits `events` returned by the methods of `PSP34Data`.
+            fn emit_events(&self, events: ::ink::prelude::vec::Vec<$crate::PSP34Event>) {
+                type Event = <$contract as ::ink::reflect::ContractEventBase>::Type;
+                type Env = <$contract as ::ink::env::ContractEnv>::Env;
+                for event in events {
+                    match event {
+                        $crate::PSP34Event::Approval {
+                            owner,
+                            operator,
+                            id,
+                            approved,
+                        } => ::ink::env::emit_event::<Env, _>($crate::events::ApprovalTopics {
+                            owner,
+                            operator,
+                            id: id.clone(),
+                            event: <Event as ::core::convert::From<_>>::from(Approval {
+                                owner,
+                                operator,
+                                id,
+                                approved,
+                            }),
+                        }),
+                        $crate::PSP34Event::Transfer { from, to, id } => {
+                            ::ink::env::emit_event::<Env, _>($crate::events::TransferTopics {
+                                from,
+                                to,
+                                id: id.clone(),
+                                event: <Event as ::core::convert::From<_>>::from(Transfer { from, to, id }),
+                            })
+                        }
+                        $crate::PSP34Event::AttributeSet { id, key, data } => {
+                            ::ink::env::emit_event::<Env, _>($crate::events::AttributeSetTopics {
+                                event: <Event as ::core::convert::From<_>>::from(AttributeSet { id, key, data }),
+                            })
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// A `Transfer` `event` of a contract, emitted with the PSP34 topics by `psp34_events!`.
+#[doc(hidden)]
+pub struct TransferTopics<T> {
+    pub from: Option<AccountId>,
+    pub to: Option<AccountId>,
+    pub id: Id,
+    pub event: T,
+}
+
+impl<T: Encode> Encode for TransferTopics<T> {
+    fn size_hint(&self) -> usize {
+        self.event.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.event.encode_to(dest)
+    }
+}
+
+impl<T> Topics for TransferTopics<T> {
+    type RemainingTopics = [state::HasRemainingTopics; 4];
+
+    fn topics<E, B>(
+        &self,
+        builder: TopicsBuilder<state::Uninit, E, B>,
+    ) -> <B as TopicsBuilderBackend<E>>::Output
+    where
+        E: Environment,
+        B: TopicsBuilderBackend<E>,
+    {
+        builder
+            .build::<Self>()
+            .push_topic(&PrefixedValue { prefix: b"", value: TRANSFER })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Transfer::from", value: &self.from })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Transfer::to", value: &self.to })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Transfer::id", value: &self.id })
+            .finish()
+    }
+}
+
+/// An `Approval` `event` of a contract, emitted with the PSP34 topics by `psp34_events!`.
+#[doc(hidden)]
+pub struct ApprovalTopics<T> {
+    pub owner: AccountId,
+    pub operator: AccountId,
+    pub id: Option<Id>,
+    pub event: T,
+}
+
+impl<T: Encode> Encode for ApprovalTopics<T> {
+    fn size_hint(&self) -> usize {
+        self.event.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.event.encode_to(dest)
+    }
+}
+
+impl<T> Topics for ApprovalTopics<T> {
+    type RemainingTopics = [state::HasRemainingTopics; 4];
+
+    fn topics<E, B>(
+        &self,
+        builder: TopicsBuilder<state::Uninit, E, B>,
+    ) -> <B as TopicsBuilderBackend<E>>::Output
+    where
+        E: Environment,
+        B: TopicsBuilderBackend<E>,
+    {
+        builder
+            .build::<Self>()
+            .push_topic(&PrefixedValue { prefix: b"", value: APPROVAL })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Approval::owner", value: &self.owner })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Approval::operator", value: &self.operator })
+            .push_topic(&PrefixedValue { prefix: b"PSP34::Approval::id", value: &self.id })
+            .finish()
+    }
+}
+
+/// An `AttributeSet` `event` of a contract, emitted with the PSP34 topic by `psp34_events!`.
+#[doc(hidden)]
+pub struct AttributeSetTopics<T> {
+    pub event: T,
+}
+
+impl<T: Encode> Encode for AttributeSetTopics<T> {
+    fn size_hint(&self) -> usize {
+        self.event.size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.event.encode_to(dest)
+    }
+}
+
+impl<T> Topics for AttributeSetTopics<T> {
+    type RemainingTopics = [state::HasRemainingTopics; 1];
+
+    fn topics<E, B>(
+        &self,
+        builder: TopicsBuilder<state::Uninit, E, B>,
+    ) -> <B as TopicsBuilderBackend<E>>::Output
+    where
+        E: Environment,
+        B: TopicsBuilderBackend<E>,
+    {
+        builder
+            .build::<Self>()
+            .push_topic(&PrefixedValue { prefix: b"", value: ATTRIBUTE_SET })
+            .finish()
+    }
+}
+
+/// Returns the signature topic of `event`, e.g. `signature_topic("Transfer")`.
+pub fn signature_topic(event: &str) -> [u8; 32] {
+    // The signature is encoded as a byte array, without a length prefix
+    let mut encoded = b"".as_slice().encode();
+    encoded.extend_from_slice(format!("PSP34::{event}").as_bytes());
+    padded_or_hashed(&encoded)
+}
+
+/// Returns the topic of the indexed `field` of `event` with `value`,
+/// e.g. `field_topic("Transfer", "id", &id)` for transfers of token `id`.
+pub fn field_topic<T: Encode>(event: &str, field: &str, value: &T) -> [u8; 32] {
+    let prefix = format!("PSP34::{event}::{field}");
+    padded_or_hashed(&PrefixedValue { prefix: prefix.as_bytes(), value }.encode())
+}
+
+/// Decodes a `Transfer`, `Approval` or `AttributeSet` event from its first topic and its payload.
+///
+/// Returns `None` for events of other kinds or malformed payloads.
+pub fn decode_event(signature: &[u8], data: &[u8]) -> Option<PSP34Event> {
+    // The payload starts with the index of the event among the events of the contract
+    let fields = &mut data.get(1..)?;
+    if signature == signature_topic("Transfer") {
+        let (from, to, id) = <(Option<AccountId>, Option<AccountId>, Id)>::decode_all(fields).ok()?;
+        Some(PSP34Event::Transfer { from, to, id })
+    } else if signature == signature_topic("Approval") {
+        let (owner, operator, id, approved) =
+            <(AccountId, AccountId, Option<Id>, bool)>::decode_all(fields).ok()?;
+        Some(PSP34Event::Approval {
+            owner,
+            operator,
+            id,
+            approved,
+        })
+    } else if signature == signature_topic("AttributeSet") {
+        let (id, key, data) = <(Id, Vec<u8>, Vec<u8>)>::decode_all(fields).ok()?;
+        Some(PSP34Event::AttributeSet { id, key, data })
+    } else {
+        None
+    }
+}
+
+/// Computes a topic from its encoding the way the contracts environment does:
+/// encodings up to 32 bytes are zero-padded, longer ones are hashed with Blake2x256.
+fn padded_or_hashed(encoded: &[u8]) -> [u8; 32] {
+    let mut topic = [0u8; 32];
+    if encoded.len() <= topic.len() {
+        topic[..encoded.len()].copy_from_slice(encoded);
+    } else {
+        ink::env::hash_bytes::<Blake2x256>(encoded, &mut topic);
+    }
+    topic
+}
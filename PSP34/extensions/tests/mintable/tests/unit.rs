@@ -58,4 +58,41 @@ mod tests {
         //Balance didn't change
         assert_eq!(token.total_supply(), 0);
     }
-}
\ No newline at end of file
+
+    #[ink::test]
+    fn transfer_events_are_indexed_by_topics() {
+        use ink::env::test::recorded_events;
+        use psp34_mintable_tests::events::{decode_event, field_topic, signature_topic};
+        use psp34_mintable_tests::PSP34Event;
+
+        let owner = AccountId::from([0x2; 32]);
+        let operator = AccountId::from([0x3; 32]);
+        let bob = AccountId::from([0x4; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut token = Token::new();
+        token.mint(Id::U8(0)).expect("Success expected");
+        token.approve(operator, Some(Id::U8(0)), true).expect("Success expected");
+
+        set_caller::<DefaultEnvironment>(operator);
+        token.transfer(bob, Id::U8(0), vec![]).expect("Success expected");
+
+        let events: Vec<_> = recorded_events().collect();
+        assert_eq!(events.len(), 3);
+        let transfer = &events[2];
+        assert_eq!(
+            transfer.topics,
+            [
+                signature_topic("Transfer"),
+                field_topic("Transfer", "from", &Some(owner)),
+                field_topic("Transfer", "to", &Some(bob)),
+                field_topic("Transfer", "id", &Id::U8(0)),
+            ]
+            .map(Vec::from)
+        );
+        // The token is transferred from its owner, not from the operator
+        assert_eq!(
+            decode_event(&transfer.topics[0], &transfer.data),
+            Some(PSP34Event::Transfer { from: Some(owner), to: Some(bob), id: Id::U8(0) })
+        );
+    }
+}
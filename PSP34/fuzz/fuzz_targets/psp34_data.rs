@@ -0,0 +1,6 @@
+//! Applies operations decoded from the fuzzer input to `PSP34Data`, checking it against the model.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| psp34_full::model::run(bytes));
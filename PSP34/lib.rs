@@ -1,11 +1,19 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-pub use data::{Id, PSP34Data, PSP34Event};
+pub use client::PSP34Ref;
+pub use data::{balance_manager, Id, PSP34Data, PSP34Event};
 pub use errors::PSP34Error;
 pub use traits::{PSP34, PSP34Burnable, PSP34Enumerable, PSP34Metadata, PSP34Mintable};
 
+pub mod client;
 mod data;
 mod errors;
+pub mod events;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(any(test, feature = "fuzz"))]
+pub mod model;
+pub mod storage;
 mod traits;
 mod unit_tests;
 mod test_utils;
@@ -16,9 +24,9 @@ mod test_utils;
 // Any contract can be easily enriched to act as PSP34 token by:
 // (1) adding PSP34Data to contract storage
 // (2) properly initializing it
-// (3) defining the correct AttributeSet, Transfer and Approval events
+// (3) defining the correct AttributeSet, Transfer and Approval events, see `events.rs`
 // (4) implementing PSP34 trait based on PSP34Data methods
-// (5) properly emitting resulting events
+// (5) properly emitting resulting events, with `emit_events` generated by `psp34_events!`
 //
 // Implemented the optional PSP34Mintable (6), PSP34Burnable (7), and PSP34Metadata (8) extensions
 // and included unit tests (8).
@@ -29,7 +37,7 @@ pub mod token {
     use ink::prelude::string::ToString;
     use ink::prelude::vec::Vec;
 
-    use crate::{Id, PSP34, PSP34Data, PSP34Error, PSP34Event};
+    use crate::{Id, PSP34, PSP34Data, PSP34Error};
     use crate::data::Data;
 
     #[ink(storage)]
@@ -45,31 +53,37 @@ pub mod token {
             }
         }
 
-        // A helper function translating a vector of PSP34Events into the proper
-        // ink event types (defined internally in this contract) and emitting them.
-        // (5)
-        fn emit_events(&self, events: ink::prelude::vec::Vec<PSP34Event>) {
-            for event in events {
-                match event {
-                    PSP34Event::Approval {
-                        owner,
-                        operator,
-                        id,
-                        approved,
-                    } => self.env().emit_event(Approval {
-                        owner,
-                        operator,
-                        id,
-                        approved,
-                    }),
-                    PSP34Event::Transfer { from, to, id } => {
-                        self.env().emit_event(Transfer { from, to, id })
-                    }
-                    PSP34Event::AttributeSet { id, key, data } => {
-                        self.env().emit_event(AttributeSet { id, key, data })
-                    }
-                }
-            }
+        // Hook points extended by the `#[smart_beaver::hook]` functions of extensions.
+        // Returning an error aborts the operation.
+        #[allow(dead_code, unused_variables)]
+        fn before_transfer(&mut self, from: AccountId, to: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+
+        #[allow(dead_code, unused_variables)]
+        fn after_transfer(&mut self, from: AccountId, to: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+
+        #[allow(dead_code, unused_variables)]
+        fn before_mint(&mut self, to: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+
+        #[allow(dead_code, unused_variables)]
+        fn before_burn(&mut self, from: AccountId, id: &Id) -> Result<(), PSP34Error> {
+            Ok(())
+        }
+
+        #[allow(dead_code, unused_variables)]
+        fn before_approve(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<&Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            Ok(())
         }
     }
 
@@ -104,6 +118,9 @@ pub mod token {
         data: Vec<u8>,
     }
 
+    // (5)
+    crate::psp34_events!(Token);
+
     // (4)
     impl PSP34 for Token {
         #[ink(message)]
@@ -133,8 +150,11 @@ pub mod token {
             id: Id,
             data: ink::prelude::vec::Vec<u8>,
         ) -> Result<(), PSP34Error> {
-            let events = self.data.transfer(self.env().caller(), to, id, data)?;
+            let from = self.data.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+            self.before_transfer(from, to, &id)?;
+            let events = self.data.transfer(self.env().caller(), to, id.clone(), data)?;
             self.emit_events(events);
+            self.after_transfer(from, to, &id)?;
             Ok(())
         }
 
@@ -145,9 +165,11 @@ pub mod token {
             id: Option<Id>,
             approved: bool,
         ) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            self.before_approve(caller, operator, id.as_ref(), approved)?;
             let events = self
                 .data
-                .approve(self.env().caller(), operator, id, approved)?;
+                .approve(caller, operator, id, approved)?;
             self.emit_events(events);
             Ok(())
         }
@@ -0,0 +1,391 @@
+//! Model-based testing of `PSP34Data`.
+//!
+//! Sequences of operations are decoded from raw bytes and applied both to `PSP34Data`
+//! and to a plain reference implementation of the PSP34 rules. After every step the
+//! outcomes and the resulting states are compared, and the invariants of the collection
+//! are checked. Used by the property tests below and by the `cargo fuzz` target in `fuzz/`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem::discriminant;
+
+use ink::env::DefaultEnvironment;
+use ink::primitives::AccountId;
+
+use crate::storage::{InMemory, OnChain, StorageBackend};
+use crate::{Id, PSP34Data, PSP34Error};
+
+/// Number of accounts taking part in the operations.
+pub const ACCOUNTS: u8 = 4;
+
+/// Number of distinct token ids taking part in the operations.
+pub const IDS: u8 = 8;
+
+/// A single operation on the collection. Accounts are indices below `ACCOUNTS`,
+/// tokens are indices below `IDS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Mint { to: u8, id: u8 },
+    Burn { caller: u8, account: u8, id: u8 },
+    Transfer { caller: u8, to: u8, id: u8 },
+    Approve { caller: u8, operator: u8, id: Option<u8>, approved: bool },
+}
+
+impl Operation {
+    /// Decodes a sequence of operations from `bytes`, ignoring an incomplete one at the end.
+    ///
+    /// Every byte is meaningful, so that any fuzzer input is a valid sequence.
+    pub fn decode_all(bytes: &[u8]) -> Vec<Operation> {
+        let mut bytes = bytes.iter().copied();
+        let mut operations = Vec::new();
+        while let Some(operation) = Self::decode(&mut bytes) {
+            operations.push(operation);
+        }
+        operations
+    }
+
+    fn decode(bytes: &mut impl Iterator<Item = u8>) -> Option<Operation> {
+        let operation = match bytes.next()? % 4 {
+            0 => Operation::Mint {
+                to: decode_account(bytes.next()?),
+                id: decode_id(bytes.next()?),
+            },
+            1 => Operation::Burn {
+                caller: decode_account(bytes.next()?),
+                account: decode_account(bytes.next()?),
+                id: decode_id(bytes.next()?),
+            },
+            2 => Operation::Transfer {
+                caller: decode_account(bytes.next()?),
+                to: decode_account(bytes.next()?),
+                id: decode_id(bytes.next()?),
+            },
+            _ => {
+                let caller = decode_account(bytes.next()?);
+                let operator = decode_account(bytes.next()?);
+                // One extra value above the ids stands for the whole collection
+                let id = bytes.next()? % (IDS + 1);
+                Operation::Approve {
+                    caller,
+                    operator,
+                    id: (id < IDS).then_some(id),
+                    approved: bytes.next()? % 2 == 0,
+                }
+            }
+        };
+        Some(operation)
+    }
+}
+
+/// Maps a byte to the index of an account.
+fn decode_account(byte: u8) -> u8 {
+    byte % ACCOUNTS
+}
+
+/// Maps a byte to the index of a token.
+fn decode_id(byte: u8) -> u8 {
+    byte % IDS
+}
+
+/// Returns the account with the given index.
+pub fn account(index: u8) -> AccountId {
+    AccountId::from([index.wrapping_add(1); 32])
+}
+
+/// Returns the token id with the given index.
+pub fn id(index: u8) -> Id {
+    Id::U8(index)
+}
+
+/// Plain implementation of the PSP34 rules, which `PSP34Data` is compared against.
+#[derive(Debug, Default)]
+struct Reference {
+    owners: BTreeMap<u8, u8>,
+    approvals: BTreeSet<(u8, u8, Option<u8>)>,
+}
+
+impl Reference {
+    fn allowance(&self, owner: u8, operator: u8, id: Option<u8>) -> bool {
+        self.approvals.contains(&(owner, operator, None))
+            || id.is_some() && self.approvals.contains(&(owner, operator, id))
+    }
+
+    fn balance(&self, account: u8) -> usize {
+        self.owners.values().filter(|owner| **owner == account).count()
+    }
+
+    fn apply(&mut self, operation: &Operation) -> Result<(), PSP34Error> {
+        match *operation {
+            Operation::Mint { to, id } => {
+                if self.owners.contains_key(&id) {
+                    return Err(PSP34Error::TokenExists);
+                }
+                self.owners.insert(id, to);
+            }
+            Operation::Burn { caller, account, id } => {
+                if self.owners.get(&id) != Some(&account) {
+                    return Err(PSP34Error::TokenNotExists);
+                }
+                if account != caller && !self.allowance(account, caller, Some(id)) {
+                    return Err(PSP34Error::NotApproved);
+                }
+                self.approvals.remove(&(account, caller, Some(id)));
+                self.owners.remove(&id);
+            }
+            Operation::Transfer { caller, to, id } => {
+                let owner = *self.owners.get(&id).ok_or(PSP34Error::TokenNotExists)?;
+                if owner == to {
+                    return Ok(());
+                }
+                if owner != caller && !self.allowance(owner, caller, Some(id)) {
+                    return Err(PSP34Error::NotApproved);
+                }
+                self.approvals.remove(&(owner, caller, Some(id)));
+                self.owners.insert(id, to);
+            }
+            Operation::Approve { caller, operator, id, approved } => {
+                let mut owner = caller;
+                if let Some(id) = id {
+                    owner = *self.owners.get(&id).ok_or(PSP34Error::TokenNotExists)?;
+                    if approved && owner == operator {
+                        return Err(PSP34Error::SelfApprove);
+                    }
+                    if owner != caller && !self.allowance(owner, caller, None) {
+                        return Err(PSP34Error::NotApproved);
+                    }
+                    if !approved && self.allowance(owner, operator, None) {
+                        return Err(PSP34Error::Custom(String::new()));
+                    }
+                }
+                if approved {
+                    self.approvals.insert((owner, operator, id));
+                } else {
+                    self.approvals.remove(&(owner, operator, id));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `PSP34Data` paired with the reference implementation it is checked against.
+#[derive(Debug, Default)]
+pub struct Model<B: StorageBackend = OnChain> {
+    data: PSP34Data<B>,
+    reference: Reference,
+}
+
+impl<B: StorageBackend> Model<B> {
+    /// Creates a model of an empty collection.
+    ///
+    /// With the `OnChain` backend, must be used inside the off-chain test environment,
+    /// e.g. within `ink::env::test::run_test`.
+    pub fn new() -> Model<B> {
+        Model::default()
+    }
+
+    /// Applies `operation` to both implementations, panics if their outcomes differ
+    /// or any invariant is broken afterwards.
+    pub fn apply(&mut self, operation: &Operation) {
+        let result = match *operation {
+            Operation::Mint { to, id: index } => self.data.mint(account(to), id(index)),
+            Operation::Burn { caller, account: from, id: index } => {
+                self.data.burn(account(caller), account(from), id(index))
+            }
+            Operation::Transfer { caller, to, id: index } => {
+                self.data
+                    .transfer(account(caller), account(to), id(index), Vec::new())
+            }
+            Operation::Approve { caller, operator, id: index, approved } => self.data.approve(
+                account(caller),
+                account(operator),
+                index.map(id),
+                approved,
+            ),
+        };
+        let expected = self.reference.apply(operation);
+        match (&result, &expected) {
+            (Ok(_), Ok(())) => {}
+            // Custom errors are compared by kind only
+            (Err(error), Err(expected_error)) => assert_eq!(
+                discriminant(error),
+                discriminant(expected_error),
+                "{operation:?} failed with {error:?} instead of {expected_error:?}"
+            ),
+            _ => panic!(
+                "{operation:?} resulted in {:?} instead of {expected:?}",
+                result.as_ref().map(|_| ())
+            ),
+        }
+        self.check_invariants();
+    }
+
+    /// Panics if the state of `PSP34Data` differs from the reference one or breaks an invariant.
+    pub fn check_invariants(&self) {
+        for index in 0..IDS {
+            assert_eq!(
+                self.data.owner_of(&id(index)),
+                self.reference.owners.get(&index).copied().map(account),
+                "owner of token {index} mismatch"
+            );
+        }
+
+        // Tokens listed by every owner exist and are owned by them
+        let mut sum: u128 = 0;
+        for owner in 0..ACCOUNTS {
+            let balance = self.data.balance_of(account(owner));
+            assert_eq!(balance as usize, self.reference.balance(owner), "balance of {owner} mismatch");
+            assert_eq!(
+                self.data.stores_balance(account(owner)),
+                balance != 0,
+                "zero balance of {owner} is stored"
+            );
+            let mut listed = BTreeSet::new();
+            for index in 0..u128::from(balance) {
+                let token = self
+                    .data
+                    .owners_token_by_index(account(owner), index)
+                    .expect("token listed below the balance");
+                assert_eq!(
+                    self.data.owner_of(&token),
+                    Some(account(owner)),
+                    "{token:?} listed by {owner} is not owned by them"
+                );
+                assert!(listed.insert(token), "token listed twice by {owner}");
+            }
+            assert!(
+                self.data.owners_token_by_index(account(owner), u128::from(balance)).is_err(),
+                "token listed above the balance of {owner}"
+            );
+            sum += u128::from(balance);
+        }
+
+        // Tokens listed by index are exactly the existing ones
+        let total_supply = self.data.total_supply();
+        assert_eq!(sum, total_supply, "sum of balances differs from total supply");
+        let listed: BTreeSet<_> = (0..total_supply)
+            .map(|index| self.data.token_by_index(index).expect("token listed below the supply"))
+            .collect();
+        let existing: BTreeSet<_> = self.reference.owners.keys().copied().map(id).collect();
+        assert_eq!(listed, existing, "tokens listed by index differ from existing ones");
+        assert!(
+            self.data.token_by_index(total_supply).is_err(),
+            "token listed above the total supply"
+        );
+
+        for owner in 0..ACCOUNTS {
+            for operator in 0..ACCOUNTS {
+                for index in (0..IDS).map(Some).chain([None]) {
+                    assert_eq!(
+                        self.data.allowance(account(owner), account(operator), index.map(id).as_ref()),
+                        self.reference.allowance(owner, operator, index),
+                        "allowance of {owner} to {operator} for {index:?} mismatch"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Applies `operations` to a fresh model in a new off-chain environment.
+pub fn run_operations(operations: &[Operation]) {
+    ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+        let mut model = Model::<OnChain>::new();
+        for operation in operations {
+            model.apply(operation);
+        }
+        Ok(())
+    })
+    .expect("off-chain environment failed");
+}
+
+/// Applies `operations` to a fresh model kept in memory, without the off-chain environment.
+pub fn run_operations_in_memory(operations: &[Operation]) {
+    let mut model = Model::<InMemory>::new();
+    for operation in operations {
+        model.apply(operation);
+    }
+}
+
+/// Decodes operations from `bytes` and applies them to a fresh model. Entry point of the fuzz target.
+pub fn run(bytes: &[u8]) {
+    run_operations(&Operation::decode_all(bytes));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Xorshift generator, deterministic so that a failing seed can be reproduced.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn bytes(&mut self, len: usize) -> Vec<u8> {
+            (0..len).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    /// Checks `cases` random sequences of `len` bytes, longer runs are left to the fuzzer.
+    fn check_random_sequences(cases: u64, len: usize) {
+        for seed in 1..=cases {
+            let bytes = Rng(seed).bytes(len);
+            let result = std::panic::catch_unwind(|| run(&bytes));
+            assert!(result.is_ok(), "sequence of seed {seed} broke the model");
+        }
+    }
+
+    /// Same as `check_random_sequences` with the in-memory backend, which allows many more cases.
+    fn check_random_sequences_in_memory(cases: u64, len: usize) {
+        for seed in 1..=cases {
+            let operations = Operation::decode_all(&Rng(seed).bytes(len));
+            let result = std::panic::catch_unwind(|| run_operations_in_memory(&operations));
+            assert!(result.is_ok(), "sequence of seed {seed} broke the model");
+        }
+    }
+
+    #[test]
+    fn short_random_sequences_match_model() {
+        check_random_sequences(32, 32);
+    }
+
+    #[test]
+    fn random_sequences_match_model_in_memory() {
+        check_random_sequences_in_memory(512, 256);
+    }
+
+    #[test]
+    fn long_random_sequences_match_model() {
+        check_random_sequences(4, 256);
+    }
+
+    #[test]
+    fn burning_token_of_another_account_is_rejected() {
+        run_operations(&[
+            Operation::Mint { to: 0, id: 1 },
+            Operation::Mint { to: 1, id: 2 },
+            Operation::Burn { caller: 1, account: 1, id: 1 },
+            Operation::Burn { caller: 0, account: 1, id: 2 },
+            Operation::Approve { caller: 1, operator: 0, id: Some(2), approved: true },
+            Operation::Burn { caller: 0, account: 1, id: 2 },
+        ]);
+    }
+
+    #[test]
+    fn approvals_follow_transfers() {
+        run_operations(&[
+            Operation::Mint { to: 0, id: 1 },
+            Operation::Approve { caller: 0, operator: 1, id: None, approved: true },
+            Operation::Transfer { caller: 1, to: 2, id: 1 },
+            Operation::Approve { caller: 2, operator: 3, id: Some(1), approved: true },
+            Operation::Transfer { caller: 3, to: 0, id: 1 },
+            Operation::Transfer { caller: 1, to: 3, id: 1 },
+            Operation::Approve { caller: 0, operator: 1, id: Some(1), approved: false },
+        ]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Storage backends of the data layer.
+//!
+//! `PSP34Data` keeps its token owners, approvals and balances in [`Map`]s, which delegate to the map type
+//! of a [`StorageBackend`]:
+//!
+//! * [`OnChain`], the default, stores entries in the contract storage with ink!'s `Mapping`,
+//! * [`InMemory`] (with the `std` feature) keeps them in a `BTreeMap`, so the same code of
+//!   `data.rs` runs in plain Rust simulations, backtests and property tests, without the
+//!   off-chain environment of ink!.
+//!
+//! As in the contract storage, entries are identified by the SCALE encoding of their key,
+//! so a `Map` accepts every key type which encodes like its own (e.g. `&Id`).
+
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ink::primitives::Key;
+use ink::storage::traits::{AutoKey, Packed, Storable, StorableHint, StorageKey};
+use ink::storage::Mapping;
+use scale::{Encode, EncodeLike, Error, Input, Output};
+
+/// Operations of a key-value map used by the data layer.
+pub trait StorageMap<K: Encode, V> {
+    /// Returns the value stored under `key`, if any.
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V>;
+
+    /// Stores `value` under `key`, replacing the previous one.
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V);
+
+    /// Removes the value stored under `key`, if any.
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q);
+
+    /// Returns `true` if a value is stored under `key`.
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool;
+}
+
+/// A family of maps in which the data layer keeps its state.
+///
+/// `KeyType` is the storage key assigned by `#[ink::storage_item]` to the field
+/// holding the map, backends which do not use the contract storage ignore it.
+pub trait StorageBackend: Debug + Default {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey>: StorageMap<K, V> + Default;
+}
+
+/// Backend storing the entries in the contract storage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct OnChain;
+
+impl StorageBackend for OnChain {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey> = Mapping<K, V, KeyType>;
+}
+
+impl<K, V, KeyType> StorageMap<K, V> for Mapping<K, V, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike,
+    KeyType: StorageKey,
+{
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        Mapping::get(self, key)
+    }
+
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        Mapping::insert(self, key, value);
+    }
+
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        Mapping::remove(self, key)
+    }
+
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        Mapping::contains(self, key)
+    }
+}
+
+/// Backend keeping the entries in memory, for running the data layer outside of a contract.
+///
+/// Nothing is written to the contract storage: a `PSP34Data<InMemory>` used as a
+/// contract field would lose its entries between calls.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale_info::TypeInfo)]
+pub struct InMemory;
+
+#[cfg(feature = "std")]
+impl StorageBackend for InMemory {
+    type Map<K: Encode, V: Packed + EncodeLike + Clone, KeyType: StorageKey> = std::collections::BTreeMap<std::vec::Vec<u8>, V>;
+}
+
+#[cfg(feature = "std")]
+impl<K: Encode, V: Clone> StorageMap<K, V> for std::collections::BTreeMap<std::vec::Vec<u8>, V> {
+    fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        self.get(&key.encode()).cloned()
+    }
+
+    fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        self.insert(key.encode(), value.clone());
+    }
+
+    fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        self.remove(&key.encode());
+    }
+
+    fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        self.contains_key(&key.encode())
+    }
+}
+
+/// A map from `K` to `V` kept by the backend `B`, used like ink!'s `Mapping`.
+///
+/// With the `OnChain` backend it is stored exactly like a `Mapping` declared in its place,
+/// so switching a storage item from `Mapping` to `Map` keeps its storage layout.
+pub struct Map<K, V, B = OnChain, KeyType = AutoKey>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    entries: B::Map<K, V, KeyType>,
+}
+
+impl<K, V, B, KeyType> Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    /// Returns the value stored under `key`, if any.
+    pub fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<V> {
+        self.entries.get(key)
+    }
+
+    /// Stores `value` under `key`, replacing the previous one.
+    pub fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &V) {
+        self.entries.insert(key, value)
+    }
+
+    /// Removes the value stored under `key`, if any.
+    pub fn remove<Q: EncodeLike<K>>(&mut self, key: Q) {
+        self.entries.remove(key)
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    pub fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        self.entries.contains(key)
+    }
+}
+
+impl<K, V, B, KeyType> Default for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn default() -> Self {
+        Map {
+            entries: Default::default(),
+        }
+    }
+}
+
+impl<K, V, B, KeyType> Debug for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Map").field("key", &KeyType::KEY).finish()
+    }
+}
+
+// The storage traits below mirror the ones of `Mapping`: the map is not part of the
+// encoded storage item, its entries live under their own keys.
+
+impl<K, V, B, KeyType> Storable for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    fn encode<T: Output + ?Sized>(&self, _dest: &mut T) {}
+
+    fn decode<I: Input>(_input: &mut I) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+}
+
+impl<K, V, B, Key, InnerKey> StorableHint<Key> for Map<K, V, B, InnerKey>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    Key: StorageKey,
+    InnerKey: StorageKey,
+{
+    type Type = Map<K, V, B, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<K, V, B, KeyType> StorageKey for Map<K, V, B, KeyType>
+where
+    K: Encode,
+    V: Packed + EncodeLike + Clone,
+    B: StorageBackend,
+    KeyType: StorageKey,
+{
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use ink::metadata::layout::{Layout, LayoutKey, RootLayout};
+    use ink::storage::traits::StorageLayout;
+    use scale_info::{Type, TypeInfo};
+
+    impl<K, V, B, KeyType> TypeInfo for Map<K, V, B, KeyType>
+    where
+        K: Encode + TypeInfo + 'static,
+        V: Packed + EncodeLike + Clone + TypeInfo + 'static,
+        B: StorageBackend,
+        KeyType: StorageKey + TypeInfo + 'static,
+    {
+        type Identity = <Mapping<K, V, KeyType> as TypeInfo>::Identity;
+
+        fn type_info() -> Type {
+            <Mapping<K, V, KeyType> as TypeInfo>::type_info()
+        }
+    }
+
+    impl<K, V, B, KeyType> StorageLayout for Map<K, V, B, KeyType>
+    where
+        K: Encode + scale_info::TypeInfo + 'static,
+        V: Packed + EncodeLike + Clone + StorageLayout + scale_info::TypeInfo + 'static,
+        B: StorageBackend,
+        KeyType: StorageKey + scale_info::TypeInfo + 'static,
+    {
+        fn layout(_: &Key) -> Layout {
+            Layout::Root(RootLayout::new(
+                LayoutKey::from(&KeyType::KEY),
+                <V as StorageLayout>::layout(&KeyType::KEY),
+            ))
+        }
+    }
+};
@@ -1,7 +1,8 @@
 /// Inserts a suite of ink! unit tests intended for a contract implementing PSP34 trait.
 /// `$contract` argument should be the name of the contract struct.
 /// `$constructor` argument should be the name of a function, which initializes `$contract`.
-/// This macro should be invoked inside `#[ink::contract]` module.
+/// This macro should be invoked inside `#[ink::contract]` module of a contract declaring
+/// the events documented in `events.rs` and emitting them with `psp34_events!`.
 #[macro_export]
 macro_rules! tests {
     ($contract:ident, $constructor:expr) => {
@@ -9,19 +10,17 @@ macro_rules! tests {
             use super::super::*;
             use ink::env::{test::*, DefaultEnvironment as E};
 
-            type Event = <$contract as ::ink::reflect::ContractEventBase>::Type;
-
             // Gathers all emitted events, skip `shift` first, decode the rest and return as vector
-            fn decode_events(shift: usize) -> Vec<Event> {
+            fn decode_events(shift: usize) -> Vec<PSP34Event> {
                 recorded_events()
                     .skip(shift)
-                    .map(|e| <Event as scale::Decode>::decode(&mut &e.data[..]).unwrap())
+                    .map(|e| $crate::events::decode_event(&e.topics[0], &e.data).unwrap())
                     .collect()
             }
 
             // Asserts if the given event is a Transfer with particular from_, to_ and value_
-            fn assert_transfer(event: &Event, from_: AccountId, to_: AccountId, id_: Id) {
-                if let Event::Transfer(Transfer { from, to, id }) = event {
+            fn assert_transfer(event: &PSP34Event, from_: AccountId, to_: AccountId, id_: Id) {
+                if let PSP34Event::Transfer { from, to, id } = event {
                     assert_eq!(*from, Some(from_), "Transfer event: 'from' mismatch");
                     assert_eq!(*to, Some(to_), "Transfer event: 'to' mismatch");
                     assert_eq!(*id, id_, "Transfer event: 'id' mismatch");
@@ -32,18 +31,18 @@ macro_rules! tests {
 
             // Asserts if the given event is a Approval with particular owner_, spender_ and amount_
             fn assert_approval(
-                event: &Event,
+                event: &PSP34Event,
                 owner_: AccountId,
                 operator_: AccountId,
                 id_: Option<Id>,
                 approved_ : bool,
             ) {
-                if let Event::Approval(Approval {
+                if let PSP34Event::Approval {
                     owner,
                     operator,
                     id,
                     approved,
-                }) = event
+                } = event
                 {
                     assert_eq!(*owner, owner_, "Approval event: 'owner' mismatch");
                     assert_eq!(*operator, operator_, "Approval event: 'operator' mismatch");
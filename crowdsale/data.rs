@@ -0,0 +1,184 @@
+//! Module describing the configuration of a sale and the price of the sold token.
+
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use merkle_distributor::{leaf_hash, verify, Node};
+use primitive_types::U256;
+use vesting::VestingSchedule;
+
+use crate::CrowdsaleError;
+
+/// Number of token units the prices are quoted for: one whole token of a token with 18 decimals.
+pub const PRICE_UNIT: u128 = 1_000_000_000_000_000_000;
+
+/// Returns `a * b / c` rounded down, without intermediate overflow.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    u128::try_from(result).unwrap_or(u128::MAX)
+}
+
+/// Price of the sold token, in native currency per `PRICE_UNIT` of the token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Pricing {
+    /// The price stays the same for the whole sale.
+    Fixed { price: u128 },
+    /// The price falls linearly from `start_price` at the start of the sale to `end_price` at its end.
+    /// All buyers pay the clearing price: the price at which the sale closed.
+    Dutch { start_price: u128, end_price: u128 },
+}
+
+/// Where the sold tokens come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum TokenSource {
+    /// The sale mints the tokens with `PSP22Mintable::mint`, so it must be allowed to mint them.
+    Mint,
+    /// The sale transfers the tokens from its own balance, funded by the owner beforehand.
+    Balance,
+}
+
+/// Stage of the life of a sale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SaleStatus {
+    /// The sale has not started yet.
+    Upcoming,
+    /// Tokens can be bought.
+    Open,
+    /// The sale has closed with at least the soft cap raised, buyers claim their tokens.
+    Succeeded,
+    /// The sale has closed below the soft cap, buyers are refunded.
+    Failed,
+}
+
+/// Proof that a buyer is on the allowlist of a sale.
+///
+/// The allowlist is a Merkle tree built like the one of `merkle_distributor`, with the leaf
+/// `leaf_hash(index, buyer, cap)` for every buyer, where `cap` limits the contribution of the buyer.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Allowance {
+    pub index: u32,
+    pub cap: u128,
+    /// Nodes leading from the leaf of the buyer to the allowlist root, from the bottom up.
+    pub proof: Vec<Node>,
+}
+
+/// Terms of a sale. All amounts of native currency are in its smallest units and
+/// all timestamps are in milliseconds, like block timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct SaleConfig {
+    /// Timestamp at which the sale opens.
+    pub start: u64,
+    /// Timestamp at which the sale closes, unless the hard cap is reached earlier.
+    pub end: u64,
+    pub pricing: Pricing,
+    /// Native currency which must be raised for the sale to succeed.
+    pub soft_cap: u128,
+    /// Native currency at which the sale closes.
+    pub hard_cap: u128,
+    /// Limit of the contribution of a single buyer, if any.
+    pub max_per_buyer: Option<u128>,
+    /// Root of the allowlist. If set, only the buyers on the allowlist can buy.
+    pub allowlist_root: Option<Node>,
+    /// Schedule releasing the bought tokens. If not set, they are claimed at once when the sale succeeds.
+    pub vesting: Option<VestingSchedule>,
+    pub source: TokenSource,
+}
+
+impl SaleConfig {
+    /// Checks that the terms are consistent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidConfig` if the sale does not end after it starts, the hard cap or a price
+    /// is zero, the soft cap exceeds the hard cap or the price of a Dutch auction rises.
+    pub fn validate(&self) -> Result<(), CrowdsaleError> {
+        let prices_valid = match self.pricing {
+            Pricing::Fixed { price } => price > 0,
+            Pricing::Dutch {
+                start_price,
+                end_price,
+            } => end_price > 0 && start_price >= end_price,
+        };
+        if self.start >= self.end || self.hard_cap == 0 || self.soft_cap > self.hard_cap || !prices_valid {
+            return Err(CrowdsaleError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Returns the price at `now`, clamped to the duration of the sale.
+    pub fn price_at(&self, now: u64) -> u128 {
+        match self.pricing {
+            Pricing::Fixed { price } => price,
+            Pricing::Dutch {
+                start_price,
+                end_price,
+            } => {
+                let elapsed = now.clamp(self.start, self.end) - self.start;
+                let drop = mul_div(
+                    start_price - end_price,
+                    u128::from(elapsed),
+                    u128::from(self.end - self.start),
+                );
+                start_price - drop
+            }
+        }
+    }
+
+    /// Returns the amount of tokens bought with `contribution` at `price`, rounded down.
+    pub fn tokens_for(contribution: u128, price: u128) -> u128 {
+        mul_div(contribution, PRICE_UNIT, price)
+    }
+
+    /// Returns the largest number of tokens the sale can sell: the hard cap at the lowest price.
+    ///
+    /// A sale selling from its balance must be funded with this amount.
+    pub fn max_tokens(&self) -> u128 {
+        let lowest_price = match self.pricing {
+            Pricing::Fixed { price } => price,
+            Pricing::Dutch { end_price, .. } => end_price,
+        };
+        Self::tokens_for(self.hard_cap, lowest_price)
+    }
+
+    /// Returns the limit of the contribution of `buyer`.
+    ///
+    /// If the sale has an allowlist, `allowance` must prove that the buyer is on it and the
+    /// limit is the lower of `max_per_buyer` and the cap of the buyer. Otherwise `allowance` is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotAllowlisted` if the sale has an allowlist and `allowance` is missing or invalid.
+    pub fn cap_of(&self, buyer: AccountId, allowance: Option<&Allowance>) -> Result<u128, CrowdsaleError> {
+        let max_per_buyer = self.max_per_buyer.unwrap_or(u128::MAX);
+        let Some(root) = self.allowlist_root else {
+            return Ok(max_per_buyer);
+        };
+        match allowance {
+            Some(allowance) if verify(&allowance.proof, &root, leaf_hash(allowance.index, buyer, allowance.cap)) => {
+                Ok(max_per_buyer.min(allowance.cap))
+            }
+            _ => Err(CrowdsaleError::NotAllowlisted),
+        }
+    }
+
+    /// Returns the part of `purchased` tokens released at `now`.
+    pub fn released(&self, purchased: u128, now: u64) -> u128 {
+        match self.vesting {
+            Some(schedule) => schedule.vested_amount(purchased, now),
+            None => purchased,
+        }
+    }
+}
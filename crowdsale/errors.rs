@@ -0,0 +1,42 @@
+//! Error types for the token sale.
+
+use psp22_full::PSP22Error;
+
+/// Errors that may occur during sale operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CrowdsaleError {
+    /// The caller is not the owner of the sale.
+    NotOwner,
+    /// The sale ends before it starts, has a zero price or hard cap,
+    /// a soft cap above the hard cap or a Dutch auction price which rises.
+    InvalidConfig,
+    /// The sale has not started yet or is already closed.
+    SaleNotOpen,
+    /// The sale is still running.
+    SaleNotEnded,
+    /// The sale has missed its soft cap, contributions are refunded instead.
+    SoftCapMissed,
+    /// The sale has reached its soft cap, so contributions are not refunded.
+    SoftCapReached,
+    /// No native currency was transferred with the purchase.
+    ZeroContribution,
+    /// The buyer is not on the allowlist of the sale.
+    NotAllowlisted,
+    /// The purchase exceeds the contribution limit of the buyer.
+    BuyerCapExceeded,
+    /// The purchase exceeds the hard cap of the sale.
+    HardCapExceeded,
+    NothingToClaim,
+    NothingToRefund,
+    /// A transfer of native currency failed.
+    TransferFailed,
+    /// The sold token returned an error.
+    PSP22(PSP22Error),
+}
+
+impl From<PSP22Error> for CrowdsaleError {
+    fn from(error: PSP22Error) -> Self {
+        CrowdsaleError::PSP22(error)
+    }
+}
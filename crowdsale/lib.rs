@@ -0,0 +1,340 @@
+//! Sale of a PSP22 token for native currency.
+//!
+//! Buyers contribute native currency while the sale is open, at a fixed price or in a Dutch
+//! auction. If the sale raises its soft cap, buyers claim the bought tokens after it closes,
+//! optionally over a vesting schedule, and the owner withdraws the proceeds. Otherwise every
+//! buyer is refunded.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+
+pub use data::{Allowance, Pricing, SaleConfig, SaleStatus, TokenSource, PRICE_UNIT};
+pub use errors::CrowdsaleError;
+
+/// Token sale implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod crowdsale {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use psp22_full::PSP22Ref;
+
+    use crate::{Allowance, CrowdsaleError, SaleConfig, SaleStatus, TokenSource};
+
+    #[ink(storage)]
+    pub struct Crowdsale {
+        /// Account which withdraws the proceeds and the unsold tokens.
+        owner: AccountId,
+        token: AccountId,
+        config: SaleConfig,
+        /// Native currency contributed by all buyers. Not decreased by refunds.
+        raised: Balance,
+        /// Timestamp at which the hard cap was reached, closing the sale before its end.
+        closed_at: Option<u64>,
+        contributions: Mapping<AccountId, Balance>,
+        /// Tokens claimed by each buyer.
+        claimed: Mapping<AccountId, u128>,
+        total_claimed: u128,
+        /// Whether the owner has withdrawn the proceeds.
+        withdrawn: bool,
+    }
+
+    #[ink(event)]
+    pub struct Purchased {
+        #[ink(topic)]
+        buyer: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        buyer: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        proceeds: Balance,
+        tokens: u128,
+    }
+
+    impl Crowdsale {
+        /// Creates a sale of `token` owned by the caller.
+        ///
+        /// A sale minting the tokens must be allowed to mint them, a sale selling from its
+        /// balance must hold `max_tokens` of them before it closes.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InvalidConfig` if the terms are inconsistent, see `SaleConfig::validate`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId, config: SaleConfig) -> Result<Self, CrowdsaleError> {
+            config.validate()?;
+            Ok(Self {
+                owner: Self::env().caller(),
+                token,
+                config,
+                raised: 0,
+                closed_at: None,
+                contributions: Default::default(),
+                claimed: Default::default(),
+                total_claimed: 0,
+                withdrawn: false,
+            })
+        }
+
+        // Contracts this one depends on implement `EmitEvent` for the same environment
+        // when built with their `contract` feature, so the contract is named explicitly
+        fn emit_event(&self, event: impl Into<<Crowdsale as ink::reflect::ContractEventBase>::Type>) {
+            ink::codegen::EmitEvent::<Crowdsale>::emit_event(self.env(), event)
+        }
+
+        /// Returns the account which withdraws the proceeds.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        #[ink(message)]
+        pub fn config(&self) -> SaleConfig {
+            self.config.clone()
+        }
+
+        #[ink(message)]
+        pub fn status(&self) -> SaleStatus {
+            let now = self.env().block_timestamp();
+            if now < self.config.start {
+                SaleStatus::Upcoming
+            } else if self.closed_at.is_none() && now < self.config.end {
+                SaleStatus::Open
+            } else if self.raised >= self.config.soft_cap {
+                SaleStatus::Succeeded
+            } else {
+                SaleStatus::Failed
+            }
+        }
+
+        /// Returns the native currency contributed by all buyers.
+        #[ink(message)]
+        pub fn raised(&self) -> Balance {
+            self.raised
+        }
+
+        /// Returns the current price, or the clearing price once the sale has closed.
+        #[ink(message)]
+        pub fn price(&self) -> u128 {
+            let now = self.env().block_timestamp();
+            self.config.price_at(self.closed_at.unwrap_or(now))
+        }
+
+        /// Returns the largest number of tokens the sale can sell.
+        #[ink(message)]
+        pub fn max_tokens(&self) -> u128 {
+            self.config.max_tokens()
+        }
+
+        #[ink(message)]
+        pub fn contribution_of(&self, buyer: AccountId) -> Balance {
+            self.contributions.get(buyer).unwrap_or_default()
+        }
+
+        /// Returns the tokens bought by `buyer`, at the current price while the sale is open.
+        #[ink(message)]
+        pub fn purchased(&self, buyer: AccountId) -> u128 {
+            SaleConfig::tokens_for(self.contribution_of(buyer), self.price())
+        }
+
+        #[ink(message)]
+        pub fn claimed(&self, buyer: AccountId) -> u128 {
+            self.claimed.get(buyer).unwrap_or_default()
+        }
+
+        /// Returns the tokens `buyer` can claim now.
+        #[ink(message)]
+        pub fn claimable(&self, buyer: AccountId) -> u128 {
+            if self.status() != SaleStatus::Succeeded {
+                return 0;
+            }
+            let released = self
+                .config
+                .released(self.purchased(buyer), self.env().block_timestamp());
+            released.saturating_sub(self.claimed(buyer))
+        }
+
+        /// Buys tokens for the transferred native currency.
+        ///
+        /// The tokens are claimed after the sale closes. The sale closes as soon as the hard cap is reached.
+        ///
+        /// # Arguments
+        ///
+        /// * `allowance` - Proof that the caller is on the allowlist. Required only if the sale has one.
+        ///
+        /// # Events
+        ///
+        /// A `Purchased` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleNotOpen` unless the sale is open, `ZeroContribution` if nothing was transferred,
+        /// `NotAllowlisted` if the caller is not on the allowlist and `BuyerCapExceeded` or `HardCapExceeded`
+        /// if the contribution exceeds the limit of the caller or the remaining hard cap.
+        #[ink(message, payable)]
+        pub fn buy(&mut self, allowance: Option<Allowance>) -> Result<(), CrowdsaleError> {
+            if self.status() != SaleStatus::Open {
+                return Err(CrowdsaleError::SaleNotOpen);
+            }
+            let buyer = self.env().caller();
+            let value = self.env().transferred_value();
+            if value == 0 {
+                return Err(CrowdsaleError::ZeroContribution);
+            }
+            let cap = self.config.cap_of(buyer, allowance.as_ref())?;
+            let contribution = self.contribution_of(buyer).saturating_add(value);
+            if contribution > cap {
+                return Err(CrowdsaleError::BuyerCapExceeded);
+            }
+            let raised = self.raised.saturating_add(value);
+            if raised > self.config.hard_cap {
+                return Err(CrowdsaleError::HardCapExceeded);
+            }
+            self.contributions.insert(buyer, &contribution);
+            self.raised = raised;
+            if raised == self.config.hard_cap {
+                self.closed_at = Some(self.env().block_timestamp());
+            }
+            self.emit_event(Purchased { buyer, value });
+            Ok(())
+        }
+
+        /// Transfers, or mints, the claimable tokens of the caller to it.
+        ///
+        /// # Events
+        ///
+        /// A `Claimed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleNotEnded` before the sale closes, `SoftCapMissed` if it has failed
+        /// and `NothingToClaim` if no tokens are claimable now.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<(), CrowdsaleError> {
+            match self.status() {
+                SaleStatus::Upcoming | SaleStatus::Open => return Err(CrowdsaleError::SaleNotEnded),
+                SaleStatus::Failed => return Err(CrowdsaleError::SoftCapMissed),
+                SaleStatus::Succeeded => {}
+            }
+            let buyer = self.env().caller();
+            let amount = self.claimable(buyer);
+            if amount == 0 {
+                return Err(CrowdsaleError::NothingToClaim);
+            }
+            // A buyer claims at most the tokens it bought, so the sums below cannot exceed
+            // `tokens_for(raised, price)`, which `withdraw` relies on
+            self.claimed.insert(buyer, &self.claimed(buyer).saturating_add(amount));
+            self.total_claimed = self.total_claimed.saturating_add(amount);
+            match self.config.source {
+                TokenSource::Mint => Self::psp22(self.token).mint(buyer, amount)?,
+                TokenSource::Balance => Self::psp22(self.token).transfer(buyer, amount, Vec::new())?,
+            }
+            self.emit_event(Claimed { buyer, amount });
+            Ok(())
+        }
+
+        /// Returns the whole contribution of the caller after the sale has missed its soft cap.
+        ///
+        /// # Events
+        ///
+        /// A `Refunded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `SaleNotEnded` before the sale closes, `SoftCapReached` if it has succeeded
+        /// and `NothingToRefund` if the caller has no contribution left.
+        #[ink(message)]
+        pub fn refund(&mut self) -> Result<(), CrowdsaleError> {
+            match self.status() {
+                SaleStatus::Upcoming | SaleStatus::Open => return Err(CrowdsaleError::SaleNotEnded),
+                SaleStatus::Succeeded => return Err(CrowdsaleError::SoftCapReached),
+                SaleStatus::Failed => {}
+            }
+            let buyer = self.env().caller();
+            let value = self.contribution_of(buyer);
+            if value == 0 {
+                return Err(CrowdsaleError::NothingToRefund);
+            }
+            self.contributions.remove(buyer);
+            self.env()
+                .transfer(buyer, value)
+                .map_err(|_| CrowdsaleError::TransferFailed)?;
+            self.emit_event(Refunded { buyer, value });
+            Ok(())
+        }
+
+        /// Transfers the proceeds and the tokens not owed to buyers to the owner.
+        ///
+        /// The proceeds are paid once, if the sale has succeeded. A sale selling from its balance
+        /// also returns all its tokens except those bought and not claimed yet, or all of them if
+        /// the sale has failed.
+        ///
+        /// # Events
+        ///
+        /// A `Withdrawn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner
+        /// and `SaleNotEnded` before the sale closes.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<(), CrowdsaleError> {
+            if self.env().caller() != self.owner {
+                return Err(CrowdsaleError::NotOwner);
+            }
+            let succeeded = match self.status() {
+                SaleStatus::Upcoming | SaleStatus::Open => return Err(CrowdsaleError::SaleNotEnded),
+                status => status == SaleStatus::Succeeded,
+            };
+            let proceeds = if succeeded && !self.withdrawn { self.raised } else { 0 };
+            self.withdrawn = true;
+
+            let mut tokens = 0;
+            if self.config.source == TokenSource::Balance {
+                let owed = if succeeded {
+                    // `total_claimed` never exceeds the tokens bought, see `claim`
+                    SaleConfig::tokens_for(self.raised, self.price()).saturating_sub(self.total_claimed)
+                } else {
+                    0
+                };
+                let mut token = Self::psp22(self.token);
+                tokens = token.balance_of(self.env().account_id())?.saturating_sub(owed);
+                if tokens > 0 {
+                    token.transfer(self.owner, tokens, Vec::new())?;
+                }
+            }
+            if proceeds > 0 {
+                self.env()
+                    .transfer(self.owner, proceeds)
+                    .map_err(|_| CrowdsaleError::TransferFailed)?;
+            }
+            self.emit_event(Withdrawn { proceeds, tokens });
+            Ok(())
+        }
+
+        fn psp22(token: AccountId) -> PSP22Ref {
+            PSP22Ref::new(token)
+        }
+    }
+}
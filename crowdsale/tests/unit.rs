@@ -0,0 +1,367 @@
+use crowdsale::crowdsale::*;
+use psp22_full::mock;
+use crowdsale::{Allowance, CrowdsaleError, Pricing, SaleConfig, SaleStatus, TokenSource, PRICE_UNIT};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{
+        get_account_balance, set_account_balance, set_block_timestamp, set_callee, set_caller,
+        set_value_transferred,
+    }};
+    use merkle_distributor::tree::MerkleTree;
+    use psp22_full::PSP22Error;
+    use vesting::VestingSchedule;
+    use super::*;
+
+    const START: u64 = 1000;
+    const END: u64 = 2000;
+    /// Ten tokens per unit of native currency.
+    const FIXED: Pricing = Pricing::Fixed { price: PRICE_UNIT / 10 };
+    /// From two to one unit of native currency per token.
+    const DUTCH: Pricing = Pricing::Dutch {
+        start_price: 2 * PRICE_UNIT,
+        end_price: PRICE_UNIT,
+    };
+
+    fn owner() -> AccountId {
+        AccountId::from([0x1; 32])
+    }
+
+    fn alice() -> AccountId {
+        AccountId::from([0x2; 32])
+    }
+
+    fn bob() -> AccountId {
+        AccountId::from([0x3; 32])
+    }
+
+    fn charlie() -> AccountId {
+        AccountId::from([0x4; 32])
+    }
+
+    fn sale_account() -> AccountId {
+        AccountId::from([0x10; 32])
+    }
+
+    fn token() -> AccountId {
+        AccountId::from([0x20; 32])
+    }
+
+    fn native_balance(account: AccountId) -> u128 {
+        get_account_balance::<DefaultEnvironment>(account).unwrap_or_default()
+    }
+
+    /// Returns the terms of a sale open from `START` to `END`, with a soft cap of 1000,
+    /// a hard cap of 5000 and at most 2000 contributed by a single buyer.
+    fn config(pricing: Pricing, source: TokenSource) -> SaleConfig {
+        SaleConfig {
+            start: START,
+            end: END,
+            pricing,
+            soft_cap: 1000,
+            hard_cap: 5000,
+            max_per_buyer: Some(2000),
+            allowlist_root: None,
+            vesting: None,
+            source,
+        }
+    }
+
+    fn deploy(config: SaleConfig) -> Crowdsale {
+        mock::deploy(token(), 0, owner());
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(sale_account());
+        set_account_balance::<DefaultEnvironment>(sale_account(), 0);
+        set_account_balance::<DefaultEnvironment>(owner(), 0);
+        set_caller::<DefaultEnvironment>(owner());
+        Crowdsale::new(token(), config).expect("Valid sale")
+    }
+
+    fn at(timestamp: u64, caller: AccountId) {
+        set_block_timestamp::<DefaultEnvironment>(timestamp);
+        set_caller::<DefaultEnvironment>(caller);
+    }
+
+    // Calls `buy` as `buyer` with `value` of native currency
+    // (the off-chain environment does not move the transferred value itself)
+    fn buy(
+        sale: &mut Crowdsale,
+        buyer: AccountId,
+        value: u128,
+        allowance: Option<Allowance>,
+    ) -> Result<(), CrowdsaleError> {
+        set_account_balance::<DefaultEnvironment>(sale_account(), native_balance(sale_account()) + value);
+        set_caller::<DefaultEnvironment>(buyer);
+        set_value_transferred::<DefaultEnvironment>(value);
+        let result = sale.buy(allowance);
+        set_value_transferred::<DefaultEnvironment>(0);
+        if result.is_err() {
+            set_account_balance::<DefaultEnvironment>(sale_account(), native_balance(sale_account()) - value);
+        }
+        result
+    }
+
+    #[test]
+    fn inconsistent_config_is_rejected() {
+        assert_eq!(config(FIXED, TokenSource::Mint).validate(), Ok(()));
+        assert_eq!(config(DUTCH, TokenSource::Mint).validate(), Ok(()));
+
+        let invalid = [
+            SaleConfig { end: START, ..config(FIXED, TokenSource::Mint) },
+            SaleConfig { hard_cap: 0, soft_cap: 0, ..config(FIXED, TokenSource::Mint) },
+            SaleConfig { soft_cap: 5001, ..config(FIXED, TokenSource::Mint) },
+            config(Pricing::Fixed { price: 0 }, TokenSource::Mint),
+            config(Pricing::Dutch { start_price: 1, end_price: 0 }, TokenSource::Mint),
+            config(Pricing::Dutch { start_price: 1, end_price: 2 }, TokenSource::Mint),
+        ];
+        for config in invalid {
+            assert_eq!(config.validate(), Err(CrowdsaleError::InvalidConfig));
+        }
+    }
+
+    #[test]
+    fn dutch_price_falls_linearly() {
+        let config = config(DUTCH, TokenSource::Mint);
+        assert_eq!(config.price_at(0), 2 * PRICE_UNIT);
+        assert_eq!(config.price_at(START), 2 * PRICE_UNIT);
+        assert_eq!(config.price_at(1250), 7 * PRICE_UNIT / 4);
+        assert_eq!(config.price_at(END), PRICE_UNIT);
+        assert_eq!(config.price_at(END + 1000), PRICE_UNIT);
+        // The hard cap at the end price
+        assert_eq!(config.max_tokens(), 5000);
+        assert_eq!(SaleConfig::tokens_for(3000, 3 * PRICE_UNIT / 2), 2000);
+    }
+
+    #[test]
+    fn allowlist_limits_buyers() {
+        let tree = MerkleTree::new([(alice(), 500), (bob(), 3000)]).expect("Valid allowlist");
+        let allowance = |index: u32, cap: u128| Allowance {
+            index,
+            cap,
+            proof: tree.proof(index).expect("Entry exists"),
+        };
+        let allowlisted = SaleConfig {
+            allowlist_root: Some(tree.root()),
+            ..config(FIXED, TokenSource::Mint)
+        };
+
+        assert_eq!(allowlisted.cap_of(alice(), Some(&allowance(0, 500))), Ok(500));
+        // The lower of the allowlisted cap and the cap of every buyer
+        assert_eq!(allowlisted.cap_of(bob(), Some(&allowance(1, 3000))), Ok(2000));
+        assert_eq!(allowlisted.cap_of(alice(), None), Err(CrowdsaleError::NotAllowlisted));
+        assert_eq!(allowlisted.cap_of(alice(), Some(&allowance(0, 5000))), Err(CrowdsaleError::NotAllowlisted));
+        assert_eq!(allowlisted.cap_of(charlie(), Some(&allowance(0, 500))), Err(CrowdsaleError::NotAllowlisted));
+
+        // Without an allowlist the allowance is ignored
+        let open = config(FIXED, TokenSource::Mint);
+        assert_eq!(open.cap_of(charlie(), None), Ok(2000));
+        assert_eq!(open.cap_of(charlie(), Some(&allowance(0, 500))), Ok(2000));
+    }
+
+    #[ink::test]
+    fn fixed_price_sale_mints_claims() {
+        let mut sale = deploy(config(FIXED, TokenSource::Mint));
+        assert_eq!(sale.status(), SaleStatus::Upcoming);
+        assert_eq!(buy(&mut sale, alice(), 100, None), Err(CrowdsaleError::SaleNotOpen));
+
+        at(START, alice());
+        assert_eq!(sale.status(), SaleStatus::Open);
+        assert_eq!(buy(&mut sale, alice(), 0, None), Err(CrowdsaleError::ZeroContribution));
+        buy(&mut sale, alice(), 600, None).expect("Bought");
+        buy(&mut sale, alice(), 400, None).expect("Bought");
+        buy(&mut sale, bob(), 500, None).expect("Bought");
+        assert_eq!(sale.raised(), 1500);
+        assert_eq!(sale.contribution_of(alice()), 1000);
+        assert_eq!(sale.purchased(alice()), 10_000);
+        assert_eq!(sale.claimable(alice()), 0);
+        assert_eq!(sale.claim(), Err(CrowdsaleError::SaleNotEnded));
+
+        at(END, alice());
+        assert_eq!(sale.status(), SaleStatus::Succeeded);
+        assert_eq!(buy(&mut sale, alice(), 100, None), Err(CrowdsaleError::SaleNotOpen));
+        assert_eq!(sale.refund(), Err(CrowdsaleError::SoftCapReached));
+        assert_eq!(sale.claim(), Ok(()));
+        assert_eq!(mock::balance_of(token(), alice()), 10_000);
+        assert_eq!(sale.claim(), Err(CrowdsaleError::NothingToClaim));
+        set_caller::<DefaultEnvironment>(charlie());
+        assert_eq!(sale.claim(), Err(CrowdsaleError::NothingToClaim));
+
+        assert_eq!(sale.withdraw(), Err(CrowdsaleError::NotOwner));
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(sale.withdraw(), Ok(()));
+        assert_eq!(native_balance(owner()), 1500);
+        // The proceeds are paid once
+        assert_eq!(sale.withdraw(), Ok(()));
+        assert_eq!(native_balance(owner()), 1500);
+
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(sale.claim(), Ok(()));
+        assert_eq!(mock::total_supply(token()), 15_000);
+    }
+
+    #[ink::test]
+    fn contributions_are_capped() {
+        let mut sale = deploy(config(FIXED, TokenSource::Mint));
+        at(START, alice());
+        buy(&mut sale, alice(), 1500, None).expect("Bought");
+        assert_eq!(buy(&mut sale, alice(), 501, None), Err(CrowdsaleError::BuyerCapExceeded));
+        buy(&mut sale, alice(), 500, None).expect("Bought");
+        buy(&mut sale, bob(), 2000, None).expect("Bought");
+        assert_eq!(buy(&mut sale, charlie(), 1001, None), Err(CrowdsaleError::HardCapExceeded));
+        assert_eq!(sale.status(), SaleStatus::Open);
+
+        // Reaching the hard cap closes the sale before its end
+        at(START + 10, charlie());
+        buy(&mut sale, charlie(), 1000, None).expect("Bought");
+        assert_eq!(sale.raised(), 5000);
+        assert_eq!(sale.status(), SaleStatus::Succeeded);
+        assert_eq!(buy(&mut sale, bob(), 1, None), Err(CrowdsaleError::SaleNotOpen));
+        set_caller::<DefaultEnvironment>(charlie());
+        assert_eq!(sale.claim(), Ok(()));
+        assert_eq!(mock::balance_of(token(), charlie()), 10_000);
+    }
+
+    #[ink::test]
+    fn allowlisted_sale_accepts_proofs() {
+        let tree = MerkleTree::new([(alice(), 500), (bob(), 1000)]).expect("Valid allowlist");
+        let mut sale = deploy(SaleConfig {
+            allowlist_root: Some(tree.root()),
+            ..config(FIXED, TokenSource::Mint)
+        });
+        let allowance = |index: u32, cap: u128| {
+            Some(Allowance {
+                index,
+                cap,
+                proof: tree.proof(index).expect("Entry exists"),
+            })
+        };
+
+        at(START, alice());
+        assert_eq!(buy(&mut sale, alice(), 100, None), Err(CrowdsaleError::NotAllowlisted));
+        assert_eq!(buy(&mut sale, charlie(), 100, allowance(1, 1000)), Err(CrowdsaleError::NotAllowlisted));
+        assert_eq!(buy(&mut sale, alice(), 501, allowance(0, 500)), Err(CrowdsaleError::BuyerCapExceeded));
+        assert_eq!(buy(&mut sale, alice(), 500, allowance(0, 500)), Ok(()));
+        assert_eq!(buy(&mut sale, bob(), 1000, allowance(1, 1000)), Ok(()));
+        assert_eq!(sale.raised(), 1500);
+    }
+
+    #[ink::test]
+    fn failed_sale_refunds_buyers() {
+        let mut sale = deploy(config(FIXED, TokenSource::Balance));
+        mock::deploy(token(), sale.max_tokens(), sale_account());
+        at(START, alice());
+        buy(&mut sale, alice(), 600, None).expect("Bought");
+        buy(&mut sale, bob(), 300, None).expect("Bought");
+        assert_eq!(sale.refund(), Err(CrowdsaleError::SaleNotEnded));
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(sale.withdraw(), Err(CrowdsaleError::SaleNotEnded));
+
+        at(END, alice());
+        set_account_balance::<DefaultEnvironment>(alice(), 0);
+        assert_eq!(sale.status(), SaleStatus::Failed);
+        assert_eq!(sale.claim(), Err(CrowdsaleError::SoftCapMissed));
+        assert_eq!(sale.claimable(alice()), 0);
+        assert_eq!(sale.refund(), Ok(()));
+        assert_eq!(native_balance(alice()), 600);
+        assert_eq!(sale.refund(), Err(CrowdsaleError::NothingToRefund));
+
+        // The owner gets all tokens back, but none of the contributions
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(sale.withdraw(), Ok(()));
+        assert_eq!(mock::balance_of(token(), owner()), 50_000);
+        assert_eq!(native_balance(owner()), 0);
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(sale.refund(), Ok(()));
+        assert_eq!(native_balance(sale_account()), 0);
+    }
+
+    #[ink::test]
+    fn dutch_auction_settles_at_clearing_price() {
+        let mut sale = deploy(config(DUTCH, TokenSource::Balance));
+        mock::deploy(token(), sale.max_tokens(), sale_account());
+        at(START, alice());
+        buy(&mut sale, alice(), 2000, None).expect("Bought");
+        assert_eq!(sale.purchased(alice()), 1000);
+
+        at(1500, bob());
+        assert_eq!(sale.price(), 3 * PRICE_UNIT / 2);
+        buy(&mut sale, bob(), 1000, None).expect("Bought");
+        // All buyers pay the same price, estimated at the current one until the sale closes
+        assert_eq!(sale.purchased(alice()), 1333);
+        assert_eq!(sale.purchased(bob()), 666);
+
+        at(END + 1000, owner());
+        assert_eq!(sale.price(), PRICE_UNIT);
+        assert_eq!(sale.withdraw(), Ok(()));
+        assert_eq!(native_balance(owner()), 3000);
+        // Tokens of the buyers stay in the sale
+        assert_eq!(mock::balance_of(token(), owner()), 2000);
+
+        set_caller::<DefaultEnvironment>(alice());
+        sale.claim().expect("Claimed");
+        set_caller::<DefaultEnvironment>(bob());
+        sale.claim().expect("Claimed");
+        assert_eq!(mock::balance_of(token(), alice()), 2000);
+        assert_eq!(mock::balance_of(token(), bob()), 1000);
+        assert_eq!(mock::balance_of(token(), sale_account()), 0);
+    }
+
+    #[ink::test]
+    fn hard_cap_fixes_clearing_price() {
+        let mut sale = deploy(config(DUTCH, TokenSource::Mint));
+        at(1500, alice());
+        buy(&mut sale, alice(), 2000, None).expect("Bought");
+        buy(&mut sale, bob(), 2000, None).expect("Bought");
+        at(1750, charlie());
+        buy(&mut sale, charlie(), 1000, None).expect("Bought");
+
+        at(END, alice());
+        assert_eq!(sale.price(), 5 * PRICE_UNIT / 4);
+        assert_eq!(sale.purchased(alice()), 1600);
+        sale.claim().expect("Claimed");
+        assert_eq!(mock::balance_of(token(), alice()), 1600);
+    }
+
+    #[ink::test]
+    fn claims_follow_vesting_schedule() {
+        let mut sale = deploy(SaleConfig {
+            vesting: Some(VestingSchedule::linear(END, 100, 1000).expect("Valid schedule")),
+            ..config(FIXED, TokenSource::Balance)
+        });
+        mock::deploy(token(), sale.max_tokens(), sale_account());
+        at(START, alice());
+        buy(&mut sale, alice(), 1000, None).expect("Bought");
+
+        at(END + 99, alice());
+        assert_eq!(sale.claimable(alice()), 0);
+        assert_eq!(sale.claim(), Err(CrowdsaleError::NothingToClaim));
+
+        at(END + 250, alice());
+        assert_eq!(sale.claimable(alice()), 2500);
+        sale.claim().expect("Claimed");
+        assert_eq!(sale.claimed(alice()), 2500);
+
+        // Unclaimed tokens are kept from the withdrawal
+        at(END + 500, owner());
+        sale.withdraw().expect("Withdrawn");
+        assert_eq!(mock::balance_of(token(), owner()), 40_000);
+        assert_eq!(mock::balance_of(token(), sale_account()), 7500);
+
+        at(END + 2000, alice());
+        sale.claim().expect("Claimed");
+        assert_eq!(mock::balance_of(token(), alice()), 10_000);
+    }
+
+    #[ink::test]
+    fn unfunded_sale_fails_claims() {
+        let mut sale = deploy(config(FIXED, TokenSource::Balance));
+        at(START, alice());
+        buy(&mut sale, alice(), 1000, None).expect("Bought");
+        at(END, alice());
+        assert_eq!(
+            sale.claim(),
+            Err(CrowdsaleError::PSP22(PSP22Error::InsufficientBalance))
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Module describing the buyout auction of the vaulted token.
+
+use ink::primitives::AccountId;
+use primitive_types::U256;
+
+use crate::VaultError;
+
+/// Denominator of `MIN_BID_INCREMENT`.
+pub const BASIS_POINTS: u128 = 10_000;
+
+/// Minimal increase of a bid over the highest one, in basis points (5%).
+pub const MIN_BID_INCREMENT: u128 = 500;
+
+/// Time left in the auction after a bid, in milliseconds (15 minutes).
+/// Bids placed later extend the auction, so that other bidders can answer them.
+pub const BID_EXTENSION: u64 = 15 * 60 * 1000;
+
+/// Returns `a * b / c` rounded down, without intermediate overflow.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    u128::try_from(result).unwrap_or(u128::MAX)
+}
+
+/// Stage of the life of a vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum VaultState {
+    /// The token has not been deposited yet.
+    Pending,
+    /// The token is held by the vault and its shares are minted. The first bid starts the auction.
+    Active,
+    /// The auction is running.
+    Auction,
+    /// The token was sold, share holders redeem the proceeds.
+    Sold,
+}
+
+/// Buyout auction of the vaulted token, paid in native currency.
+///
+/// The first bid of at least `reserve_price` starts an auction lasting `duration` milliseconds.
+/// Every following bid must exceed the highest one by `MIN_BID_INCREMENT`, and a bid placed less
+/// than `BID_EXTENSION` before the end extends the auction. After the end, the highest bidder gets
+/// the token and the share holders redeem the highest bid in proportion to their shares.
+/// All timestamps are in milliseconds, like block timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Auction {
+    state: VaultState,
+    reserve_price: u128,
+    duration: u64,
+    end: u64,
+    bidder: Option<AccountId>,
+    bid: u128,
+    proceeds: u128,
+}
+
+impl Auction {
+    /// Creates the auction of a vault whose token has not been deposited yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDuration` if `duration` is zero.
+    pub fn new(reserve_price: u128, duration: u64) -> Result<Auction, VaultError> {
+        if duration == 0 {
+            return Err(VaultError::InvalidDuration);
+        }
+        Ok(Auction {
+            state: VaultState::Pending,
+            reserve_price,
+            duration,
+            end: 0,
+            bidder: None,
+            bid: 0,
+            proceeds: 0,
+        })
+    }
+
+    pub fn state(&self) -> VaultState {
+        self.state
+    }
+
+    pub fn reserve_price(&self) -> u128 {
+        self.reserve_price
+    }
+
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
+
+    /// Returns the timestamp at which the auction ends, zero before it starts.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    pub fn highest_bidder(&self) -> Option<AccountId> {
+        self.bidder
+    }
+
+    pub fn highest_bid(&self) -> u128 {
+        self.bid
+    }
+
+    /// Returns the part of the sale price not redeemed yet.
+    pub fn proceeds(&self) -> u128 {
+        self.proceeds
+    }
+
+    /// Returns the lowest bid accepted now.
+    pub fn min_bid(&self) -> u128 {
+        match self.bidder {
+            None => self.reserve_price.max(1),
+            Some(_) => self
+                .bid
+                .saturating_add((self.bid.saturating_mul(MIN_BID_INCREMENT) / BASIS_POINTS).max(1)),
+        }
+    }
+
+    /// Marks the token as deposited.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlreadyDeposited` if it was deposited before.
+    pub fn activate(&mut self) -> Result<(), VaultError> {
+        if self.state != VaultState::Pending {
+            return Err(VaultError::AlreadyDeposited);
+        }
+        self.state = VaultState::Active;
+        Ok(())
+    }
+
+    /// Records a bid of `value` by `bidder` and returns the outbid bidder with the bid to refund.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotDeposited` before the token is deposited, `AuctionEnded` once the auction
+    /// has ended and `BidTooLow` if `value` is below `min_bid`.
+    pub fn bid(&mut self, bidder: AccountId, value: u128, now: u64) -> Result<Option<(AccountId, u128)>, VaultError> {
+        match self.state {
+            VaultState::Pending => return Err(VaultError::NotDeposited),
+            VaultState::Sold => return Err(VaultError::AuctionEnded),
+            VaultState::Auction if now >= self.end => return Err(VaultError::AuctionEnded),
+            _ => {}
+        }
+        if value < self.min_bid() {
+            return Err(VaultError::BidTooLow);
+        }
+        if self.state == VaultState::Active {
+            self.state = VaultState::Auction;
+            self.end = now.saturating_add(self.duration);
+        } else if self.end - now < BID_EXTENSION {
+            self.end = now.saturating_add(BID_EXTENSION);
+        }
+        let refund = self.bidder.map(|previous| (previous, self.bid));
+        self.bidder = Some(bidder);
+        self.bid = value;
+        Ok(refund)
+    }
+
+    /// Ends the auction and returns the winner with the sale price.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuctionNotStarted` if nobody has bid yet, `AuctionNotEnded` before the end
+    /// and `AuctionEnded` if the auction has already been ended.
+    pub fn finish(&mut self, now: u64) -> Result<(AccountId, u128), VaultError> {
+        match self.state {
+            VaultState::Sold => return Err(VaultError::AuctionEnded),
+            VaultState::Auction => {}
+            _ => return Err(VaultError::AuctionNotStarted),
+        }
+        if now < self.end {
+            return Err(VaultError::AuctionNotEnded);
+        }
+        let winner = self.bidder.ok_or(VaultError::AuctionNotStarted)?;
+        self.state = VaultState::Sold;
+        self.proceeds = self.bid;
+        Ok((winner, self.bid))
+    }
+
+    /// Returns the part of the proceeds paid for `shares` out of `total_shares` and deducts it.
+    ///
+    /// The shares must be burned afterwards, so that the last holder receives all remaining proceeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotSold` before the auction has been ended and `NoShares` if `shares` is zero.
+    pub fn redeem(&mut self, shares: u128, total_shares: u128) -> Result<u128, VaultError> {
+        if self.state != VaultState::Sold {
+            return Err(VaultError::NotSold);
+        }
+        if shares == 0 {
+            return Err(VaultError::NoShares);
+        }
+        let payout = mul_div(self.proceeds, shares, total_shares.max(shares));
+        self.proceeds -= payout;
+        Ok(payout)
+    }
+}
@@ -0,0 +1,52 @@
+//! Error types for the fractionalization vault.
+
+use psp22_full::PSP22Error;
+use psp34_full::PSP34Error;
+
+/// Errors that may occur during vault operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VaultError {
+    /// The caller is not the curator of the vault.
+    NotCurator,
+    /// The share supply must be greater than zero.
+    ZeroSupply,
+    /// The auction must last longer than zero.
+    InvalidDuration,
+    /// The token was already deposited.
+    AlreadyDeposited,
+    /// The token was not deposited yet.
+    NotDeposited,
+    /// The auction has not started yet.
+    AuctionNotStarted,
+    /// The auction is still running.
+    AuctionNotEnded,
+    /// The auction has ended, no more bids are accepted.
+    AuctionEnded,
+    /// The bid is below the reserve price or does not exceed the highest bid enough.
+    BidTooLow,
+    /// The token has not been sold yet, so there are no proceeds to redeem.
+    NotSold,
+    /// The caller holds no shares.
+    NoShares,
+    /// The caller has no outbid bids to withdraw.
+    NoRefund,
+    /// A transfer of native currency failed.
+    TransferFailed,
+    /// The share token returned an error.
+    PSP22(PSP22Error),
+    /// The collection of the token returned an error.
+    PSP34(PSP34Error),
+}
+
+impl From<PSP22Error> for VaultError {
+    fn from(error: PSP22Error) -> Self {
+        VaultError::PSP22(error)
+    }
+}
+
+impl From<PSP34Error> for VaultError {
+    fn from(error: PSP34Error) -> Self {
+        VaultError::PSP34(error)
+    }
+}
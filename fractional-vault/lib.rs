@@ -0,0 +1,428 @@
+//! Vault fractionalizing a PSP34 token into PSP22 shares.
+//!
+//! The curator deposits a PSP34 token into the vault, which mints a fixed supply of shares of it.
+//! The vault is itself the PSP22 share token. Anyone can buy the whole token out in an auction paid
+//! in native currency, after which the share holders redeem the sale price pro rata.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+
+pub use data::{Auction, VaultState, BASIS_POINTS, BID_EXTENSION, MIN_BID_INCREMENT};
+pub use errors::VaultError;
+
+/// Fractionalization vault implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod vault {
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::Mapping;
+    use psp22_full::traits::PSP22Metadata;
+    use psp22_full::{PSP22Data, PSP22Error, PSP22};
+    use psp34_full::PSP34Ref;
+    use psp34_full::Id;
+
+    use crate::{Auction, VaultError, VaultState};
+
+    #[ink(storage)]
+    pub struct Vault {
+        /// Account which deposits the token and receives all shares.
+        curator: AccountId,
+        collection: AccountId,
+        id: Id,
+        /// Number of shares minted when the token is deposited.
+        supply: u128,
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: u8,
+        shares: PSP22Data,
+        auction: Auction,
+        /// Bids of outbid bidders, waiting to be withdrawn with `withdraw_refund`.
+        pending_returns: Mapping<AccountId, Balance>,
+    }
+
+    /// PSP22 event of the shares, with the layout documented in `psp22_full::events`.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: u128,
+    }
+
+    /// PSP22 event of the shares, with the layout documented in `psp22_full::events`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: u128,
+    }
+
+    psp22_full::psp22_events!(Vault);
+
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        curator: AccountId,
+        id: Id,
+        supply: u128,
+    }
+
+    #[ink(event)]
+    pub struct Bid {
+        #[ink(topic)]
+        bidder: AccountId,
+        value: Balance,
+        end: u64,
+    }
+
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        bidder: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AuctionEnded {
+        #[ink(topic)]
+        winner: AccountId,
+        price: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Redeemed {
+        #[ink(topic)]
+        holder: AccountId,
+        shares: u128,
+        payout: Balance,
+    }
+
+    impl Vault {
+        /// Creates a vault curated by the caller, waiting for the token to be deposited.
+        ///
+        /// # Arguments
+        ///
+        /// * `collection` - The PSP34 collection of the token.
+        /// * `id` - The id of the token.
+        /// * `supply` - The number of shares minted to the curator on deposit.
+        /// * `reserve_price` - The lowest first bid, in native currency.
+        /// * `auction_duration` - Length of the auction started by the first bid, in milliseconds.
+        /// * `name`, `symbol`, `decimals` - Metadata of the share token.
+        #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            collection: AccountId,
+            id: Id,
+            supply: u128,
+            reserve_price: Balance,
+            auction_duration: u64,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Result<Self, VaultError> {
+            if supply == 0 {
+                return Err(VaultError::ZeroSupply);
+            }
+            Ok(Self {
+                curator: Self::env().caller(),
+                collection,
+                id,
+                supply,
+                name,
+                symbol,
+                decimals,
+                shares: PSP22Data::new(0, Self::env().caller()),
+                auction: Auction::new(reserve_price, auction_duration)?,
+                pending_returns: Mapping::default(),
+            })
+        }
+
+        // Contracts this one depends on implement `EmitEvent` for the same environment
+        // when built with their `contract` feature, so the contract is named explicitly
+        fn emit_event(&self, event: impl Into<<Vault as ink::reflect::ContractEventBase>::Type>) {
+            ink::codegen::EmitEvent::<Vault>::emit_event(self.env(), event)
+        }
+
+        /// Returns the account which deposits the token.
+        #[ink(message)]
+        pub fn curator(&self) -> AccountId {
+            self.curator
+        }
+
+        #[ink(message)]
+        pub fn collection(&self) -> AccountId {
+            self.collection
+        }
+
+        /// Returns the id of the vaulted token.
+        #[ink(message)]
+        pub fn token_id(&self) -> Id {
+            self.id.clone()
+        }
+
+        #[ink(message)]
+        pub fn state(&self) -> VaultState {
+            self.auction.state()
+        }
+
+        #[ink(message)]
+        pub fn reserve_price(&self) -> Balance {
+            self.auction.reserve_price()
+        }
+
+        /// Returns the timestamp at which the auction ends, zero before it starts.
+        #[ink(message)]
+        pub fn auction_end(&self) -> u64 {
+            self.auction.end()
+        }
+
+        #[ink(message)]
+        pub fn highest_bidder(&self) -> Option<AccountId> {
+            self.auction.highest_bidder()
+        }
+
+        #[ink(message)]
+        pub fn highest_bid(&self) -> Balance {
+            self.auction.highest_bid()
+        }
+
+        /// Returns the lowest bid accepted now.
+        #[ink(message)]
+        pub fn min_bid(&self) -> Balance {
+            self.auction.min_bid()
+        }
+
+        /// Returns the bids of `bidder` which were outbid and not withdrawn yet.
+        #[ink(message)]
+        pub fn pending_return(&self, bidder: AccountId) -> Balance {
+            self.pending_returns.get(bidder).unwrap_or_default()
+        }
+
+        /// Returns the part of the sale price not redeemed yet.
+        #[ink(message)]
+        pub fn proceeds(&self) -> Balance {
+            self.auction.proceeds()
+        }
+
+        /// Transfers the token from the curator to the vault and mints all shares to the curator.
+        ///
+        /// The curator must have approved the vault to transfer the token.
+        ///
+        /// # Events
+        ///
+        /// A `Deposited` event and a PSP22 `Transfer` event of the minted shares are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotCurator` if called by an account other than the curator
+        /// and `AlreadyDeposited` if the token was deposited before.
+        #[ink(message)]
+        pub fn deposit(&mut self) -> Result<(), VaultError> {
+            if self.env().caller() != self.curator {
+                return Err(VaultError::NotCurator);
+            }
+            self.auction.activate()?;
+            Self::psp34(self.collection).transfer(self.env().account_id(), self.id.clone(), Vec::new())?;
+            let events = self.shares.mint(self.curator, self.supply)?;
+            self.emit_events(events);
+            self.emit_event(Deposited {
+                curator: self.curator,
+                id: self.id.clone(),
+                supply: self.supply,
+            });
+            Ok(())
+        }
+
+        /// Bids the transferred native currency for the token.
+        ///
+        /// The bid of the outbid bidder is kept for it to withdraw with `withdraw_refund`, so that
+        /// a bidder which cannot receive the currency does not block higher bids. The first bid of at least the reserve price starts the auction. Later bids must
+        /// exceed the highest one by `MIN_BID_INCREMENT` and extend the auction if placed
+        /// less than `BID_EXTENSION` before its end.
+        ///
+        /// # Events
+        ///
+        /// A `Bid` event is emitted with the end of the auction.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotDeposited` before the token is deposited, `AuctionEnded` once the auction
+        /// has ended and `BidTooLow` if the bid is below `min_bid`.
+        #[ink(message, payable)]
+        pub fn bid(&mut self) -> Result<(), VaultError> {
+            let bidder = self.env().caller();
+            let value = self.env().transferred_value();
+            let refund = self.auction.bid(bidder, value, self.env().block_timestamp())?;
+            if let Some((previous, amount)) = refund {
+                let pending = self.pending_return(previous).saturating_add(amount);
+                self.pending_returns.insert(previous, &pending);
+            }
+            self.emit_event(Bid {
+                bidder,
+                value,
+                end: self.auction.end(),
+            });
+            Ok(())
+        }
+
+        /// Transfers the outbid bids of the caller back to it.
+        ///
+        /// # Events
+        ///
+        /// A `Refunded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NoRefund` if the caller has no outbid bids to withdraw
+        /// and `TransferFailed` if the currency could not be transferred.
+        #[ink(message)]
+        pub fn withdraw_refund(&mut self) -> Result<(), VaultError> {
+            let bidder = self.env().caller();
+            let value = self.pending_returns.take(bidder).unwrap_or_default();
+            if value == 0 {
+                return Err(VaultError::NoRefund);
+            }
+            self.env()
+                .transfer(bidder, value)
+                .map_err(|_| VaultError::TransferFailed)?;
+            self.emit_event(Refunded { bidder, value });
+            Ok(())
+        }
+
+        /// Ends the auction and transfers the token to the highest bidder. Can be called by anyone.
+        ///
+        /// # Events
+        ///
+        /// An `AuctionEnded` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AuctionNotStarted` if nobody has bid yet, `AuctionNotEnded` before the end
+        /// and `AuctionEnded` if the auction has already been ended.
+        #[ink(message)]
+        pub fn end_auction(&mut self) -> Result<(), VaultError> {
+            let (winner, price) = self.auction.finish(self.env().block_timestamp())?;
+            Self::psp34(self.collection).transfer(winner, self.id.clone(), Vec::new())?;
+            self.emit_event(AuctionEnded { winner, price });
+            Ok(())
+        }
+
+        /// Burns all shares of the caller and pays the caller its part of the sale price.
+        ///
+        /// # Events
+        ///
+        /// A PSP22 `Transfer` event of the burned shares and a `Redeemed` event are emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotSold` before the auction has been ended and `NoShares` if the caller holds no shares.
+        #[ink(message)]
+        pub fn redeem(&mut self) -> Result<(), VaultError> {
+            let holder = self.env().caller();
+            let shares = self.shares.balance_of(holder);
+            let payout = self.auction.redeem(shares, self.shares.total_supply())?;
+            let events = self.shares.burn(holder, shares)?;
+            if payout > 0 {
+                self.env()
+                    .transfer(holder, payout)
+                    .map_err(|_| VaultError::TransferFailed)?;
+            }
+            self.emit_events(events);
+            self.emit_event(Redeemed {
+                holder,
+                shares,
+                payout,
+            });
+            Ok(())
+        }
+
+        fn psp34(collection: AccountId) -> PSP34Ref {
+            PSP34Ref::new(collection)
+        }
+    }
+
+    impl PSP22 for Vault {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.shares.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.shares.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.shares.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128, _data: Vec<u8>) -> Result<(), PSP22Error> {
+            let events = self.shares.transfer(self.env().caller(), to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .shares
+                .transfer_from(self.env().caller(), from, to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.shares.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> {
+            let events = self
+                .shares
+                .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: u128) -> Result<(), PSP22Error> {
+            let events = self
+                .shares
+                .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Metadata for Vault {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+    }
+}
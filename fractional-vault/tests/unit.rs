@@ -0,0 +1,286 @@
+use fractional_vault::vault::*;
+use psp34_full::mock;
+use fractional_vault::{Auction, VaultError, VaultState, BID_EXTENSION};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{
+        get_account_balance, recorded_events, set_account_balance, set_block_timestamp, set_callee,
+        set_caller, set_value_transferred,
+    }};
+    use psp22_full::traits::PSP22Metadata;
+    use psp22_full::{PSP22Error, PSP22Event, PSP22};
+    use psp34_full::{Id, PSP34Error};
+    use super::*;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn curator() -> AccountId {
+        AccountId::from([0x1; 32])
+    }
+
+    fn alice() -> AccountId {
+        AccountId::from([0x2; 32])
+    }
+
+    fn bob() -> AccountId {
+        AccountId::from([0x3; 32])
+    }
+
+    fn charlie() -> AccountId {
+        AccountId::from([0x4; 32])
+    }
+
+    fn vault_account() -> AccountId {
+        AccountId::from([0x10; 32])
+    }
+
+    fn collection() -> AccountId {
+        AccountId::from([0x20; 32])
+    }
+
+    fn native_balance(account: AccountId) -> u128 {
+        get_account_balance::<DefaultEnvironment>(account).unwrap_or_default()
+    }
+
+    // Decodes the event at `index` as a PSP22 `Transfer`
+    fn decode_transfer(index: usize) -> (Option<AccountId>, Option<AccountId>, u128) {
+        let event = recorded_events().nth(index).expect("Event expected");
+        match psp22_full::events::decode_event(&event.topics[0], &event.data) {
+            Some(PSP22Event::Transfer { from, to, value }) => (from, to, value),
+            _ => panic!("Transfer event expected"),
+        }
+    }
+
+    /// Deploys a vault of token 7 held by the curator, with 1000 shares, a reserve price of 100
+    /// and auctions lasting a day, and deposits the token.
+    fn setup() -> Vault {
+        mock::deploy(collection());
+        mock::mint(collection(), curator(), Id::U8(7));
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(vault_account());
+        set_account_balance::<DefaultEnvironment>(vault_account(), 0);
+        set_caller::<DefaultEnvironment>(curator());
+        let mut vault = Vault::new(
+            collection(),
+            Id::U8(7),
+            1000,
+            100,
+            24 * HOUR,
+            Some("Beaver shares".into()),
+            Some("BVS".into()),
+            0,
+        )
+        .expect("Valid vault");
+        mock::approve(collection(), curator(), vault_account(), Id::U8(7));
+        vault.deposit().expect("Deposited");
+        vault
+    }
+
+    // Calls `bid` as `bidder` with `value` of native currency
+    // (the off-chain environment does not move the transferred value itself)
+    fn bid(vault: &mut Vault, bidder: AccountId, value: u128) -> Result<(), VaultError> {
+        set_account_balance::<DefaultEnvironment>(vault_account(), native_balance(vault_account()) + value);
+        set_caller::<DefaultEnvironment>(bidder);
+        set_value_transferred::<DefaultEnvironment>(value);
+        let result = vault.bid();
+        set_value_transferred::<DefaultEnvironment>(0);
+        if result.is_err() {
+            set_account_balance::<DefaultEnvironment>(vault_account(), native_balance(vault_account()) - value);
+        }
+        result
+    }
+
+    #[test]
+    fn first_bid_starts_auction() {
+        let mut auction = Auction::new(100, 1000).expect("Valid auction");
+        assert_eq!(auction.bid(alice(), 100, 0), Err(VaultError::NotDeposited));
+        auction.activate().expect("Activated");
+        assert_eq!(auction.activate(), Err(VaultError::AlreadyDeposited));
+        assert_eq!(auction.finish(0), Err(VaultError::AuctionNotStarted));
+
+        assert_eq!(auction.min_bid(), 100);
+        assert_eq!(auction.bid(alice(), 99, 10), Err(VaultError::BidTooLow));
+        assert_eq!(auction.bid(alice(), 100, 10), Ok(None));
+        assert_eq!(auction.state(), VaultState::Auction);
+        assert_eq!(auction.end(), 1010);
+        assert_eq!(auction.highest_bidder(), Some(alice()));
+    }
+
+    #[test]
+    fn bids_must_exceed_highest_by_increment() {
+        let mut auction = Auction::new(0, 100 * BID_EXTENSION).expect("Valid auction");
+        auction.activate().expect("Activated");
+        // A reserve price of zero still requires a bid
+        assert_eq!(auction.bid(alice(), 0, 0), Err(VaultError::BidTooLow));
+        auction.bid(alice(), 1000, 0).expect("Bid placed");
+        assert_eq!(auction.min_bid(), 1050);
+        assert_eq!(auction.bid(bob(), 1049, 1), Err(VaultError::BidTooLow));
+        assert_eq!(auction.bid(bob(), 1050, 1), Ok(Some((alice(), 1000))));
+        assert_eq!(auction.bid(alice(), 2000, 2), Ok(Some((bob(), 1050))));
+        assert_eq!(auction.highest_bid(), 2000);
+    }
+
+    #[test]
+    fn late_bid_extends_auction() {
+        let mut auction = Auction::new(100, 10 * BID_EXTENSION).expect("Valid auction");
+        auction.activate().expect("Activated");
+        auction.bid(alice(), 100, 0).expect("Bid placed");
+        auction.bid(bob(), 200, BID_EXTENSION).expect("Bid placed");
+        assert_eq!(auction.end(), 10 * BID_EXTENSION);
+
+        auction.bid(alice(), 300, 10 * BID_EXTENSION - 1).expect("Bid placed");
+        assert_eq!(auction.end(), 11 * BID_EXTENSION - 1);
+        assert_eq!(auction.finish(11 * BID_EXTENSION - 2), Err(VaultError::AuctionNotEnded));
+        assert_eq!(auction.bid(bob(), 400, 11 * BID_EXTENSION - 1), Err(VaultError::AuctionEnded));
+    }
+
+    #[test]
+    fn proceeds_are_redeemed_pro_rata() {
+        let mut auction = Auction::new(100, 1000).expect("Valid auction");
+        auction.activate().expect("Activated");
+        assert_eq!(auction.redeem(1, 3), Err(VaultError::NotSold));
+        auction.bid(alice(), 1000, 0).expect("Bid placed");
+        assert_eq!(auction.finish(1000), Ok((alice(), 1000)));
+        assert_eq!(auction.state(), VaultState::Sold);
+        assert_eq!(auction.finish(1000), Err(VaultError::AuctionEnded));
+        assert_eq!(auction.bid(bob(), 2000, 1000), Err(VaultError::AuctionEnded));
+
+        assert_eq!(auction.redeem(0, 3), Err(VaultError::NoShares));
+        assert_eq!(auction.redeem(1, 3), Ok(333));
+        assert_eq!(auction.redeem(1, 2), Ok(333));
+        // The last holder receives the rounding dust
+        assert_eq!(auction.redeem(1, 1), Ok(334));
+        assert_eq!(auction.proceeds(), 0);
+    }
+
+    #[test]
+    fn zero_duration_is_invalid() {
+        assert_eq!(Auction::new(100, 0), Err(VaultError::InvalidDuration));
+    }
+
+    #[ink::test]
+    fn deposit_mints_shares_to_curator() {
+        let vault = setup();
+        assert_eq!(mock::owner_of(collection(), Id::U8(7)), Some(vault_account()));
+        assert_eq!(vault.state(), VaultState::Active);
+        assert_eq!(vault.total_supply(), 1000);
+        assert_eq!(vault.balance_of(curator()), 1000);
+        assert_eq!(vault.token_name(), Some("Beaver shares".into()));
+        assert_eq!(vault.token_symbol(), Some("BVS".into()));
+        assert_eq!(vault.token_decimals(), 0);
+        assert_eq!(decode_transfer(0), (None, Some(curator()), 1000));
+    }
+
+    #[ink::test]
+    fn deposit_is_restricted() {
+        mock::deploy(collection());
+        mock::mint(collection(), curator(), Id::U8(7));
+        set_callee::<DefaultEnvironment>(vault_account());
+        set_caller::<DefaultEnvironment>(curator());
+        let new = |supply| Vault::new(collection(), Id::U8(7), supply, 100, HOUR, None, None, 0);
+        assert_eq!(new(0).err(), Some(VaultError::ZeroSupply));
+        let mut vault = new(1000).expect("Valid vault");
+
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(vault.deposit(), Err(VaultError::NotCurator));
+        set_caller::<DefaultEnvironment>(curator());
+        // A failed message reverts on chain, the off-chain test needs a fresh vault afterwards
+        let mut unapproved = new(1000).expect("Valid vault");
+        assert_eq!(unapproved.deposit(), Err(VaultError::PSP34(PSP34Error::NotApproved)));
+
+        mock::approve(collection(), curator(), vault_account(), Id::U8(7));
+        assert_eq!(vault.deposit(), Ok(()));
+        assert_eq!(vault.deposit(), Err(VaultError::AlreadyDeposited));
+        assert_eq!(vault.total_supply(), 1000);
+    }
+
+    #[ink::test]
+    fn buyout_pays_share_holders() {
+        let mut vault = setup();
+        set_caller::<DefaultEnvironment>(curator());
+        vault.transfer(alice(), 250, Vec::new()).expect("Transferred");
+
+        set_account_balance::<DefaultEnvironment>(bob(), 0);
+        set_account_balance::<DefaultEnvironment>(charlie(), 0);
+        assert_eq!(bid(&mut vault, bob(), 99), Err(VaultError::BidTooLow));
+        assert_eq!(bid(&mut vault, bob(), 1000), Ok(()));
+        assert_eq!(vault.auction_end(), 24 * HOUR);
+        assert_eq!(vault.min_bid(), 1050);
+        assert_eq!(bid(&mut vault, charlie(), 2000), Ok(()));
+        assert_eq!(vault.highest_bidder(), Some(charlie()));
+        // The outbid bid waits for its bidder to withdraw it
+        assert_eq!(native_balance(bob()), 0);
+        assert_eq!(vault.pending_return(bob()), 1000);
+        assert_eq!(bid(&mut vault, bob(), 2100), Ok(()));
+        assert_eq!(bid(&mut vault, charlie(), 2500), Ok(()));
+        assert_eq!(vault.pending_return(bob()), 3100);
+        assert_eq!(vault.pending_return(charlie()), 2000);
+
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(vault.withdraw_refund(), Ok(()));
+        assert_eq!(native_balance(bob()), 3100);
+        assert_eq!(vault.pending_return(bob()), 0);
+        assert_eq!(vault.withdraw_refund(), Err(VaultError::NoRefund));
+        set_caller::<DefaultEnvironment>(charlie());
+        assert_eq!(vault.withdraw_refund(), Ok(()));
+        assert_eq!(native_balance(charlie()), 2000);
+        assert_eq!(native_balance(vault_account()), 2500);
+
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(vault.redeem(), Err(VaultError::NotSold));
+        assert_eq!(vault.end_auction(), Err(VaultError::AuctionNotEnded));
+        set_block_timestamp::<DefaultEnvironment>(24 * HOUR);
+        assert_eq!(vault.end_auction(), Ok(()));
+        assert_eq!(vault.state(), VaultState::Sold);
+        assert_eq!(mock::owner_of(collection(), Id::U8(7)), Some(charlie()));
+
+        set_account_balance::<DefaultEnvironment>(alice(), 0);
+        assert_eq!(vault.redeem(), Ok(()));
+        assert_eq!(native_balance(alice()), 625);
+        assert_eq!(vault.balance_of(alice()), 0);
+        assert_eq!(vault.redeem(), Err(VaultError::NoShares));
+
+        set_caller::<DefaultEnvironment>(curator());
+        set_account_balance::<DefaultEnvironment>(curator(), 0);
+        assert_eq!(vault.redeem(), Ok(()));
+        assert_eq!(native_balance(curator()), 1875);
+        assert_eq!(vault.total_supply(), 0);
+        assert_eq!(vault.proceeds(), 0);
+        assert_eq!(native_balance(vault_account()), 0);
+    }
+
+    #[ink::test]
+    fn bids_need_deposit_and_end_with_auction() {
+        mock::deploy(collection());
+        set_callee::<DefaultEnvironment>(vault_account());
+        set_account_balance::<DefaultEnvironment>(vault_account(), 0);
+        set_caller::<DefaultEnvironment>(curator());
+        let mut vault = Vault::new(collection(), Id::U8(7), 1000, 100, HOUR, None, None, 0).expect("Valid vault");
+        assert_eq!(bid(&mut vault, bob(), 100), Err(VaultError::NotDeposited));
+
+        let mut vault = setup();
+        assert_eq!(vault.end_auction(), Err(VaultError::AuctionNotStarted));
+        bid(&mut vault, bob(), 100).expect("Bid placed");
+        set_block_timestamp::<DefaultEnvironment>(24 * HOUR);
+        assert_eq!(bid(&mut vault, charlie(), 200), Err(VaultError::AuctionEnded));
+        assert_eq!(vault.end_auction(), Ok(()));
+        assert_eq!(vault.end_auction(), Err(VaultError::AuctionEnded));
+    }
+
+    #[ink::test]
+    fn shares_are_psp22_tokens() {
+        let mut vault = setup();
+        set_caller::<DefaultEnvironment>(curator());
+        assert_eq!(vault.approve(alice(), 100), Ok(()));
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(vault.transfer_from(curator(), bob(), 60, Vec::new()), Ok(()));
+        assert_eq!(vault.allowance(curator(), alice()), 40);
+        assert_eq!(
+            vault.transfer_from(curator(), bob(), 60, Vec::new()),
+            Err(PSP22Error::InsufficientAllowance)
+        );
+        assert_eq!(vault.balance_of(bob()), 60);
+    }
+}
@@ -0,0 +1,55 @@
+//! Module verifying claims against the Merkle root and tracking paid claims.
+
+use ink::env::hash::Blake2x256;
+use ink::{primitives::AccountId, storage::Mapping};
+
+/// A node of the Merkle tree.
+pub type Node = [u8; 32];
+
+const BITS_PER_WORD: u32 = u128::BITS;
+
+/// Returns the leaf of the claim of `amount` by `account` at `index`:
+/// the Blake2x256 hash of the SCALE encoding of the three values.
+pub fn leaf_hash(index: u32, account: AccountId, amount: u128) -> Node {
+    let mut leaf = [0u8; 32];
+    ink::env::hash_encoded::<Blake2x256, _>(&(index, account, amount), &mut leaf);
+    leaf
+}
+
+/// Returns the parent of two nodes: the Blake2x256 hash of the lower node followed by the higher one.
+///
+/// Sorting the pair makes the proofs independent of the side of each node.
+pub fn hash_pair(a: &Node, b: &Node) -> Node {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(low);
+    input[32..].copy_from_slice(high);
+    let mut parent = [0u8; 32];
+    ink::env::hash_bytes::<Blake2x256>(&input, &mut parent);
+    parent
+}
+
+/// Returns `true` if hashing `leaf` with the nodes of `proof`, from the bottom up, gives `root`.
+pub fn verify(proof: &[Node], root: &Node, leaf: Node) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+/// Claimed flags of all claims, packed by 128 into a bitmap.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct ClaimedBitmap {
+    words: Mapping<u32, u128>,
+}
+
+impl ClaimedBitmap {
+    pub fn is_claimed(&self, index: u32) -> bool {
+        let word = self.words.get(index / BITS_PER_WORD).unwrap_or_default();
+        word & (1 << (index % BITS_PER_WORD)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        let word = self.words.get(index / BITS_PER_WORD).unwrap_or_default();
+        self.words
+            .insert(index / BITS_PER_WORD, &(word | (1 << (index % BITS_PER_WORD))));
+    }
+}
@@ -0,0 +1,27 @@
+//! Error types for the Merkle distributor.
+
+use psp22_full::PSP22Error;
+
+/// Errors that may occur during distributor operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum DistributorError {
+    /// The caller is not the owner of the distributor.
+    NotOwner,
+    /// The claim at this index was already paid.
+    AlreadyClaimed,
+    /// The proof does not lead from the claim to the Merkle root.
+    InvalidProof,
+    /// The claim period is over.
+    Expired,
+    /// Unclaimed tokens cannot be swept before the claim period is over.
+    NotExpired,
+    /// The distributed token returned an error.
+    PSP22(PSP22Error),
+}
+
+impl From<PSP22Error> for DistributorError {
+    fn from(error: PSP22Error) -> Self {
+        DistributorError::PSP22(error)
+    }
+}
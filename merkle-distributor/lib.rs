@@ -0,0 +1,168 @@
+//! Merkle airdrop distributor of PSP22 tokens.
+//!
+//! The owner funds the distributor with a PSP22 token and commits to the whole distribution
+//! with the root of its Merkle tree. Every recipient claims its amount with a proof, until the
+//! claim period ends and the owner sweeps the unclaimed tokens.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+#[cfg(feature = "std")]
+pub mod tree;
+
+pub use data::{hash_pair, leaf_hash, verify, ClaimedBitmap, Node};
+pub use errors::DistributorError;
+
+/// Merkle distributor implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod merkle_distributor {
+    use ink::prelude::vec::Vec;
+    use psp22_full::PSP22Ref;
+
+    use crate::{leaf_hash, verify, ClaimedBitmap, DistributorError, Node};
+
+    #[ink(storage)]
+    pub struct MerkleDistributor {
+        /// Account which sweeps the unclaimed tokens.
+        owner: AccountId,
+        token: AccountId,
+        merkle_root: Node,
+        /// Timestamp from which claims are rejected and unclaimed tokens can be swept.
+        expires_at: u64,
+        claimed: ClaimedBitmap,
+    }
+
+    #[ink(event)]
+    pub struct Claimed {
+        index: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct Swept {
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    impl MerkleDistributor {
+        /// Creates a distributor owned by the caller.
+        ///
+        /// # Arguments
+        ///
+        /// * `token` - The PSP22 token distributed. The distributor is funded by transferring it there.
+        /// * `merkle_root` - The root of the Merkle tree of all claims, see `leaf_hash` and `hash_pair`.
+        /// * `expires_at` - The timestamp at which the claim period ends, in milliseconds.
+        #[ink(constructor)]
+        pub fn new(token: AccountId, merkle_root: Node, expires_at: u64) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                token,
+                merkle_root,
+                expires_at,
+                claimed: ClaimedBitmap::default(),
+            }
+        }
+
+        /// Returns the account which sweeps the unclaimed tokens.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        #[ink(message)]
+        pub fn merkle_root(&self) -> Node {
+            self.merkle_root
+        }
+
+        /// Returns the timestamp at which the claim period ends.
+        #[ink(message)]
+        pub fn expires_at(&self) -> u64 {
+            self.expires_at
+        }
+
+        #[ink(message)]
+        pub fn is_claimed(&self, index: u32) -> bool {
+            self.claimed.is_claimed(index)
+        }
+
+        /// Transfers `amount` to `account` for the claim at `index`. Can be called by anyone.
+        ///
+        /// `proof` holds the nodes leading from the leaf of the claim to the Merkle root, from the bottom up.
+        ///
+        /// # Events
+        ///
+        /// A `Claimed` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Expired` once the claim period is over, `AlreadyClaimed` if the claim was paid
+        /// and `InvalidProof` if the claim is not part of the distribution.
+        #[ink(message)]
+        pub fn claim(
+            &mut self,
+            index: u32,
+            account: AccountId,
+            amount: u128,
+            proof: Vec<Node>,
+        ) -> Result<(), DistributorError> {
+            if self.env().block_timestamp() >= self.expires_at {
+                return Err(DistributorError::Expired);
+            }
+            if self.claimed.is_claimed(index) {
+                return Err(DistributorError::AlreadyClaimed);
+            }
+            if !verify(&proof, &self.merkle_root, leaf_hash(index, account, amount)) {
+                return Err(DistributorError::InvalidProof);
+            }
+            self.claimed.set_claimed(index);
+            Self::psp22(self.token).transfer(account, amount, Vec::new())?;
+            self.env().emit_event(Claimed {
+                index,
+                account,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Transfers all tokens left in the distributor to the owner.
+        ///
+        /// # Events
+        ///
+        /// A `Swept` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner
+        /// and `NotExpired` before the claim period is over.
+        #[ink(message)]
+        pub fn sweep(&mut self) -> Result<(), DistributorError> {
+            if self.env().caller() != self.owner {
+                return Err(DistributorError::NotOwner);
+            }
+            if self.env().block_timestamp() < self.expires_at {
+                return Err(DistributorError::NotExpired);
+            }
+            let mut token = Self::psp22(self.token);
+            let amount = token.balance_of(self.env().account_id())?;
+            token.transfer(self.owner, amount, Vec::new())?;
+            self.env().emit_event(Swept {
+                to: self.owner,
+                amount,
+            });
+            Ok(())
+        }
+
+        fn psp22(token: AccountId) -> PSP22Ref {
+            PSP22Ref::new(token)
+        }
+    }
+}
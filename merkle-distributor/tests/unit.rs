@@ -0,0 +1,194 @@
+use merkle_distributor::merkle_distributor::*;
+use merkle_distributor::tree::{MerkleTree, TreeError};
+use psp22_full::mock;
+use merkle_distributor::{leaf_hash, verify, ClaimedBitmap, DistributorError};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{set_block_timestamp, set_callee, set_caller}};
+    use psp22_full::PSP22Error;
+    use super::*;
+
+    const EXPIRY: u64 = 1000;
+
+    fn owner() -> AccountId {
+        AccountId::from([0xAA; 32])
+    }
+
+    fn distributor_account() -> AccountId {
+        AccountId::from([0x10; 32])
+    }
+
+    fn token() -> AccountId {
+        AccountId::from([0x20; 32])
+    }
+
+    fn recipient(byte: u8) -> AccountId {
+        AccountId::from([byte; 32])
+    }
+
+    /// Returns a distribution of `100 * n` tokens to the account filled with `n`, for `n` from 1 to `count`.
+    fn csv(count: u8) -> String {
+        let mut csv = String::from("# account,amount\n");
+        for byte in 1..=count {
+            csv.push_str(&format!("0x{},{}\n", format!("{byte:02x}").repeat(32), 100 * u128::from(byte)));
+        }
+        csv
+    }
+
+    /// Deploys a distributor of `tree` funded with the sum of its claims plus 1000 tokens.
+    fn setup(tree: &MerkleTree) -> MerkleDistributor {
+        let total: u128 = tree.claims().iter().map(|claim| claim.amount).sum();
+        mock::deploy(token(), total + 1000, distributor_account());
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(distributor_account());
+        set_caller::<DefaultEnvironment>(owner());
+        MerkleDistributor::new(token(), tree.root(), EXPIRY)
+    }
+
+    #[test]
+    fn csv_is_parsed() {
+        let tree = MerkleTree::from_csv(&csv(3)).expect("Valid distribution");
+        assert_eq!(tree.claims().len(), 3);
+        assert_eq!(tree.claims()[2].index, 2);
+        assert_eq!(tree.claims()[2].account, recipient(3));
+        assert_eq!(tree.claims()[2].amount, 300);
+
+        let account = "02".repeat(32);
+        assert_eq!(MerkleTree::from_csv("").err(), Some(TreeError::Empty));
+        assert_eq!(
+            MerkleTree::from_csv(&format!("\n{account}\n")).err(),
+            Some(TreeError::InvalidLine { line: 2 })
+        );
+        assert_eq!(
+            MerkleTree::from_csv(&format!("{account},1,2")).err(),
+            Some(TreeError::InvalidLine { line: 1 })
+        );
+        assert_eq!(
+            MerkleTree::from_csv("0x0202,1").err(),
+            Some(TreeError::InvalidAccount { line: 1 })
+        );
+        assert_eq!(
+            MerkleTree::from_csv(&format!("{}zz,1", "02".repeat(31))).err(),
+            Some(TreeError::InvalidAccount { line: 1 })
+        );
+        assert_eq!(
+            MerkleTree::from_csv(&format!("{account}, -1")).err(),
+            Some(TreeError::InvalidAmount { line: 1 })
+        );
+    }
+
+    #[test]
+    fn proofs_of_all_tree_sizes_verify() {
+        for count in 1..=17 {
+            let tree = MerkleTree::from_csv(&csv(count)).expect("Valid distribution");
+            for claim in tree.claims() {
+                let proof = tree.proof(claim.index).expect("Claim exists");
+                let leaf = leaf_hash(claim.index, claim.account, claim.amount);
+                assert!(verify(&proof, &tree.root(), leaf));
+                // Any other amount, account or index fails
+                assert!(!verify(&proof, &tree.root(), leaf_hash(claim.index, claim.account, claim.amount + 1)));
+                assert!(!verify(&proof, &tree.root(), leaf_hash(claim.index, owner(), claim.amount)));
+                assert!(!verify(&proof, &tree.root(), leaf_hash(claim.index + 1, claim.account, claim.amount)));
+            }
+            assert_eq!(tree.proof(u32::from(count)), None);
+        }
+    }
+
+    #[test]
+    fn single_claim_tree_has_leaf_as_root() {
+        let tree = MerkleTree::new([(recipient(1), 100)]).expect("Valid distribution");
+        assert_eq!(tree.root(), leaf_hash(0, recipient(1), 100));
+        assert_eq!(tree.proof(0), Some(Vec::new()));
+    }
+
+    #[ink::test]
+    fn bitmap_tracks_claims() {
+        let mut bitmap = ClaimedBitmap::default();
+        for index in [0, 1, 127, 128, 1000, u32::MAX] {
+            assert!(!bitmap.is_claimed(index));
+            bitmap.set_claimed(index);
+            assert!(bitmap.is_claimed(index));
+        }
+        assert!(!bitmap.is_claimed(2));
+        assert!(!bitmap.is_claimed(126));
+        assert!(!bitmap.is_claimed(129));
+    }
+
+    #[ink::test]
+    fn every_recipient_claims_once() {
+        let tree = MerkleTree::from_csv(&csv(5)).expect("Valid distribution");
+        let mut distributor = setup(&tree);
+        assert_eq!(distributor.merkle_root(), tree.root());
+        assert_eq!(distributor.expires_at(), EXPIRY);
+
+        for claim in tree.claims() {
+            let proof = tree.proof(claim.index).expect("Claim exists");
+            assert!(!distributor.is_claimed(claim.index));
+            // Anyone can submit the claim, the tokens go to the recipient
+            set_caller::<DefaultEnvironment>(owner());
+            assert_eq!(distributor.claim(claim.index, claim.account, claim.amount, proof.clone()), Ok(()));
+            assert!(distributor.is_claimed(claim.index));
+            assert_eq!(mock::balance_of(token(), claim.account), claim.amount);
+            assert_eq!(
+                distributor.claim(claim.index, claim.account, claim.amount, proof),
+                Err(DistributorError::AlreadyClaimed)
+            );
+        }
+        assert_eq!(mock::balance_of(token(), distributor_account()), 1000);
+    }
+
+    #[ink::test]
+    fn invalid_claims_are_rejected() {
+        let tree = MerkleTree::from_csv(&csv(4)).expect("Valid distribution");
+        let mut distributor = setup(&tree);
+        let proof = tree.proof(1).expect("Claim exists");
+        assert_eq!(
+            distributor.claim(1, recipient(2), 201, proof.clone()),
+            Err(DistributorError::InvalidProof)
+        );
+        assert_eq!(
+            distributor.claim(1, recipient(3), 200, proof.clone()),
+            Err(DistributorError::InvalidProof)
+        );
+        assert_eq!(
+            distributor.claim(1, recipient(2), 200, tree.proof(2).expect("Claim exists")),
+            Err(DistributorError::InvalidProof)
+        );
+        assert!(!distributor.is_claimed(1));
+
+        set_block_timestamp::<DefaultEnvironment>(EXPIRY);
+        assert_eq!(distributor.claim(1, recipient(2), 200, proof), Err(DistributorError::Expired));
+    }
+
+    #[ink::test]
+    fn claim_fails_without_funds() {
+        let tree = MerkleTree::from_csv(&csv(2)).expect("Valid distribution");
+        let mut distributor = setup(&tree);
+        mock::deploy(token(), 0, distributor_account());
+        assert_eq!(
+            distributor.claim(0, recipient(1), 100, tree.proof(0).expect("Claim exists")),
+            Err(DistributorError::PSP22(PSP22Error::InsufficientBalance))
+        );
+    }
+
+    #[ink::test]
+    fn owner_sweeps_after_expiry() {
+        let tree = MerkleTree::from_csv(&csv(3)).expect("Valid distribution");
+        let mut distributor = setup(&tree);
+        distributor
+            .claim(0, recipient(1), 100, tree.proof(0).expect("Claim exists"))
+            .expect("Claimed");
+
+        assert_eq!(distributor.sweep(), Err(DistributorError::NotExpired));
+        set_block_timestamp::<DefaultEnvironment>(EXPIRY);
+        set_caller::<DefaultEnvironment>(recipient(1));
+        assert_eq!(distributor.sweep(), Err(DistributorError::NotOwner));
+
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(distributor.sweep(), Ok(()));
+        assert_eq!(mock::balance_of(token(), owner()), 200 + 300 + 1000);
+        assert_eq!(mock::balance_of(token(), distributor_account()), 0);
+    }
+}
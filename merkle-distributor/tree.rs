@@ -0,0 +1,143 @@
+//! Builder of the Merkle tree of a distribution, producing the root and the proofs of all claims.
+//!
+//! Available with the `std` feature, for the tooling preparing a distribution and for tests.
+//! The tree is built from the same `leaf_hash` and `hash_pair` the contract verifies with. A node
+//! without a sibling is carried up to the next level unchanged, so it adds nothing to the proof.
+
+use ink::primitives::AccountId;
+
+use crate::data::{hash_pair, leaf_hash, Node};
+
+/// A claim of `amount` tokens by `account`, identified by its `index` in the distribution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub index: u32,
+    pub account: AccountId,
+    pub amount: u128,
+}
+
+/// Errors that may occur while building a tree. Lines are numbered from one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    /// The distribution has no claims.
+    Empty,
+    /// The distribution has more claims than fit into a `u32` index.
+    TooManyClaims,
+    /// The line does not have exactly two fields.
+    InvalidLine { line: usize },
+    /// The account is not 32 bytes written in hex.
+    InvalidAccount { line: usize },
+    /// The amount is not a decimal `u128`.
+    InvalidAmount { line: usize },
+}
+
+/// Merkle tree of a distribution.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    claims: Vec<Claim>,
+    /// Nodes of every level, from the leaves up to the root.
+    levels: Vec<Vec<Node>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree of a distribution paying `amount` to `account` for every pair, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Empty` if there are no pairs and `TooManyClaims` if their index would overflow.
+    pub fn new(distribution: impl IntoIterator<Item = (AccountId, u128)>) -> Result<MerkleTree, TreeError> {
+        let claims = distribution
+            .into_iter()
+            .enumerate()
+            .map(|(index, (account, amount))| {
+                let index = u32::try_from(index).map_err(|_| TreeError::TooManyClaims)?;
+                Ok(Claim { index, account, amount })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if claims.is_empty() {
+            return Err(TreeError::Empty);
+        }
+
+        let mut levels = vec![claims
+            .iter()
+            .map(|claim| leaf_hash(claim.index, claim.account, claim.amount))
+            .collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+        Ok(MerkleTree { claims, levels })
+    }
+
+    /// Builds the tree of a distribution given as lines of `account,amount`.
+    ///
+    /// The account is written in hex, with an optional `0x` prefix, the amount in decimal.
+    /// Blank lines and lines starting with `#` are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first malformed line, or the errors of `new`.
+    pub fn from_csv(input: &str) -> Result<MerkleTree, TreeError> {
+        let mut distribution = Vec::new();
+        for (number, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let number = number + 1;
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [account, amount] = fields[..] else {
+                return Err(TreeError::InvalidLine { line: number });
+            };
+            let account = parse_account(account).ok_or(TreeError::InvalidAccount { line: number })?;
+            let amount = amount
+                .parse()
+                .map_err(|_| TreeError::InvalidAmount { line: number })?;
+            distribution.push((account, amount));
+        }
+        MerkleTree::new(distribution)
+    }
+
+    pub fn root(&self) -> Node {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn claims(&self) -> &[Claim] {
+        &self.claims
+    }
+
+    /// Returns the proof of the claim at `index`, from the bottom up.
+    pub fn proof(&self, index: u32) -> Option<Vec<Node>> {
+        let mut position = usize::try_from(index).ok()?;
+        if position >= self.claims.len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+}
+
+fn parse_account(input: &str) -> Option<AccountId> {
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+    if hex.len() != 64 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * index..2 * index + 2], 16).ok()?;
+    }
+    Some(AccountId::from(bytes))
+}
@@ -0,0 +1,228 @@
+//! Module keeping the staked tokens and their rewards.
+
+use ink::{prelude::vec::Vec, primitives::AccountId, storage::Mapping};
+use primitive_types::U256;
+use psp34_full::balance_manager::Balances;
+use psp34_full::{Id, PSP34Error};
+
+use crate::NftStakingError;
+
+/// Multiplier of a token earning exactly the reward rate, in percent.
+pub const BASE_MULTIPLIER: u32 = 100;
+
+/// Largest reward multiplier, in percent. Higher values read from the metadata are capped to it.
+pub const MAX_MULTIPLIER: u32 = 10 * BASE_MULTIPLIER;
+
+/// Largest number of tokens staked by one account, bounding the loops over its tokens
+/// in `earned` and `take_reward`.
+pub const MAX_STAKED_PER_ACCOUNT: u32 = 50;
+
+const MILLISECONDS_PER_SECOND: u128 = 1000;
+
+/// Returns `a * b / c` rounded down, without intermediate overflow.
+/// Results above `u128::MAX` are saturated.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    u128::try_from(result).unwrap_or(u128::MAX)
+}
+
+/// Reads a reward multiplier from the value of a metadata attribute.
+///
+/// The value is a decimal number of percent written in ASCII, e.g. `b"150"` for one and a half
+/// times the reward rate. A missing or malformed value gives `BASE_MULTIPLIER`, a value above
+/// `MAX_MULTIPLIER` gives `MAX_MULTIPLIER`.
+pub fn parse_multiplier(value: Option<Vec<u8>>) -> u32 {
+    value
+        .and_then(|value| core::str::from_utf8(&value).ok()?.trim().parse::<u32>().ok())
+        .map_or(BASE_MULTIPLIER, |multiplier| multiplier.min(MAX_MULTIPLIER))
+}
+
+/// A staked token.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct StakeInfo {
+    /// Account which staked the token and earns its rewards.
+    pub staker: AccountId,
+    /// Timestamp at which the token was staked.
+    pub staked_at: u64,
+    /// Reward multiplier of the token, in percent.
+    pub multiplier: u32,
+    /// Value of the accumulator when the rewards of the token were last settled.
+    accumulated_paid: u128,
+}
+
+/// Staked tokens and their rewards.
+///
+/// Every staked token earns `reward_rate` tokens per second, scaled by its multiplier.
+/// `accumulated` sums the reward rate over time (in tokens times milliseconds) since the
+/// contract was created, so a token earns its multiplier times the growth of the accumulator
+/// since its rewards were last settled, and the reward rate can change at any time.
+///
+/// The tokens staked by each account are enumerated with the `Balances` index of `psp34-full`,
+/// with the staker in place of the owner. Methods take the current block timestamp `now`,
+/// the token transfers are done by the contract.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct NftStakingData {
+    reward_rate: u128,
+    accumulated: u128,
+    last_update_time: u64,
+    stakes: Mapping<Id, StakeInfo>,
+    staked: Balances,
+    rewards: Mapping<AccountId, u128>,
+}
+
+impl NftStakingData {
+    /// Creates a contract without stakes, paying `reward_rate` tokens per second per staked token.
+    pub fn new(reward_rate: u128, now: u64) -> NftStakingData {
+        NftStakingData {
+            reward_rate,
+            last_update_time: now,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the reward paid per second to a token with `BASE_MULTIPLIER`.
+    pub fn reward_rate(&self) -> u128 {
+        self.reward_rate
+    }
+
+    /// Returns the number of staked tokens.
+    pub fn total_staked(&self) -> u128 {
+        self.staked.total_supply()
+    }
+
+    pub fn stake_info(&self, id: &Id) -> Option<StakeInfo> {
+        self.stakes.get(id)
+    }
+
+    /// Returns the number of tokens staked by `staker`.
+    pub fn staked_count(&self, staker: AccountId) -> u32 {
+        self.staked.balance_of(&staker)
+    }
+
+    /// Returns the token staked by `staker` at `index`.
+    pub fn staked_token_by_index(&self, staker: AccountId, index: u128) -> Result<Id, PSP34Error> {
+        self.staked.owners_token_by_index(staker, index)
+    }
+
+    /// Returns the staked token at `index`.
+    pub fn token_by_index(&self, index: u128) -> Result<Id, PSP34Error> {
+        self.staked.token_by_index(index)
+    }
+
+    fn accumulated(&self, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_update_time);
+        self.accumulated
+            .saturating_add(u128::from(elapsed).saturating_mul(self.reward_rate))
+    }
+
+    fn reward(&self, stake: &StakeInfo, accumulated: u128) -> u128 {
+        mul_div(
+            accumulated.saturating_sub(stake.accumulated_paid),
+            u128::from(stake.multiplier),
+            MILLISECONDS_PER_SECOND * u128::from(BASE_MULTIPLIER),
+        )
+    }
+
+    /// Returns the reward earned by the staked token `id` until `now` and not yet settled.
+    pub fn earned_by_token(&self, id: &Id, now: u64) -> u128 {
+        self.stakes
+            .get(id)
+            .map_or(0, |stake| self.reward(&stake, self.accumulated(now)))
+    }
+
+    /// Returns the reward earned by `staker` until `now` and not yet paid to it.
+    pub fn earned(&self, staker: AccountId, now: u64) -> u128 {
+        let accumulated = self.accumulated(now);
+        (0..u128::from(self.staked_count(staker)))
+            .filter_map(|index| self.staked_token_by_index(staker, index).ok())
+            .filter_map(|id| self.stakes.get(&id))
+            .fold(self.rewards.get(staker).unwrap_or_default(), |earned, stake| {
+                earned.saturating_add(self.reward(&stake, accumulated))
+            })
+    }
+
+    /// Brings the accumulator up to `now`. Must be called before the reward rate changes.
+    fn update(&mut self, now: u64) {
+        self.accumulated = self.accumulated(now);
+        self.last_update_time = now;
+    }
+
+    /// Moves the reward earned by the staked token `id` to the rewards of its staker.
+    fn settle(&mut self, id: &Id, stake: &mut StakeInfo) {
+        let reward = self.reward(stake, self.accumulated);
+        if reward > 0 {
+            let rewards = self.rewards.get(stake.staker).unwrap_or_default();
+            self.rewards.insert(stake.staker, &rewards.saturating_add(reward));
+        }
+        stake.accumulated_paid = self.accumulated;
+        self.stakes.insert(id, stake);
+    }
+
+    /// Records the token `id` as staked by `staker` with the reward `multiplier`, in percent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AlreadyStaked` if the token is already staked and `TooManyStaked` if `staker`
+    /// already stakes `MAX_STAKED_PER_ACCOUNT` tokens.
+    pub fn stake(&mut self, staker: AccountId, id: Id, multiplier: u32, now: u64) -> Result<(), NftStakingError> {
+        if self.stakes.contains(&id) {
+            return Err(NftStakingError::AlreadyStaked);
+        }
+        if self.staked_count(staker) >= MAX_STAKED_PER_ACCOUNT {
+            return Err(NftStakingError::TooManyStaked);
+        }
+        self.update(now);
+        let stake = StakeInfo {
+            staker,
+            staked_at: now,
+            multiplier,
+            accumulated_paid: self.accumulated,
+        };
+        self.stakes.insert(&id, &stake);
+        self.staked.increase_balance(&staker, &id, true)?;
+        Ok(())
+    }
+
+    /// Removes the stake of the token `id`, keeping its reward for `caller`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotStaked` if the token is not staked and `NotStaker` if it was staked by another account.
+    pub fn unstake(&mut self, caller: AccountId, id: &Id, now: u64) -> Result<(), NftStakingError> {
+        let mut stake = self.stakes.get(id).ok_or(NftStakingError::NotStaked)?;
+        if stake.staker != caller {
+            return Err(NftStakingError::NotStaker);
+        }
+        self.update(now);
+        self.settle(id, &mut stake);
+        self.stakes.remove(id);
+        self.staked.decrease_balance(&caller, id, true);
+        Ok(())
+    }
+
+    /// Returns the reward earned by `staker` until `now` and marks it as paid.
+    pub fn take_reward(&mut self, staker: AccountId, now: u64) -> u128 {
+        self.update(now);
+        for index in 0..u128::from(self.staked_count(staker)) {
+            if let Ok(id) = self.staked_token_by_index(staker, index) {
+                if let Some(mut stake) = self.stakes.get(&id) {
+                    self.settle(&id, &mut stake);
+                }
+            }
+        }
+        let reward = self.rewards.get(staker).unwrap_or_default();
+        self.rewards.remove(staker);
+        reward
+    }
+
+    /// Sets the reward paid per second to a token with `BASE_MULTIPLIER`, from `now` on.
+    pub fn set_reward_rate(&mut self, reward_rate: u128, now: u64) {
+        self.update(now);
+        self.reward_rate = reward_rate;
+    }
+}
@@ -0,0 +1,38 @@
+//! Error types for NFT staking.
+
+use psp22_full::PSP22Error;
+use psp34_full::PSP34Error;
+
+/// Errors that may occur during NFT staking operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum NftStakingError {
+    /// The caller is not the owner of the staking contract.
+    NotOwner,
+    /// The caller does not hold the token it tries to stake.
+    NotTokenOwner,
+    /// The token is already staked.
+    AlreadyStaked,
+    /// The token is not staked.
+    NotStaked,
+    /// The token was staked by another account.
+    NotStaker,
+    /// The account already stakes `MAX_STAKED_PER_ACCOUNT` tokens.
+    TooManyStaked,
+    /// The reward token returned an error.
+    PSP22(PSP22Error),
+    /// The staked collection returned an error.
+    PSP34(PSP34Error),
+}
+
+impl From<PSP22Error> for NftStakingError {
+    fn from(error: PSP22Error) -> Self {
+        NftStakingError::PSP22(error)
+    }
+}
+
+impl From<PSP34Error> for NftStakingError {
+    fn from(error: PSP34Error) -> Self {
+        NftStakingError::PSP34(error)
+    }
+}
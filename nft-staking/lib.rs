@@ -0,0 +1,317 @@
+//! Staking of PSP34 tokens accruing PSP22 rewards.
+//!
+//! Holders lock their tokens of one PSP34 collection in the contract and earn a PSP22 reward
+//! per token per second, scaled by a multiplier read from the metadata of the token.
+//! Unstaking returns the token to its staker.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+
+pub use data::{
+    parse_multiplier, NftStakingData, StakeInfo, BASE_MULTIPLIER, MAX_MULTIPLIER, MAX_STAKED_PER_ACCOUNT,
+};
+pub use errors::NftStakingError;
+
+/// NFT staking implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod nft_staking {
+    use ink::prelude::vec::Vec;
+    use psp22_full::PSP22Ref;
+    use psp34_full::PSP34Ref;
+    use psp34_full::{Id, PSP34Error};
+
+    use crate::{parse_multiplier, NftStakingData, NftStakingError, StakeInfo, BASE_MULTIPLIER};
+
+    #[ink(storage)]
+    pub struct NftStaking {
+        /// Account which sets the reward rate.
+        owner: AccountId,
+        collection: AccountId,
+        rewards_token: AccountId,
+        /// Metadata attribute holding the reward multiplier of each token, if any.
+        multiplier_key: Option<Vec<u8>>,
+        data: NftStakingData,
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        staker: AccountId,
+        id: Id,
+        multiplier: u32,
+    }
+
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        staker: AccountId,
+        id: Id,
+    }
+
+    #[ink(event)]
+    pub struct RewardPaid {
+        #[ink(topic)]
+        staker: AccountId,
+        reward: u128,
+    }
+
+    #[ink(event)]
+    pub struct Recovered {
+        #[ink(topic)]
+        to: AccountId,
+        id: Id,
+    }
+
+    #[ink(event)]
+    pub struct RewardRateUpdated {
+        reward_rate: u128,
+    }
+
+    impl NftStaking {
+        /// Creates a staking contract owned by the caller.
+        ///
+        /// # Arguments
+        ///
+        /// * `collection` - The PSP34 collection whose tokens are staked.
+        /// * `rewards_token` - The PSP22 token paid as rewards. The contract must hold enough of it.
+        /// * `reward_rate` - The reward paid per second for a staked token with the base multiplier.
+        /// * `multiplier_key` - The metadata attribute holding the multiplier of each token, in percent.
+        ///   `None` if every token earns the reward rate, the collection must implement `PSP34Metadata` otherwise.
+        #[ink(constructor)]
+        pub fn new(
+            collection: AccountId,
+            rewards_token: AccountId,
+            reward_rate: u128,
+            multiplier_key: Option<Vec<u8>>,
+        ) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                collection,
+                rewards_token,
+                multiplier_key,
+                data: NftStakingData::new(reward_rate, Self::env().block_timestamp()),
+            }
+        }
+
+        // Contracts this one depends on implement `EmitEvent` for the same environment
+        // when built with their `contract` feature, so the contract is named explicitly
+        fn emit_event(&self, event: impl Into<<NftStaking as ink::reflect::ContractEventBase>::Type>) {
+            ink::codegen::EmitEvent::<NftStaking>::emit_event(self.env(), event)
+        }
+
+        /// Returns the account which sets the reward rate.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn collection(&self) -> AccountId {
+            self.collection
+        }
+
+        #[ink(message)]
+        pub fn rewards_token(&self) -> AccountId {
+            self.rewards_token
+        }
+
+        #[ink(message)]
+        pub fn multiplier_key(&self) -> Option<Vec<u8>> {
+            self.multiplier_key.clone()
+        }
+
+        /// Returns the reward paid per second for a staked token with the base multiplier.
+        #[ink(message)]
+        pub fn reward_rate(&self) -> u128 {
+            self.data.reward_rate()
+        }
+
+        /// Returns the number of staked tokens.
+        #[ink(message)]
+        pub fn total_staked(&self) -> u128 {
+            self.data.total_staked()
+        }
+
+        /// Returns the staker, stake time and multiplier of the token `id`, if staked.
+        #[ink(message)]
+        pub fn stake_info(&self, id: Id) -> Option<StakeInfo> {
+            self.data.stake_info(&id)
+        }
+
+        /// Returns the number of tokens staked by `staker`.
+        #[ink(message)]
+        pub fn staked_count(&self, staker: AccountId) -> u32 {
+            self.data.staked_count(staker)
+        }
+
+        /// Returns the token staked by `staker` at `index`, enumerating its staked tokens.
+        #[ink(message)]
+        pub fn staked_token_by_index(&self, staker: AccountId, index: u128) -> Result<Id, PSP34Error> {
+            self.data.staked_token_by_index(staker, index)
+        }
+
+        /// Returns the staked token at `index`, enumerating all staked tokens.
+        #[ink(message)]
+        pub fn token_by_index(&self, index: u128) -> Result<Id, PSP34Error> {
+            self.data.token_by_index(index)
+        }
+
+        /// Returns the reward earned by `staker` and not yet paid to it.
+        #[ink(message)]
+        pub fn earned(&self, staker: AccountId) -> u128 {
+            self.data.earned(staker, self.env().block_timestamp())
+        }
+
+        /// Stakes the token `id` of the caller.
+        ///
+        /// The caller must have approved the contract to transfer the token. Its multiplier
+        /// is read from the metadata when it is staked.
+        ///
+        /// # Events
+        ///
+        /// A `Staked` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotTokenOwner` if the caller does not hold the token
+        /// and `TooManyStaked` if it already stakes `MAX_STAKED_PER_ACCOUNT` tokens.
+        #[ink(message)]
+        pub fn stake(&mut self, id: Id) -> Result<(), NftStakingError> {
+            let staker = self.env().caller();
+            let mut collection = Self::psp34(self.collection);
+            // The contract may be approved for all tokens of several accounts,
+            // so the transfer alone does not prove that the caller holds the token
+            if collection.owner_of(id.clone())? != Some(staker) {
+                return Err(NftStakingError::NotTokenOwner);
+            }
+            let multiplier = match &self.multiplier_key {
+                Some(key) => parse_multiplier(collection.get_attribute(id.clone(), key.clone())?),
+                None => BASE_MULTIPLIER,
+            };
+            collection.transfer(self.env().account_id(), id.clone(), Vec::new())?;
+            self.data
+                .stake(staker, id.clone(), multiplier, self.env().block_timestamp())?;
+            self.emit_event(Staked {
+                staker,
+                id,
+                multiplier,
+            });
+            Ok(())
+        }
+
+        /// Stakes the tokens `ids` of the caller in a single transaction.
+        ///
+        /// Once the caller has approved the contract to transfer all its tokens of the collection,
+        /// with `PSP34::approve` without an `id`, staking any of them needs no further approval.
+        ///
+        /// # Events
+        ///
+        /// A `Staked` event is emitted for every token.
+        ///
+        /// # Errors
+        ///
+        /// Fails like `stake` if any of the tokens cannot be staked, in which case none is staked.
+        #[ink(message)]
+        pub fn stake_batch(&mut self, ids: Vec<Id>) -> Result<(), NftStakingError> {
+            for id in ids {
+                self.stake(id)?;
+            }
+            Ok(())
+        }
+
+        /// Returns the staked token `id` to the caller. Its reward is kept until claimed.
+        ///
+        /// # Events
+        ///
+        /// An `Unstaked` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotStaked` if the token is not staked and `NotStaker` if it was staked by another account.
+        #[ink(message)]
+        pub fn unstake(&mut self, id: Id) -> Result<(), NftStakingError> {
+            let staker = self.env().caller();
+            self.data.unstake(staker, &id, self.env().block_timestamp())?;
+            Self::psp34(self.collection).transfer(staker, id.clone(), Vec::new())?;
+            self.emit_event(Unstaked { staker, id });
+            Ok(())
+        }
+
+        /// Transfers the reward earned by the caller to it. No-op if nothing was earned.
+        ///
+        /// # Events
+        ///
+        /// A `RewardPaid` event is emitted if any reward was paid.
+        #[ink(message)]
+        pub fn claim(&mut self) -> Result<(), NftStakingError> {
+            let staker = self.env().caller();
+            let reward = self.data.take_reward(staker, self.env().block_timestamp());
+            if reward == 0 {
+                return Ok(());
+            }
+            Self::psp22(self.rewards_token).transfer(staker, reward, Vec::new())?;
+            self.emit_event(RewardPaid { staker, reward });
+            Ok(())
+        }
+
+        /// Transfers the token `id`, held by the contract but not staked, to `to`.
+        ///
+        /// Tokens transferred to the contract directly instead of through `stake` are not staked
+        /// and cannot be attributed to their sender, so only the owner can return them.
+        ///
+        /// # Events
+        ///
+        /// A `Recovered` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner
+        /// and `AlreadyStaked` if the token is staked.
+        #[ink(message)]
+        pub fn recover(&mut self, id: Id, to: AccountId) -> Result<(), NftStakingError> {
+            self.ensure_owner()?;
+            if self.data.stake_info(&id).is_some() {
+                return Err(NftStakingError::AlreadyStaked);
+            }
+            Self::psp34(self.collection).transfer(to, id.clone(), Vec::new())?;
+            self.emit_event(Recovered { to, id });
+            Ok(())
+        }
+
+        /// Sets the reward paid per second for a staked token with the base multiplier.
+        /// Rewards earned so far are kept.
+        ///
+        /// # Events
+        ///
+        /// A `RewardRateUpdated` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner.
+        #[ink(message)]
+        pub fn set_reward_rate(&mut self, reward_rate: u128) -> Result<(), NftStakingError> {
+            self.ensure_owner()?;
+            self.data
+                .set_reward_rate(reward_rate, self.env().block_timestamp());
+            self.emit_event(RewardRateUpdated { reward_rate });
+            Ok(())
+        }
+
+        fn ensure_owner(&self) -> Result<(), NftStakingError> {
+            if self.env().caller() != self.owner {
+                return Err(NftStakingError::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn psp22(token: AccountId) -> PSP22Ref {
+            PSP22Ref::new(token)
+        }
+
+        fn psp34(collection: AccountId) -> PSP34Ref {
+            PSP34Ref::new(collection)
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use nft_staking::nft_staking::*;
+use nft_staking::{
+    parse_multiplier, NftStakingData, NftStakingError, BASE_MULTIPLIER, MAX_MULTIPLIER, MAX_STAKED_PER_ACCOUNT,
+};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{set_block_timestamp, set_callee, set_caller}};
+    use psp22_full::PSP22Error;
+    use psp34_full::{Id, PSP34Error};
+    use super::*;
+
+    const SECOND: u64 = 1000;
+    const MULTIPLIER: &[u8] = b"multiplier";
+
+    fn owner() -> AccountId {
+        AccountId::from([0x1; 32])
+    }
+
+    fn alice() -> AccountId {
+        AccountId::from([0x2; 32])
+    }
+
+    fn bob() -> AccountId {
+        AccountId::from([0x3; 32])
+    }
+
+    fn staking_account() -> AccountId {
+        AccountId::from([0x10; 32])
+    }
+
+    fn collection() -> AccountId {
+        AccountId::from([0x20; 32])
+    }
+
+    fn reward_token() -> AccountId {
+        AccountId::from([0x30; 32])
+    }
+
+    /// Deploys a collection with tokens 1 and 2 held by Alice and token 3 held by Bob, where token 2
+    /// has a multiplier of 150%, and a staking contract paying 10 tokens per second, funded with 1_000_000.
+    fn setup() -> NftStaking {
+        psp34_full::mock::deploy(collection());
+        psp34_full::mock::mint(collection(), alice(), Id::U8(1));
+        psp34_full::mock::mint(collection(), alice(), Id::U8(2));
+        psp34_full::mock::mint(collection(), bob(), Id::U8(3));
+        psp34_full::mock::set_attribute(collection(), Id::U8(2), MULTIPLIER, b"150");
+        psp22_full::mock::deploy(reward_token(), 1_000_000, staking_account());
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(staking_account());
+        set_caller::<DefaultEnvironment>(owner());
+        NftStaking::new(collection(), reward_token(), 10, Some(MULTIPLIER.to_vec()))
+    }
+
+    fn stake(staking: &mut NftStaking, account: AccountId, id: Id) -> Result<(), NftStakingError> {
+        set_caller::<DefaultEnvironment>(account);
+        psp34_full::mock::approve(collection(), account, staking_account(), id.clone());
+        staking.stake(id)
+    }
+
+    #[test]
+    fn multiplier_is_parsed_from_attribute() {
+        assert_eq!(parse_multiplier(Some(b"150".to_vec())), 150);
+        assert_eq!(parse_multiplier(Some(b" 0 ".to_vec())), 0);
+        assert_eq!(parse_multiplier(Some(b"1.5".to_vec())), BASE_MULTIPLIER);
+        assert_eq!(parse_multiplier(Some(vec![0xff])), BASE_MULTIPLIER);
+        assert_eq!(parse_multiplier(None), BASE_MULTIPLIER);
+        assert_eq!(parse_multiplier(Some(b"1000000".to_vec())), MAX_MULTIPLIER);
+    }
+
+    #[ink::test]
+    fn tokens_earn_rate_scaled_by_multiplier() {
+        let mut data = NftStakingData::new(10, 0);
+        data.stake(alice(), Id::U8(1), 100, 0).expect("Staked");
+        // Rewards accrue per millisecond
+        assert_eq!(data.earned_by_token(&Id::U8(1), 500), 5);
+        data.stake(alice(), Id::U8(2), 150, 10 * SECOND).expect("Staked");
+
+        assert_eq!(data.earned_by_token(&Id::U8(1), 20 * SECOND), 200);
+        assert_eq!(data.earned_by_token(&Id::U8(2), 20 * SECOND), 150);
+        assert_eq!(data.earned(alice(), 20 * SECOND), 350);
+
+        assert_eq!(data.take_reward(alice(), 20 * SECOND), 350);
+        assert_eq!(data.earned(alice(), 20 * SECOND), 0);
+        assert_eq!(data.earned(alice(), 30 * SECOND), 250);
+    }
+
+    #[ink::test]
+    fn reward_rate_change_keeps_earned_rewards() {
+        let mut data = NftStakingData::new(10, 0);
+        data.stake(alice(), Id::U8(1), 100, 0).expect("Staked");
+        data.set_reward_rate(20, 10 * SECOND);
+        assert_eq!(data.reward_rate(), 20);
+        assert_eq!(data.earned(alice(), 20 * SECOND), 100 + 200);
+
+        data.set_reward_rate(0, 20 * SECOND);
+        assert_eq!(data.earned(alice(), 100 * SECOND), 300);
+    }
+
+    #[ink::test]
+    fn unstaked_token_keeps_reward_until_taken() {
+        let mut data = NftStakingData::new(10, 0);
+        data.stake(alice(), Id::U8(1), 100, 0).expect("Staked");
+        assert_eq!(data.unstake(bob(), &Id::U8(1), SECOND), Err(NftStakingError::NotStaker));
+        assert_eq!(data.unstake(alice(), &Id::U8(2), SECOND), Err(NftStakingError::NotStaked));
+
+        assert_eq!(data.unstake(alice(), &Id::U8(1), 10 * SECOND), Ok(()));
+        assert_eq!(data.stake_info(&Id::U8(1)), None);
+        assert_eq!(data.earned_by_token(&Id::U8(1), 20 * SECOND), 0);
+        assert_eq!(data.earned(alice(), 20 * SECOND), 100);
+        assert_eq!(data.take_reward(alice(), 20 * SECOND), 100);
+        assert_eq!(data.take_reward(alice(), 20 * SECOND), 0);
+    }
+
+    #[ink::test]
+    fn staked_tokens_are_enumerated() {
+        let mut data = NftStakingData::new(10, 0);
+        data.stake(alice(), Id::U8(1), 100, 0).expect("Staked");
+        data.stake(alice(), Id::U8(2), 100, 0).expect("Staked");
+        data.stake(bob(), Id::U8(3), 100, 0).expect("Staked");
+        assert_eq!(data.stake(bob(), Id::U8(3), 100, 0), Err(NftStakingError::AlreadyStaked));
+
+        assert_eq!(data.total_staked(), 3);
+        assert_eq!(data.staked_count(alice()), 2);
+        assert_eq!(data.staked_token_by_index(alice(), 0), Ok(Id::U8(1)));
+        assert_eq!(data.staked_token_by_index(alice(), 1), Ok(Id::U8(2)));
+        assert_eq!(data.staked_token_by_index(alice(), 2), Err(PSP34Error::TokenNotExists));
+        assert_eq!(data.token_by_index(2), Ok(Id::U8(3)));
+
+        data.unstake(alice(), &Id::U8(1), 0).expect("Unstaked");
+        assert_eq!(data.total_staked(), 2);
+        assert_eq!(data.staked_count(alice()), 1);
+        assert_eq!(data.staked_token_by_index(alice(), 0), Ok(Id::U8(2)));
+    }
+
+    #[ink::test]
+    fn staked_tokens_per_account_are_limited() {
+        let mut data = NftStakingData::new(10, 0);
+        for id in 0..MAX_STAKED_PER_ACCOUNT {
+            data.stake(alice(), Id::U32(id), 100, 0).expect("Staked");
+        }
+        assert_eq!(
+            data.stake(alice(), Id::U32(MAX_STAKED_PER_ACCOUNT), 100, 0),
+            Err(NftStakingError::TooManyStaked)
+        );
+        data.stake(bob(), Id::U32(MAX_STAKED_PER_ACCOUNT), 100, 0).expect("Staked");
+
+        data.unstake(alice(), &Id::U32(0), 0).expect("Unstaked");
+        data.stake(alice(), Id::U32(0), 100, 0).expect("Staked");
+    }
+
+    #[ink::test]
+    fn stake_batch_needs_one_approval_for_all_tokens() {
+        let mut staking = setup();
+        psp34_full::mock::approve_all(collection(), alice(), staking_account());
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(staking.stake_batch(vec![Id::U8(1), Id::U8(2)]), Ok(()));
+        assert_eq!(staking.staked_count(alice()), 2);
+        assert_eq!(psp34_full::mock::owner_of(collection(), Id::U8(2)), Some(staking_account()));
+        assert_eq!(staking.stake_info(Id::U8(2)).map(|info| info.multiplier), Some(150));
+
+        // Bob's approval does not let Alice stake his token
+        psp34_full::mock::approve_all(collection(), bob(), staking_account());
+        assert_eq!(staking.stake_batch(vec![Id::U8(3)]), Err(NftStakingError::NotTokenOwner));
+    }
+
+    #[ink::test]
+    fn stake_claim_and_unstake_work() {
+        let mut staking = setup();
+        assert_eq!(staking.owner(), owner());
+        assert_eq!(staking.reward_rate(), 10);
+        assert_eq!(staking.multiplier_key(), Some(MULTIPLIER.to_vec()));
+
+        assert_eq!(stake(&mut staking, alice(), Id::U8(1)), Ok(()));
+        assert_eq!(stake(&mut staking, alice(), Id::U8(2)), Ok(()));
+        assert_eq!(psp34_full::mock::owner_of(collection(), Id::U8(1)), Some(staking_account()));
+        let info = staking.stake_info(Id::U8(2)).expect("Token staked");
+        assert_eq!((info.staker, info.staked_at, info.multiplier), (alice(), 0, 150));
+        assert_eq!(staking.staked_count(alice()), 2);
+        assert_eq!(staking.staked_token_by_index(alice(), 1), Ok(Id::U8(2)));
+        assert_eq!(staking.total_staked(), 2);
+
+        set_block_timestamp::<DefaultEnvironment>(10 * SECOND);
+        assert_eq!(staking.earned(alice()), 100 + 150);
+        assert_eq!(staking.claim(), Ok(()));
+        assert_eq!(psp22_full::mock::balance_of(reward_token(), alice()), 250);
+        assert_eq!(staking.earned(alice()), 0);
+
+        set_block_timestamp::<DefaultEnvironment>(20 * SECOND);
+        assert_eq!(staking.unstake(Id::U8(1)), Ok(()));
+        assert_eq!(psp34_full::mock::owner_of(collection(), Id::U8(1)), Some(alice()));
+        assert_eq!(staking.staked_count(alice()), 1);
+
+        set_block_timestamp::<DefaultEnvironment>(30 * SECOND);
+        assert_eq!(staking.earned(alice()), 100 + 300);
+        assert_eq!(staking.claim(), Ok(()));
+        assert_eq!(psp22_full::mock::balance_of(reward_token(), alice()), 650);
+    }
+
+    #[ink::test]
+    fn only_holder_stakes_and_only_staker_unstakes() {
+        let mut staking = setup();
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(staking.stake(Id::U8(1)), Err(NftStakingError::NotTokenOwner));
+        assert_eq!(staking.stake(Id::U8(9)), Err(NftStakingError::NotTokenOwner));
+        // Staking requires an approval of the token
+        assert_eq!(
+            staking.stake(Id::U8(3)),
+            Err(NftStakingError::PSP34(PSP34Error::NotApproved))
+        );
+
+        stake(&mut staking, alice(), Id::U8(1)).expect("Staked");
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(staking.unstake(Id::U8(1)), Err(NftStakingError::NotStaker));
+        assert_eq!(staking.unstake(Id::U8(3)), Err(NftStakingError::NotStaked));
+        assert_eq!(staking.stake(Id::U8(1)), Err(NftStakingError::NotTokenOwner));
+    }
+
+    #[ink::test]
+    fn collection_without_multipliers_pays_base_rate() {
+        let mut staking = setup();
+        set_caller::<DefaultEnvironment>(owner());
+        let mut staking_without_key = NftStaking::new(collection(), reward_token(), 10, None);
+        stake(&mut staking_without_key, alice(), Id::U8(2)).expect("Staked");
+        assert_eq!(
+            staking_without_key.stake_info(Id::U8(2)).map(|info| info.multiplier),
+            Some(BASE_MULTIPLIER)
+        );
+        assert_eq!(stake(&mut staking, alice(), Id::U8(1)), Ok(()));
+        assert_eq!(staking.stake_info(Id::U8(1)).map(|info| info.multiplier), Some(BASE_MULTIPLIER));
+    }
+
+    #[ink::test]
+    fn claim_fails_without_funds() {
+        let mut staking = setup();
+        psp22_full::mock::deploy(reward_token(), 0, staking_account());
+        stake(&mut staking, alice(), Id::U8(1)).expect("Staked");
+        set_block_timestamp::<DefaultEnvironment>(10 * SECOND);
+        assert_eq!(
+            staking.claim(),
+            Err(NftStakingError::PSP22(PSP22Error::InsufficientBalance))
+        );
+        // The token can be unstaked regardless
+        assert_eq!(staking.unstake(Id::U8(1)), Ok(()));
+    }
+
+    #[ink::test]
+    fn owner_recovers_tokens_transferred_without_staking() {
+        let mut staking = setup();
+        stake(&mut staking, alice(), Id::U8(1)).expect("Staked");
+        // Bob sends his token directly instead of staking it
+        psp34_full::mock::transfer(collection(), bob(), staking_account(), Id::U8(3));
+        assert_eq!(staking.stake_info(Id::U8(3)), None);
+
+        set_caller::<DefaultEnvironment>(bob());
+        assert_eq!(staking.stake(Id::U8(3)), Err(NftStakingError::NotTokenOwner));
+        assert_eq!(staking.recover(Id::U8(3), bob()), Err(NftStakingError::NotOwner));
+
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(staking.recover(Id::U8(1), owner()), Err(NftStakingError::AlreadyStaked));
+        assert_eq!(staking.recover(Id::U8(3), bob()), Ok(()));
+        assert_eq!(psp34_full::mock::owner_of(collection(), Id::U8(3)), Some(bob()));
+        assert_eq!(psp34_full::mock::owner_of(collection(), Id::U8(1)), Some(staking_account()));
+        // Tokens the contract does not hold cannot be recovered
+        assert_eq!(
+            staking.recover(Id::U8(2), owner()),
+            Err(NftStakingError::PSP34(PSP34Error::NotApproved))
+        );
+    }
+
+    #[ink::test]
+    fn only_owner_sets_reward_rate() {
+        let mut staking = setup();
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(staking.set_reward_rate(20), Err(NftStakingError::NotOwner));
+
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(staking.set_reward_rate(20), Ok(()));
+        assert_eq!(staking.reward_rate(), 20);
+    }
+}
@@ -1,7 +1,7 @@
 #!/bin/bash
 
-# Run cargo init
-cargo run init
+# Fail if the generated test crates are out of date
+cargo run check || exit 1
 
 # Define an array of contract paths
 contract_paths=(
@@ -11,6 +11,27 @@ contract_paths=(
     "PSP22/extensions/tests/capped"
     "PSP22/extensions/tests/pausable"
     "PSP22/extensions/tests/wrapper"
+    "PSP22/extensions/tests/fees"
+    "PSP22/extensions/tests/allowance_expiry"
+    "PSP22/extensions/tests/enumerable"
+    "PSP22/extensions/tests/batch"
+    "PSP22/extensions/tests/native_wrapper"
+    "PSP22/extensions/tests/reentrancy_guard"
+    "PSP22/extensions/tests/upgradeable"
+    "PSP22/extensions/tests/metadata"
+    "PSP34"
+    "PSP34/extensions/tests/burnable"
+    "PSP34/extensions/tests/enumerable"
+    "PSP34/extensions/tests/metadata"
+    "PSP34/extensions/tests/mintable"
+    "smart-beaver-cli"
+    "smart-beaver-indexer"
+    "vesting"
+    "staking"
+    "nft-staking"
+    "fractional-vault"
+    "merkle-distributor"
+    "crowdsale"
 )
 
 # Loop through each path and run cargo test
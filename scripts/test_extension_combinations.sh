@@ -0,0 +1,58 @@
+#!/bin/bash
+
+# Composes every valid combination of extensions of the given base contracts
+# (PSP22 and PSP34 by default) and checks that each of them compiles.
+#
+# Usage: scripts/test_extension_combinations.sh [BASE...]
+#
+# MAX_EXTENSIONS limits the number of extensions in a combination,
+# CARGO_FLAGS is passed to every cargo invocation (e.g. "--offline").
+# Checking all combinations takes hours, so this script is meant to be run nightly.
+
+root="$(cd "$(dirname "$0")/.." && pwd)"
+bases=("$@")
+if [ ${#bases[@]} -eq 0 ]; then
+    bases=("PSP22" "PSP34")
+fi
+
+cargo build $CARGO_FLAGS --quiet --manifest-path "$root/Cargo.toml" -p smart-beaver-cli || exit 1
+composer="$root/target/debug/smart-beaver"
+
+work="$(mktemp -d)"
+trap 'rm -rf "$work"' EXIT
+export CARGO_TARGET_DIR="$work/target"
+
+failed=()
+for base in "${bases[@]}"; do
+    crate="$work/$base"
+    mkdir -p "$crate"
+    cp "$root/Cargo.lock" "$crate/Cargo.lock"
+    # Manifest of the base crate without its tests, built from `src` outside of the workspace
+    awk '
+        /^\[\[test\]\]|^\[dev-dependencies\]/ { skip = 1; next }
+        /^\[/ { skip = 0 }
+        !skip
+    ' "$root/$base/Cargo.toml" \
+        | sed -e 's/^name = .*/name = "combination"/' -e 's|^path = "lib.rs"|path = "src/lib.rs"|' \
+        > "$crate/Cargo.toml"
+    printf '\n[workspace]\n' >> "$crate/Cargo.toml"
+
+    while IFS= read -r line; do
+        read -ra extensions <<< "$line"
+        if [ -n "$MAX_EXTENSIONS" ] && [ ${#extensions[@]} -gt "$MAX_EXTENSIONS" ]; then
+            continue
+        fi
+        printf "\n  \033[44m\033[30m  %s [%s]  \033[0m\n\n" "$base" "$line"
+        rm -rf "$crate/src"
+        if ! "$composer" compose "$root/$base" "$crate/src" "${extensions[@]}" \
+            || ! cargo check $CARGO_FLAGS --quiet --manifest-path "$crate/Cargo.toml" --features contract,enumerable; then
+            failed+=("$base [$line]")
+        fi
+    done < <("$composer" combinations "$root/$base")
+done
+
+if [ ${#failed[@]} -ne 0 ]; then
+    printf "\nFailed combinations:\n"
+    printf "  %s\n" "${failed[@]}"
+    exit 1
+fi
@@ -0,0 +1,704 @@
+//! Merging of parsed extensions into a base contract.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    parse_quote, visit_mut::VisitMut, Expr, ExprStruct, FieldValue, Fields, FnArg, ImplItem,
+    ImplItemFn, Item, ItemMod, Member, Pat, Stmt,
+};
+
+use crate::config;
+use crate::errors::ComposerError;
+use crate::extension::{Append, Extension};
+
+/// Directory, relative to the base crate, holding the `.trs` templates.
+pub const EXTENSIONS_DIR: &str = "extensions";
+
+/// Result of composing a contract.
+pub struct Composed {
+    /// Formatted source of the generated `lib.rs`.
+    pub source: String,
+    /// Non-fatal problems found while composing, e.g. unused imports of a template.
+    pub warnings: Vec<String>,
+}
+
+/// Finds the template of the extension called `name` inside `base/extensions`.
+///
+/// Templates may be grouped in subdirectories (like `security/ownable.trs`),
+/// the `tests` directory is skipped.
+pub fn find_extension(base: &Path, name: &str) -> Result<PathBuf, ComposerError> {
+    fn search(dir: &Path, file_name: &str) -> Option<PathBuf> {
+        let mut entries: Vec<_> = fs::read_dir(dir).ok()?.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        let direct = dir.join(file_name);
+        if direct.is_file() {
+            return Some(direct);
+        }
+        entries
+            .into_iter()
+            .filter(|path| path.is_dir() && !path.ends_with("tests"))
+            .find_map(|path| search(&path, file_name))
+    }
+    search(&base.join(EXTENSIONS_DIR), &format!("{}.trs", name))
+        .ok_or_else(|| ComposerError::MissingExtension(name.to_string()))
+}
+
+/// Loads the requested extensions together with all of their dependencies.
+///
+/// Requested extensions keep their order and are followed by the dependencies
+/// which were not requested explicitly, in the order they were discovered.
+pub fn resolve(base: &Path, names: &[String]) -> Result<Vec<Extension>, ComposerError> {
+    let mut resolved: Vec<Extension> = Vec::new();
+    let mut queue: Vec<String> = names.to_vec();
+    let mut index = 0;
+    while index < queue.len() {
+        let name = queue[index].clone();
+        index += 1;
+        if resolved.iter().any(|extension| extension.name == name) {
+            continue;
+        }
+        let extension = Extension::from_file(&find_extension(base, &name)?)?;
+        queue.extend(extension.dependencies.iter().cloned());
+        resolved.push(extension);
+    }
+    Ok(resolved)
+}
+
+/// Loads every extension template of the `base` crate, sorted by name.
+pub fn available_extensions(base: &Path) -> Result<Vec<Extension>, ComposerError> {
+    fn collect(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), ComposerError> {
+        let entries = fs::read_dir(dir).map_err(|err| ComposerError::Io(dir.to_path_buf(), err))?;
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() && !path.ends_with("tests") {
+                collect(&path, paths)?;
+            } else if path.extension().is_some_and(|ext| ext == "trs") {
+                paths.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut paths = Vec::new();
+    collect(&base.join(EXTENSIONS_DIR), &mut paths)?;
+    let mut extensions = paths
+        .iter()
+        .map(|path| Extension::from_file(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    extensions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(extensions)
+}
+
+/// Lists every valid combination of the extensions of the `base` crate, including the empty one.
+///
+/// A combination is valid if it contains the dependencies of all of its extensions
+/// and none of them conflicts with another one. Names within a combination are sorted.
+pub fn combinations(base: &Path) -> Result<Vec<Vec<String>>, ComposerError> {
+    let extensions = available_extensions(base)?;
+    if extensions.len() >= usize::BITS as usize {
+        return Err(ComposerError::Config(format!(
+            "{}: too many extensions to combine",
+            base.display()
+        )));
+    }
+    let mut valid = Vec::new();
+    for mask in 0..(1usize << extensions.len()) {
+        let selected: Vec<&Extension> = extensions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| mask & (1 << index) != 0)
+            .map(|(_, extension)| extension)
+            .collect();
+        let names: BTreeSet<&str> = selected.iter().map(|e| e.name.as_str()).collect();
+        let closed = selected
+            .iter()
+            .all(|e| e.dependencies.iter().all(|d| names.contains(d.as_str())));
+        let conflicting = selected
+            .iter()
+            .any(|e| e.conflicts.iter().any(|c| names.contains(c.as_str())));
+        if closed && !conflicting {
+            valid.push(names.into_iter().map(str::to_string).collect());
+        }
+    }
+    Ok(valid)
+}
+
+/// Merges `extensions` into the contract defined in `base_lib` (the source of `lib.rs`).
+///
+/// Fails if the extensions cannot be combined: one conflicts with another, two of them
+/// declare the same storage field or implement the same trait, an extension uses storage
+/// of an extension it does not depend on, or an append does not match its target message.
+pub fn compose_lib(
+    path: &Path,
+    base_lib: &str,
+    extensions: &[Extension],
+) -> Result<Composed, ComposerError> {
+    let mut file =
+        syn::parse_file(base_lib).map_err(|err| ComposerError::Parse(path.to_path_buf(), err))?;
+    let warnings = merge(path, &mut file, extensions)?;
+    Ok(Composed {
+        source: prettyplease::unparse(&file),
+        warnings,
+    })
+}
+
+/// Composes every valid combination of the extensions of the `base` crate
+/// (see [`combinations`]), without writing the results.
+///
+/// Returns the number of checked combinations. Warnings are treated as errors.
+pub fn check_combinations(base: &Path) -> Result<usize, ComposerError> {
+    let path = base.join("lib.rs");
+    let base_lib = fs::read_to_string(&path).map_err(|err| ComposerError::Io(path.clone(), err))?;
+    let file = syn::parse_file(&base_lib).map_err(|err| ComposerError::Parse(path.clone(), err))?;
+    let available = available_extensions(base)?;
+    let combinations = combinations(base)?;
+    for combination in &combinations {
+        let extensions: Vec<Extension> = available
+            .iter()
+            .filter(|extension| combination.contains(&extension.name))
+            .cloned()
+            .collect();
+        let warnings = merge(&path, &mut file.clone(), &extensions).map_err(|err| {
+            ComposerError::Incompatible(format!("[{}]: {}", combination.join(", "), err))
+        })?;
+        if let Some(warning) = warnings.first() {
+            return Err(ComposerError::Incompatible(format!(
+                "[{}]: {}",
+                combination.join(", "),
+                warning
+            )));
+        }
+    }
+    Ok(combinations.len())
+}
+
+/// Merges `extensions` into the contract module of `file`, returning the warnings.
+fn merge(path: &Path, file: &mut syn::File, extensions: &[Extension]) -> Result<Vec<String>, ComposerError> {
+    let module = file
+        .items
+        .iter_mut()
+        .find_map(|item| match item {
+            Item::Mod(module) if is_ink_contract(module) => Some(module),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            ComposerError::Template(format!("{}: no `#[ink::contract]` module", path.display()))
+        })?;
+    let items = &mut module
+        .content
+        .as_mut()
+        .ok_or_else(|| ComposerError::Template(format!("{}: empty contract module", path.display())))?
+        .1;
+
+    check_compatibility(extensions)?;
+    check_storage_access(items, extensions)?;
+    let warnings: Vec<String> = extensions.iter().flat_map(|e| e.warnings.iter().cloned()).collect();
+    for extension in extensions {
+        add_uses(items, extension);
+        add_storage(items, extension)?;
+        items.extend(extension.items.iter().cloned());
+        items.extend(extension.impls.iter().cloned().map(Item::Impl));
+    }
+    for extension in hook_order(extensions) {
+        for hook in &extension.hooks {
+            apply_hook(items, extension, hook)?;
+        }
+    }
+    for extension in extensions {
+        for append in &extension.appends {
+            apply_append(items, extension, append)?;
+        }
+    }
+    Ok(warnings)
+}
+
+/// Sources of a generated contract crate.
+pub struct RenderedCrate {
+    /// File names (relative to `src`) and contents of the generated sources.
+    pub files: Vec<(PathBuf, String)>,
+    /// Non-fatal problems found while composing.
+    pub warnings: Vec<String>,
+}
+
+impl RenderedCrate {
+    /// Returns the Cargo features tested by the `cfg` and `cfg_attr` attributes of the sources.
+    pub fn features(&self) -> BTreeSet<String> {
+        let mut features = BTreeSet::new();
+        for (_, content) in &self.files {
+            if let Ok(tokens) = content.parse::<TokenStream>() {
+                collect_features(tokens, false, &mut features);
+            }
+        }
+        features
+    }
+}
+
+/// Adds the names compared with `feature` in `tokens` to `features`, looking inside
+/// the arguments of `cfg` and `cfg_attr` only, or everywhere if `in_cfg` is set.
+fn collect_features(tokens: TokenStream, in_cfg: bool, features: &mut BTreeSet<String>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let is_cfg = index > 0
+                    && matches!(&tokens[index - 1], TokenTree::Ident(ident) if ident == "cfg" || ident == "cfg_attr");
+                collect_features(group.stream(), in_cfg || is_cfg, features);
+            }
+            TokenTree::Ident(ident) if in_cfg && ident == "feature" => {
+                let (Some(TokenTree::Punct(punct)), Some(TokenTree::Literal(literal))) =
+                    (tokens.get(index + 1), tokens.get(index + 2))
+                else {
+                    continue;
+                };
+                if punct.as_char() != '=' {
+                    continue;
+                }
+                if let Ok(syn::Lit::Str(name)) = syn::parse_str::<syn::Lit>(&literal.to_string()) {
+                    features.insert(name.value());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renders the sources of a contract crate built from the `base` crate and the `extensions`.
+///
+/// The top level `.rs` files of `base` are copied, except for the modules the base crate
+/// lists as skipped (see [`config::skipped_modules`]), whose declarations are removed from
+/// `lib.rs`. `lib.rs` is replaced with the composed one.
+pub fn render_crate(base: &Path, extensions: &[String]) -> Result<RenderedCrate, ComposerError> {
+    let extensions = resolve(base, extensions)?;
+    let skipped = config::skipped_modules(base)?;
+    let lib_path = base.join("lib.rs");
+    let base_lib =
+        fs::read_to_string(&lib_path).map_err(|err| ComposerError::Io(lib_path.clone(), err))?;
+    let mut file =
+        syn::parse_file(&base_lib).map_err(|err| ComposerError::Parse(lib_path.clone(), err))?;
+    file.items.retain(|item| {
+        !matches!(item, Item::Mod(module) if module.content.is_none() && skipped.contains(&module.ident.to_string()))
+    });
+    let warnings = merge(&lib_path, &mut file, &extensions)?;
+
+    let mut sources: Vec<PathBuf> = fs::read_dir(base)
+        .map_err(|err| ComposerError::Io(base.to_path_buf(), err))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs") && path.file_name() != Some("lib.rs".as_ref()))
+        .filter(|path| {
+            !path
+                .file_stem()
+                .is_some_and(|stem| skipped.iter().any(|module| stem == module.as_str()))
+        })
+        .collect();
+    sources.sort();
+    let mut files = Vec::new();
+    for source in sources {
+        let content =
+            fs::read_to_string(&source).map_err(|err| ComposerError::Io(source.clone(), err))?;
+        files.push((PathBuf::from(source.file_name().expect("read_dir yields file names")), content));
+    }
+    files.push((PathBuf::from("lib.rs"), prettyplease::unparse(&file)));
+    Ok(RenderedCrate { files, warnings })
+}
+
+/// Generates the sources of a contract crate in `out` from the `base` crate and the `extensions`.
+///
+/// Returns the warnings found while composing.
+pub fn compose_crate(
+    base: &Path,
+    extensions: &[String],
+    out: &Path,
+) -> Result<Vec<String>, ComposerError> {
+    let rendered = render_crate(base, extensions)?;
+    fs::create_dir_all(out).map_err(|err| ComposerError::Io(out.to_path_buf(), err))?;
+    for (name, content) in rendered.files {
+        let target = out.join(name);
+        fs::write(&target, content).map_err(|err| ComposerError::Io(target, err))?;
+    }
+    Ok(rendered.warnings)
+}
+
+/// Returns the Cargo features tested by the sources of the `generated` crate
+/// which its manifest does not declare.
+pub fn undeclared_features(generated: &config::GeneratedCrate) -> Result<Vec<String>, ComposerError> {
+    let rendered = render_crate(&generated.base, &generated.extensions)?;
+    Ok(rendered
+        .features()
+        .into_iter()
+        .filter(|feature| !generated.features.contains(feature))
+        .collect())
+}
+
+/// Compares the sources in `out` with the ones the composer would generate.
+///
+/// Returns the paths of files which are missing, differ or are not generated by the composer.
+pub fn check_crate(
+    base: &Path,
+    extensions: &[String],
+    out: &Path,
+) -> Result<Vec<PathBuf>, ComposerError> {
+    let rendered = render_crate(base, extensions)?;
+    let mut stale = Vec::new();
+    for (name, content) in &rendered.files {
+        let target = out.join(name);
+        if fs::read_to_string(&target).ok().as_ref() != Some(content) {
+            stale.push(target);
+        }
+    }
+    if let Ok(entries) = fs::read_dir(out) {
+        let mut extra: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| !rendered.files.iter().any(|(name, _)| path.file_name() == Some(name.as_os_str())))
+            .collect();
+        extra.sort();
+        stale.extend(extra);
+    }
+    Ok(stale)
+}
+
+fn is_ink_contract(module: &ItemMod) -> bool {
+    module.attrs.iter().any(|attr| {
+        let segments: Vec<String> = attr.path().segments.iter().map(|s| s.ident.to_string()).collect();
+        segments == ["ink", "contract"]
+    })
+}
+
+fn has_ink_arg(attrs: &[syn::Attribute], arg: &str) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("ink")
+            && attr
+                .meta
+                .require_list()
+                .is_ok_and(|list| list.tokens.to_string().split(',').any(|t| t.trim() == arg))
+    })
+}
+
+fn add_uses(items: &mut Vec<Item>, extension: &Extension) {
+    let existing: HashSet<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Use(item_use) => Some(item_use.tree.to_token_stream().to_string()),
+            _ => None,
+        })
+        .collect();
+    let position = items
+        .iter()
+        .position(|item| !matches!(item, Item::Use(_)))
+        .unwrap_or(items.len());
+    let new_uses: Vec<Item> = extension
+        .uses
+        .iter()
+        .filter(|item_use| !existing.contains(&item_use.tree.to_token_stream().to_string()))
+        .cloned()
+        .map(Item::Use)
+        .collect();
+    items.splice(position..position, new_uses);
+}
+
+fn add_storage(items: &mut [Item], extension: &Extension) -> Result<(), ComposerError> {
+    if extension.fields.is_empty() {
+        return Ok(());
+    }
+    let storage = items
+        .iter_mut()
+        .find_map(|item| match item {
+            Item::Struct(item_struct) if has_ink_arg(&item_struct.attrs, "storage") => {
+                Some(item_struct)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| ComposerError::Template("no `#[ink(storage)]` struct".to_string()))?;
+    let Fields::Named(named) = &mut storage.fields else {
+        return Err(ComposerError::Template(
+            "`#[ink(storage)]` struct must have named fields".to_string(),
+        ));
+    };
+    for field in &extension.fields {
+        named.named.push(field.field.clone());
+    }
+
+    for item in items.iter_mut() {
+        let Item::Impl(item_impl) = item else { continue };
+        if item_impl.trait_.is_some() {
+            continue;
+        }
+        for impl_item in item_impl.items.iter_mut() {
+            let ImplItem::Fn(function) = impl_item else { continue };
+            if !has_ink_arg(&function.attrs, "constructor") {
+                continue;
+            }
+            let mut initializers = Vec::new();
+            for field in &extension.fields {
+                let ident = field.field.ident.clone().expect("named field");
+                let value: FieldValue = match &field.init {
+                    Some(init) => {
+                        for arg in &field.args {
+                            if !has_argument(function, &arg.pat) {
+                                function.sig.inputs.push(FnArg::Typed(arg.clone()));
+                            }
+                        }
+                        parse_quote!(#ident: #init)
+                    }
+                    None => {
+                        let ty = &field.field.ty;
+                        let arg: FnArg = parse_quote!(#ident: #ty);
+                        function.sig.inputs.push(arg);
+                        parse_quote!(#ident)
+                    }
+                };
+                initializers.push(value);
+            }
+            StorageInitializer { initializers }.visit_impl_item_fn_mut(function);
+        }
+    }
+    Ok(())
+}
+
+/// Whether `function` already takes an argument bound to `pat`, e.g. one declared by the
+/// initializer of another field.
+fn has_argument(function: &ImplItemFn, pat: &Pat) -> bool {
+    function.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(existing) => existing.pat.to_token_stream().to_string() == pat.to_token_stream().to_string(),
+        FnArg::Receiver(_) => false,
+    })
+}
+
+/// Appends field initializers to every `Self { .. }` expression it visits.
+struct StorageInitializer {
+    initializers: Vec<FieldValue>,
+}
+
+impl VisitMut for StorageInitializer {
+    fn visit_expr_struct_mut(&mut self, node: &mut ExprStruct) {
+        if node.path.is_ident("Self") || node.path.is_ident("Token") {
+            for value in &self.initializers {
+                let exists = node.fields.iter().any(|existing| match (&existing.member, &value.member) {
+                    (Member::Named(a), Member::Named(b)) => a == b,
+                    _ => false,
+                });
+                if !exists {
+                    node.fields.push(value.clone());
+                }
+            }
+        }
+        syn::visit_mut::visit_expr_struct_mut(self, node);
+    }
+}
+
+/// Orders `extensions` so that every extension comes after its dependencies.
+///
+/// Extensions which do not depend on each other keep their resolution order,
+/// so the hooks of a given selection are always concatenated the same way.
+fn hook_order(extensions: &[Extension]) -> Vec<&Extension> {
+    fn visit<'a>(extension: &'a Extension, extensions: &'a [Extension], ordered: &mut Vec<&'a Extension>) {
+        if ordered.iter().any(|visited| visited.name == extension.name) {
+            return;
+        }
+        ordered.push(extension);
+        let position = ordered.len() - 1;
+        for dependency in &extension.dependencies {
+            if let Some(dependency) = extensions.iter().find(|e| &e.name == dependency) {
+                visit(dependency, extensions, ordered);
+            }
+        }
+        // Move the extension behind the dependencies visited above
+        let extension = ordered.remove(position);
+        ordered.push(extension);
+    }
+    let mut ordered = Vec::new();
+    for extension in extensions {
+        visit(extension, extensions, &mut ordered);
+    }
+    ordered
+}
+
+/// Adds the body of `hook` to the hook point of the same name, right before its final `Ok(())`.
+fn apply_hook(items: &mut [Item], extension: &Extension, hook: &ImplItemFn) -> Result<(), ComposerError> {
+    let name = hook.sig.ident.to_string();
+    let error = |message: String| {
+        ComposerError::Template(format!("{}: hook `{}` {}", extension.path.display(), name, message))
+    };
+    let target = find_function(items, None, &name)
+        .ok_or_else(|| error("is not declared by the base contract".to_string()))?;
+    if signature(&target.sig) != signature(&hook.sig) {
+        return Err(error(format!(
+            "has the signature `{}`, but the hook point is `{}`",
+            signature(&hook.sig),
+            signature(&target.sig)
+        )));
+    }
+    let mut stmts = hook.block.stmts.clone();
+    if !stmts.last().is_some_and(is_ok_unit) {
+        return Err(error("must end with `Ok(())`".to_string()));
+    }
+    stmts.pop();
+    let end = target.block.stmts.len().saturating_sub(1);
+    target.block.stmts.splice(end..end, stmts);
+    Ok(())
+}
+
+fn is_ok_unit(stmt: &Stmt) -> bool {
+    let Stmt::Expr(expr, None) = stmt else { return false };
+    let ok: Expr = parse_quote!(Ok(()));
+    expr.to_token_stream().to_string() == ok.to_token_stream().to_string()
+}
+
+fn apply_append(items: &mut [Item], extension: &Extension, append: &Append) -> Result<(), ComposerError> {
+    let name = append.function.sig.ident.to_string();
+    let target_name = match &append.target_trait {
+        Some(target_trait) => format!("{}::{}", target_trait, name),
+        None => name.clone(),
+    };
+    let Some(target) = find_function(items, append.target_trait.as_deref(), &name) else {
+        return Err(ComposerError::Incompatible(format!(
+            "{}: appends to `{}`, which neither the base contract nor the dependencies of `{}` define",
+            extension.path.display(),
+            target_name,
+            extension.name
+        )));
+    };
+    if signature(&target.sig) != signature(&append.function.sig) {
+        return Err(ComposerError::Incompatible(format!(
+            "{}: append to `{}` has the signature `{}`, but the message is `{}`",
+            extension.path.display(),
+            target_name,
+            signature(&append.function.sig),
+            signature(&target.sig)
+        )));
+    }
+    let stmts: Vec<Stmt> = append.function.block.stmts.clone();
+    let line = append.line.min(target.block.stmts.len());
+    target.block.stmts.splice(line..line, stmts);
+    Ok(())
+}
+
+/// Renders the arguments and the return type of a function, ignoring whitespace.
+fn signature(sig: &syn::Signature) -> String {
+    let inputs = sig.inputs.to_token_stream().to_string();
+    let output = sig.output.to_token_stream().to_string();
+    format!("fn {}({}) {}", sig.ident, inputs, output).trim().to_string()
+}
+
+/// Checks the declared conflicts and that no two extensions declare the same
+/// storage field or implement the same trait.
+fn check_compatibility(extensions: &[Extension]) -> Result<(), ComposerError> {
+    let incompatible = |a: &Extension, b: &Extension, reason: String| {
+        Err(ComposerError::Incompatible(format!("`{}` and `{}` {}", a.name, b.name, reason)))
+    };
+    let traits: Vec<BTreeSet<String>> = extensions
+        .iter()
+        .map(|extension| {
+            extension
+                .impls
+                .iter()
+                .filter_map(|item_impl| item_impl.trait_.as_ref())
+                .map(|(_, path, _)| path.to_token_stream().to_string())
+                .collect()
+        })
+        .collect();
+    for (index, a) in extensions.iter().enumerate() {
+        for (offset, b) in extensions[index + 1..].iter().enumerate() {
+            if a.conflicts.contains(&b.name) || b.conflicts.contains(&a.name) {
+                return incompatible(a, b, "cannot be used together".to_string());
+            }
+            for field in &a.fields {
+                let ident = &field.field.ident;
+                if b.fields.iter().any(|other| &other.field.ident == ident) {
+                    return incompatible(
+                        a,
+                        b,
+                        format!("both declare the storage field `{}`", ident.to_token_stream()),
+                    );
+                }
+            }
+            if let Some(common) = traits[index].intersection(&traits[index + 1 + offset]).next() {
+                return incompatible(a, b, format!("both implement `{}`", common));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every extension only uses storage fields of the base contract,
+/// its own ones and the ones of its (transitive) dependencies.
+fn check_storage_access(items: &[Item], extensions: &[Extension]) -> Result<(), ComposerError> {
+    let base_fields: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item_struct) if has_ink_arg(&item_struct.attrs, "storage") => {
+                Some(item_struct.fields.iter().filter_map(|f| f.ident.as_ref()).map(|i| i.to_string()))
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let fields_of = |extension: &Extension| -> Vec<String> {
+        extension
+            .fields
+            .iter()
+            .filter_map(|field| field.field.ident.as_ref().map(|ident| ident.to_string()))
+            .collect()
+    };
+    for extension in extensions {
+        let mut allowed = base_fields.clone();
+        let mut pending = vec![extension];
+        let mut visited = BTreeSet::new();
+        while let Some(current) = pending.pop() {
+            if !visited.insert(current.name.as_str()) {
+                continue;
+            }
+            allowed.extend(fields_of(current));
+            pending.extend(
+                extensions
+                    .iter()
+                    .filter(|other| current.dependencies.contains(&other.name)),
+            );
+        }
+
+        for field in &extension.used_fields {
+            if allowed.contains(field) {
+                continue;
+            }
+            let owner = extensions
+                .iter()
+                .find(|other| fields_of(other).contains(field))
+                .map_or_else(
+                    || "no selected extension".to_string(),
+                    |other| format!("`{}`, which is not among its dependencies", other.name),
+                );
+            return Err(ComposerError::Incompatible(format!(
+                "{}: uses the storage field `{}` declared by {}",
+                extension.path.display(),
+                field,
+                owner
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn find_function<'a>(
+    items: &'a mut [Item],
+    target_trait: Option<&str>,
+    name: &str,
+) -> Option<&'a mut ImplItemFn> {
+    items.iter_mut().find_map(|item| {
+        let Item::Impl(item_impl) = item else { return None };
+        let trait_name = item_impl
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last().map(|s| s.ident.to_string()));
+        if trait_name.as_deref() != target_trait {
+            return None;
+        }
+        item_impl.items.iter_mut().find_map(|impl_item| match impl_item {
+            ImplItem::Fn(function) if function.sig.ident == name => Some(function),
+            _ => None,
+        })
+    })
+}
+
@@ -0,0 +1,141 @@
+//! Discovery of generated contract crates in a cargo workspace.
+//!
+//! A crate is generated by the composer when its manifest contains:
+//!
+//! ```toml
+//! [package.metadata.smart-beaver]
+//! base = "../../.."
+//! extensions = ["capped"]
+//! ```
+//!
+//! `base` is the path (relative to the crate) of the base contract and `extensions`
+//! lists the `.trs` templates applied on top of it. Dependencies of the listed
+//! extensions are resolved automatically.
+//!
+//! A base crate can list modules which generated crates do not need, e.g. test helpers:
+//!
+//! ```toml
+//! [package.metadata.smart-beaver-base]
+//! skip = ["model"]
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+
+use crate::errors::ComposerError;
+
+/// Name of the table under `[package.metadata]` holding the composer configuration.
+pub const METADATA_KEY: &str = "smart-beaver";
+
+/// Name of the table under `[package.metadata]` holding the configuration of a base crate.
+pub const BASE_METADATA_KEY: &str = "smart-beaver-base";
+
+/// A crate whose sources are generated by the composer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedCrate {
+    /// Directory of the generated crate.
+    pub path: PathBuf,
+    /// Directory of the base contract.
+    pub base: PathBuf,
+    /// Extensions applied on top of the base contract.
+    pub extensions: Vec<String>,
+    /// Cargo features declared by the manifest of the generated crate.
+    pub features: Vec<String>,
+}
+
+impl GeneratedCrate {
+    /// Directory the composed sources are written to.
+    pub fn src(&self) -> PathBuf {
+        self.path.join("src")
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Value, ComposerError> {
+    let manifest = path.join("Cargo.toml");
+    let content =
+        fs::read_to_string(&manifest).map_err(|err| ComposerError::Io(manifest.clone(), err))?;
+    content
+        .parse::<Value>()
+        .map_err(|err| ComposerError::Config(format!("{}: {}", manifest.display(), err)))
+}
+
+/// Returns the composer configuration of the crate in `path`, if there is one.
+pub fn generated_crate(path: &Path) -> Result<Option<GeneratedCrate>, ComposerError> {
+    let manifest = read_manifest(path)?;
+    let Some(config) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get(METADATA_KEY))
+    else {
+        return Ok(None);
+    };
+    let invalid = |message: &str| {
+        ComposerError::Config(format!("{}/Cargo.toml: {}", path.display(), message))
+    };
+    let base = config
+        .get("base")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("`base` must be a string"))?;
+    let extensions = config
+        .get("extensions")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("`extensions` must be an array"))?
+        .iter()
+        .map(|name| name.as_str().map(String::from))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| invalid("`extensions` must contain strings only"))?;
+    let features = manifest
+        .get("features")
+        .and_then(Value::as_table)
+        .map(|features| features.keys().cloned().collect())
+        .unwrap_or_default();
+    Ok(Some(GeneratedCrate {
+        path: path.to_path_buf(),
+        base: path.join(base),
+        extensions,
+        features,
+    }))
+}
+
+/// Returns the modules of the base crate in `base` which are not copied into generated crates.
+pub fn skipped_modules(base: &Path) -> Result<Vec<String>, ComposerError> {
+    let manifest = read_manifest(base)?;
+    let Some(skip) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get(BASE_METADATA_KEY))
+        .and_then(|config| config.get("skip"))
+    else {
+        return Ok(Vec::new());
+    };
+    skip.as_array()
+        .and_then(|modules| modules.iter().map(|name| name.as_str().map(String::from)).collect())
+        .ok_or_else(|| {
+            ComposerError::Config(format!(
+                "{}/Cargo.toml: `skip` must be an array of module names",
+                base.display()
+            ))
+        })
+}
+
+/// Lists all generated crates among the members of the workspace rooted at `root`.
+pub fn workspace_crates(root: &Path) -> Result<Vec<GeneratedCrate>, ComposerError> {
+    let manifest = read_manifest(root)?;
+    let members = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(Value::as_array)
+        .ok_or_else(|| ComposerError::Config(format!("{}: no workspace members", root.display())))?;
+    let mut crates = Vec::new();
+    for member in members {
+        let member = member
+            .as_str()
+            .ok_or_else(|| ComposerError::Config("workspace members must be strings".to_string()))?;
+        if let Some(generated) = generated_crate(&root.join(member))? {
+            crates.push(generated);
+        }
+    }
+    Ok(crates)
+}
@@ -0,0 +1,40 @@
+//! Error types for the extension composer.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors that may occur while reading templates or composing contracts.
+#[derive(Debug)]
+pub enum ComposerError {
+    /// A file could not be read or written.
+    Io(PathBuf, std::io::Error),
+    /// A Rust source file or a `.trs` template is not valid Rust syntax.
+    Parse(PathBuf, syn::Error),
+    /// A template is valid Rust but does not follow the extension format.
+    Template(String),
+    /// An extension (or one of its dependencies) could not be found.
+    MissingExtension(String),
+    /// A manifest contains an invalid `smart-beaver` configuration.
+    Config(String),
+    /// The selected extensions cannot be combined, or a template does not match
+    /// the contract it extends.
+    Incompatible(String),
+}
+
+impl fmt::Display for ComposerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComposerError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ComposerError::Parse(path, err) => {
+                let start = err.span().start();
+                write!(f, "{}:{}:{}: {}", path.display(), start.line, start.column + 1, err)
+            }
+            ComposerError::Template(message) => write!(f, "invalid template: {}", message),
+            ComposerError::MissingExtension(name) => write!(f, "unknown extension `{}`", name),
+            ComposerError::Config(message) => write!(f, "invalid configuration: {}", message),
+            ComposerError::Incompatible(message) => write!(f, "incompatible extensions: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ComposerError {}
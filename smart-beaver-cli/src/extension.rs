@@ -0,0 +1,399 @@
+//! Parsing of `.trs` extension templates.
+//!
+//! A template is a regular Rust module annotated with `#[smart_beaver::extension]`.
+//! Its contents describe what should be merged into the base contract:
+//!
+//! * `use` items are added to the contract module,
+//! * the fields of a `#[smart_beaver::storage]` struct are added to the contract storage,
+//!   initialized with their `#[smart_beaver::init(...)]` expression or from a constructor
+//!   argument of the same name,
+//! * `impl` blocks are added to the contract module, unless their functions are
+//!   annotated with `#[smart_beaver::append(line = N)]`, in which case the body of
+//!   the function is spliced into an already existing function with the same name,
+//! * functions of inherent `impl` blocks annotated with `#[smart_beaver::hook]` are
+//!   added to the hook point of the same name declared by the base contract
+//!   (see [`HOOK_POINTS`]),
+//! * every other item (events, constants, helper types) is copied as is.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    parenthesized, Attribute, Expr, ExprArray, Field, FnArg, Ident, ImplItem, ImplItemFn, Item,
+    ItemImpl, ItemUse, Lit, LitInt, Pat, PatType, Token,
+};
+
+use crate::errors::ComposerError;
+
+/// A storage field declared by an extension.
+#[derive(Clone, Debug)]
+pub struct StorageField {
+    /// The field, stripped of all `smart_beaver` attributes.
+    pub field: Field,
+    /// Expression passed to `#[smart_beaver::init(...)]`.
+    ///
+    /// Fields without an initializer become additional constructor arguments.
+    pub init: Option<Expr>,
+    /// Constructor arguments used by the initializer, declared as
+    /// `#[smart_beaver::init(Some(name), args(name: String))]`.
+    pub args: Vec<PatType>,
+}
+
+/// Arguments of the `#[smart_beaver::init(expression, args(name: Type, ..))]` attribute.
+struct Init {
+    expr: Expr,
+    args: Vec<PatType>,
+}
+
+impl Parse for Init {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let expr = input.parse()?;
+        let mut args = Vec::new();
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let keyword: Ident = input.parse()?;
+            if keyword != "args" {
+                return Err(syn::Error::new_spanned(keyword, "unsupported init argument"));
+            }
+            let content;
+            parenthesized!(content in input);
+            let declared = Punctuated::<FnArg, Token![,]>::parse_terminated(&content)?;
+            for arg in declared {
+                match arg {
+                    FnArg::Typed(arg) if matches!(*arg.pat, Pat::Ident(_)) => args.push(arg),
+                    other => return Err(syn::Error::new_spanned(other, "expected `name: Type`")),
+                }
+            }
+        }
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after init arguments"));
+        }
+        Ok(Init { expr, args })
+    }
+}
+
+/// A function whose body is spliced into an existing function of the contract.
+#[derive(Clone, Debug)]
+pub struct Append {
+    /// Name of the trait implemented by the target `impl` block, `None` for inherent impls.
+    pub target_trait: Option<String>,
+    /// The function carrying the statements to insert.
+    pub function: ImplItemFn,
+    /// Index of the statement before which the body is inserted.
+    pub line: usize,
+}
+
+/// Names of the hook points the base contracts declare as inherent `Token` functions.
+///
+/// Each of them returns `Result<(), Error>`, so an extension can veto an operation by
+/// returning an error from its hook. `PSP22Data` and `PSP34Data` do not call them: every
+/// message changing balances or allowances, of the base contract or of an extension like
+/// `batch` or `mintable`, must call the hook point of the operation itself before calling
+/// `PSP22Data`/`PSP34Data`. `before_initial_mint` is called by the constructor instead, and
+/// `transfer_fee` also lets an extension set the fee of a transfer.
+pub const HOOK_POINTS: &[&str] = &[
+    "before_transfer",
+    "transfer_fee",
+    "after_transfer",
+    "before_initial_mint",
+    "before_mint",
+    "before_burn",
+    "before_approve",
+];
+
+/// A parsed `.trs` extension template.
+#[derive(Clone, Debug)]
+pub struct Extension {
+    /// Name of the extension, equal to the template file stem.
+    pub name: String,
+    /// Path of the template the extension was read from.
+    pub path: PathBuf,
+    /// Names of the extensions this one depends on.
+    pub dependencies: Vec<String>,
+    /// Names of the extensions which cannot be used together with this one.
+    pub conflicts: Vec<String>,
+    pub uses: Vec<ItemUse>,
+    pub fields: Vec<StorageField>,
+    pub impls: Vec<ItemImpl>,
+    pub appends: Vec<Append>,
+    /// Functions whose bodies are added to the hook point with the same name.
+    pub hooks: Vec<ImplItemFn>,
+    pub items: Vec<Item>,
+    /// Names of the storage fields the extension accesses as `self.field`.
+    pub used_fields: BTreeSet<String>,
+    /// Non-fatal problems of the template, reported whenever it is composed.
+    pub warnings: Vec<String>,
+}
+
+impl Extension {
+    /// Reads and parses the template at `path`.
+    pub fn from_file(path: &Path) -> Result<Extension, ComposerError> {
+        let source =
+            fs::read_to_string(path).map_err(|err| ComposerError::Io(path.to_path_buf(), err))?;
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| ComposerError::Template(format!("{}: invalid file name", path.display())))?
+            .to_string();
+        Self::parse(&name, path, &source)
+    }
+
+    /// Parses the `source` of a template called `name`.
+    pub fn parse(name: &str, path: &Path, source: &str) -> Result<Extension, ComposerError> {
+        let file = syn::parse_file(source).map_err(|err| ComposerError::Parse(path.to_path_buf(), err))?;
+        let template_error = |message: &str| {
+            ComposerError::Template(format!("{}: {}", path.display(), message))
+        };
+
+        let module = file
+            .items
+            .into_iter()
+            .find_map(|item| match item {
+                Item::Mod(module) if has_attribute(&module.attrs, "extension") => Some(module),
+                _ => None,
+            })
+            .ok_or_else(|| template_error("missing `#[smart_beaver::extension]` module"))?;
+
+        let (dependencies, conflicts) = parse_extension_args(&module.attrs)
+            .map_err(|err| ComposerError::Parse(path.to_path_buf(), err))?;
+        let mut extension = Extension {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            dependencies,
+            conflicts,
+            uses: vec![],
+            fields: vec![],
+            impls: vec![],
+            appends: vec![],
+            hooks: vec![],
+            items: vec![],
+            used_fields: BTreeSet::new(),
+            warnings: vec![],
+        };
+
+        let (_, content) = module
+            .content
+            .ok_or_else(|| template_error("extension module has no body"))?;
+        for item in content {
+            match item {
+                Item::Use(item_use) => extension.uses.push(item_use),
+                Item::Struct(item_struct) if has_attribute(&item_struct.attrs, "storage") => {
+                    for mut field in item_struct.fields {
+                        let init = find_attribute(&field.attrs, "init")
+                            .map(|attr| attr.parse_args::<Init>())
+                            .transpose()
+                            .map_err(|err| ComposerError::Parse(path.to_path_buf(), err))?;
+                        field.attrs.retain(|attr| !is_smart_beaver(attr));
+                        let (init, args) = match init {
+                            Some(Init { expr, args }) => (Some(expr), args),
+                            None => (None, vec![]),
+                        };
+                        extension.fields.push(StorageField { field, init, args });
+                    }
+                }
+                Item::Impl(item_impl) => extension
+                    .split_impl(item_impl)
+                    .map_err(|err| ComposerError::Parse(path.to_path_buf(), err))?,
+                item => extension.items.push(item),
+            }
+        }
+        let mut used_fields = BTreeSet::new();
+        for function in extension.functions() {
+            self_fields(function.to_token_stream(), &mut used_fields);
+        }
+        extension.used_fields = used_fields;
+        extension.warnings = unused_imports(&extension);
+        Ok(extension)
+    }
+
+    /// Returns all functions of the template: the ones of `impl` blocks, hooks and appends.
+    fn functions(&self) -> Vec<&ImplItemFn> {
+        self.impls
+            .iter()
+            .flat_map(|item_impl| &item_impl.items)
+            .filter_map(|item| match item {
+                ImplItem::Fn(function) => Some(function),
+                _ => None,
+            })
+            .chain(&self.hooks)
+            .chain(self.appends.iter().map(|append| &append.function))
+            .collect()
+    }
+
+    /// Separates `append` and `hook` functions of an `impl` block from the regular ones.
+    fn split_impl(&mut self, mut item_impl: ItemImpl) -> syn::Result<()> {
+        let target_trait = item_impl.trait_.as_ref().and_then(|(_, path, _)| {
+            path.segments.last().map(|segment| segment.ident.to_string())
+        });
+        let mut regular = Vec::new();
+        for impl_item in item_impl.items {
+            match impl_item {
+                ImplItem::Fn(mut function) if has_attribute(&function.attrs, "hook") => {
+                    if target_trait.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &function.sig,
+                            "hooks must be defined in an inherent `impl Token` block",
+                        ));
+                    }
+                    let name = function.sig.ident.to_string();
+                    if !HOOK_POINTS.contains(&name.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            &function.sig.ident,
+                            format!("unknown hook point `{}`, expected one of {:?}", name, HOOK_POINTS),
+                        ));
+                    }
+                    function.attrs.retain(|attr| !is_smart_beaver(attr));
+                    self.hooks.push(function);
+                }
+                ImplItem::Fn(mut function) => match find_attribute(&function.attrs, "append") {
+                    Some(attr) => {
+                        let line = parse_line(attr)?;
+                        function.attrs.retain(|attr| !is_smart_beaver(attr));
+                        self.appends.push(Append {
+                            target_trait: target_trait.clone(),
+                            function,
+                            line,
+                        });
+                    }
+                    None => regular.push(ImplItem::Fn(function)),
+                },
+                other => regular.push(other),
+            }
+        }
+        if !regular.is_empty() {
+            item_impl.items = regular;
+            self.impls.push(item_impl);
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if the attribute lives in the `smart_beaver` namespace.
+pub fn is_smart_beaver(attr: &Attribute) -> bool {
+    let segments = &attr.path().segments;
+    segments.len() == 2 && segments[0].ident == "smart_beaver"
+}
+
+fn has_attribute(attrs: &[Attribute], name: &str) -> bool {
+    find_attribute(attrs, name).is_some()
+}
+
+fn find_attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs
+        .iter()
+        .find(|attr| is_smart_beaver(attr) && attr.path().segments[1].ident == name)
+}
+
+/// Parses the `dependencies` and `conflicts` lists of the `#[smart_beaver::extension]` attribute.
+fn parse_extension_args(attrs: &[Attribute]) -> syn::Result<(Vec<String>, Vec<String>)> {
+    let mut dependencies = Vec::new();
+    let mut conflicts = Vec::new();
+    let Some(attr) = find_attribute(attrs, "extension") else {
+        return Ok((dependencies, conflicts));
+    };
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return Ok((dependencies, conflicts));
+    }
+    attr.parse_nested_meta(|meta| {
+        let names = if meta.path.is_ident("dependencies") {
+            &mut dependencies
+        } else if meta.path.is_ident("conflicts") {
+            &mut conflicts
+        } else {
+            return Err(meta.error("unsupported extension argument"));
+        };
+        let array: ExprArray = meta.value()?.parse()?;
+        for element in array.elems {
+            match element {
+                Expr::Lit(syn::ExprLit { lit: Lit::Str(name), .. }) => names.push(name.value()),
+                other => return Err(syn::Error::new_spanned(other, "expected a string literal")),
+            }
+        }
+        Ok(())
+    })?;
+    Ok((dependencies, conflicts))
+}
+
+fn parse_line(attr: &Attribute) -> syn::Result<usize> {
+    let mut line = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("line") {
+            let value: LitInt = meta.value()?.parse()?;
+            line = Some(value.base10_parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported append argument"))
+        }
+    })?;
+    line.ok_or_else(|| syn::Error::new_spanned(attr, "missing `line` argument"))
+}
+
+/// Collects the names of the fields accessed as `self.field`, also inside macro invocations.
+fn self_fields(tokens: TokenStream, fields: &mut BTreeSet<String>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        if let TokenTree::Group(group) = token {
+            self_fields(group.stream(), fields);
+            continue;
+        }
+        let (Some(TokenTree::Punct(dot)), Some(TokenTree::Ident(field))) =
+            (tokens.get(index + 1), tokens.get(index + 2))
+        else {
+            continue;
+        };
+        let is_call = matches!(
+            tokens.get(index + 3),
+            Some(TokenTree::Group(group)) if group.delimiter() == proc_macro2::Delimiter::Parenthesis
+        );
+        if matches!(token, TokenTree::Ident(ident) if ident == "self") && dot.as_char() == '.' && !is_call {
+            fields.insert(field.to_string());
+        }
+    }
+}
+
+/// Reports the traits a template imports from the crate but never uses.
+fn unused_imports(extension: &Extension) -> Vec<String> {
+    let mut body = TokenStream::new();
+    for item_impl in &extension.impls {
+        item_impl.to_tokens(&mut body);
+    }
+    for function in extension.hooks.iter().chain(extension.appends.iter().map(|a| &a.function)) {
+        function.to_tokens(&mut body);
+    }
+    for item in &extension.items {
+        item.to_tokens(&mut body);
+    }
+    for field in &extension.fields {
+        field.field.to_tokens(&mut body);
+        field.init.to_tokens(&mut body);
+        for arg in &field.args {
+            arg.to_tokens(&mut body);
+        }
+    }
+    let body = body.to_string();
+    let used: HashSet<&str> = body.split(|c: char| !c.is_alphanumeric() && c != '_').collect();
+    extension
+        .uses
+        .iter()
+        .filter_map(|item_use| match &item_use.tree {
+            syn::UseTree::Path(path) if path.ident == "crate" => last_name(&path.tree),
+            _ => None,
+        })
+        .filter(|name| !used.contains(name.as_str()))
+        .map(|name| format!("{}: `{}` is imported but never used", extension.path.display(), name))
+        .collect()
+}
+
+fn last_name(tree: &syn::UseTree) -> Option<String> {
+    match tree {
+        syn::UseTree::Path(path) => last_name(&path.tree),
+        syn::UseTree::Name(name) => Some(name.ident.to_string()),
+        syn::UseTree::Rename(rename) => Some(rename.rename.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,16 @@
+//! Composer of Smart-Beaver contracts.
+//!
+//! Turns a base contract (`PSP22`, `PSP34`) and a selection of `.trs` extension
+//! templates into a single compilable ink! contract.
+
+pub mod compose;
+pub mod config;
+pub mod errors;
+pub mod extension;
+
+pub use compose::{
+    available_extensions, check_combinations, check_crate, combinations, compose_crate,
+    compose_lib, render_crate, resolve, undeclared_features,
+};
+pub use errors::ComposerError;
+pub use extension::Extension;
@@ -0,0 +1,122 @@
+//! Command line interface of the Smart-Beaver composer.
+//!
+//! ```text
+//! smart-beaver init [WORKSPACE]
+//! smart-beaver check [WORKSPACE]
+//! smart-beaver compose BASE OUT [EXTENSION...]
+//! smart-beaver combinations BASE
+//! ```
+//!
+//! `init` regenerates the sources of every workspace member configured with
+//! `[package.metadata.smart-beaver]`, removing the files it no longer generates, `check` fails if any of them is out of date,
+//! `compose` writes a single contract to `OUT` and `combinations` prints every valid
+//! combination of the extensions of `BASE`, one per line.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use smart_beaver_cli::{
+    check_crate, combinations, compose_crate, config, undeclared_features, ComposerError,
+};
+
+const USAGE: &str = "usage:
+    smart-beaver init [WORKSPACE]
+    smart-beaver check [WORKSPACE]
+    smart-beaver compose BASE OUT [EXTENSION...]
+    smart-beaver combinations BASE";
+
+fn print_warnings(warnings: Vec<String>) {
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+fn init(root: &Path) -> Result<(), ComposerError> {
+    for generated in config::workspace_crates(root)? {
+        println!(
+            "Generating {} [{}]",
+            generated.path.display(),
+            generated.extensions.join(", ")
+        );
+        print_warnings(compose_crate(&generated.base, &generated.extensions, &generated.src())?);
+        // Whatever is still out of date is left over from an earlier generation.
+        for leftover in check_crate(&generated.base, &generated.extensions, &generated.src())? {
+            println!("Removing {}", leftover.display());
+            fs::remove_file(&leftover).map_err(|err| ComposerError::Io(leftover.clone(), err))?;
+        }
+        print_warnings(
+            undeclared_features(&generated)?
+                .into_iter()
+                .map(|feature| undeclared_feature(&generated, &feature))
+                .collect(),
+        );
+    }
+    Ok(())
+}
+
+fn undeclared_feature(generated: &config::GeneratedCrate, feature: &str) -> String {
+    format!(
+        "{}/Cargo.toml: feature `{}` is used by the generated sources but not declared",
+        generated.path.display(),
+        feature
+    )
+}
+
+fn check(root: &Path) -> Result<(), ComposerError> {
+    let mut stale = Vec::new();
+    let mut undeclared = Vec::new();
+    for generated in config::workspace_crates(root)? {
+        stale.extend(check_crate(&generated.base, &generated.extensions, &generated.src())?);
+        for feature in undeclared_features(&generated)? {
+            undeclared.push(undeclared_feature(&generated, &feature));
+        }
+    }
+    for path in &stale {
+        eprintln!("out of date: {}", path.display());
+    }
+    for message in &undeclared {
+        eprintln!("{}", message);
+    }
+    if !stale.is_empty() {
+        return Err(ComposerError::Config(
+            "generated sources are out of date, run `smart-beaver init`".to_string(),
+        ));
+    }
+    if !undeclared.is_empty() {
+        return Err(ComposerError::Config(
+            "generated crates do not declare the features their sources use".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let workspace = PathBuf::from(args.get(1).map_or(".", String::as_str));
+    let result = match args.first().map(String::as_str) {
+        Some("init") => init(&workspace),
+        Some("check") => check(&workspace),
+        Some("compose") if args.len() >= 3 => {
+            compose_crate(Path::new(&args[1]), &args[3..], Path::new(&args[2])).map(print_warnings)
+        }
+        Some("combinations") if args.len() == 2 => {
+            combinations(Path::new(&args[1])).map(|combinations| {
+                for combination in combinations {
+                    println!("{}", combination.join(" "));
+                }
+            })
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::path::Path;
+
+use smart_beaver_cli::{
+    check_combinations, check_crate, combinations, compose_lib, config, resolve, undeclared_features,
+    ComposerError, Extension,
+};
+
+const BASE: &str = r#"
+#[ink::contract]
+pub mod token {
+    use crate::PSP22;
+
+    #[ink(storage)]
+    pub struct Token {
+        data: u128,
+    }
+
+    impl Token {
+        #[ink(constructor)]
+        pub fn new(supply: u128) -> Self {
+            Self { data: supply }
+        }
+
+        fn before_transfer(&mut self, from: AccountId, value: u128) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+    }
+
+    impl PSP22 for Token {
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            self.data -= value;
+            Ok(())
+        }
+    }
+}
+"#;
+
+const CAPPED: &str = r#"
+#[smart_beaver::extension(dependencies = ["ownable"])]
+pub mod capped_extension {
+    use crate::traits::PSP22Capped;
+
+    #[smart_beaver::storage]
+    pub struct Token {
+        cap: u128,
+        #[smart_beaver::init(false)]
+        locked: bool,
+    }
+
+    impl PSP22Capped for Token {
+        #[ink(message)]
+        fn cap(&self) -> u128 {
+            self.cap
+        }
+    }
+
+    impl PSP22 for Token {
+        #[ink(message)]
+        #[smart_beaver::append(line = 0)]
+        fn transfer(&mut self, to: AccountId, value: u128) -> Result<(), PSP22Error> {
+            assert!(!self.locked);
+        }
+    }
+}
+"#;
+
+fn parse(name: &str, source: &str) -> Extension {
+    Extension::parse(name, Path::new(name), source).expect("valid template")
+}
+
+fn normalize(source: &str) -> String {
+    source.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("workspace root")
+}
+
+#[test]
+fn template_is_parsed() {
+    let extension = parse("capped", CAPPED);
+    assert_eq!(extension.dependencies, vec!["ownable".to_string()]);
+    assert_eq!(extension.uses.len(), 1);
+    assert_eq!(extension.fields.len(), 2);
+    assert!(extension.fields[0].init.is_none());
+    assert!(extension.fields[1].init.is_some());
+    assert_eq!(extension.impls.len(), 1);
+    assert_eq!(extension.appends.len(), 1);
+    assert_eq!(extension.appends[0].target_trait.as_deref(), Some("PSP22"));
+    assert_eq!(extension.appends[0].line, 0);
+}
+
+#[test]
+fn template_without_extension_module_is_rejected() {
+    let result = Extension::parse("broken", Path::new("broken.trs"), "pub mod broken {}");
+    assert!(matches!(result, Err(ComposerError::Template(_))));
+    let result = Extension::parse("broken", Path::new("broken.trs"), "pub mod broken {");
+    assert!(matches!(result, Err(ComposerError::Parse(..))));
+}
+
+#[test]
+fn storage_and_constructor_are_extended() {
+    let composed = compose_lib(Path::new("lib.rs"), BASE, &[parse("capped", CAPPED)])
+        .expect("composition succeeds");
+    let source = normalize(&composed.source);
+    assert!(composed.warnings.is_empty());
+    assert!(source.contains("use crate::traits::PSP22Capped;"));
+    assert!(source.contains("pub struct Token { data: u128, cap: u128, locked: bool, }"));
+    assert!(source.contains("pub fn new(supply: u128, cap: u128) -> Self"));
+    assert!(source.contains("Self { data: supply, cap, locked: false, }"));
+    assert!(source.contains("impl PSP22Capped for Token"));
+    assert!(source.contains("assert!(! self.locked); self.data -= value;"));
+}
+
+#[test]
+fn initializer_arguments_are_added_to_constructor() {
+    let template = r#"
+        #[smart_beaver::extension]
+        pub mod metadata_extension {
+            #[smart_beaver::storage]
+            pub struct Token {
+                #[smart_beaver::init(name.is_empty(), args(name: String))]
+                unnamed: bool,
+                #[smart_beaver::init(Some(name), args(name: String))]
+                name: Option<String>,
+                #[smart_beaver::init(None)]
+                icon_uri: Option<String>,
+            }
+        }
+    "#;
+    let composed = compose_lib(Path::new("lib.rs"), BASE, &[parse("metadata", template)])
+        .expect("composition succeeds");
+    let source = normalize(&composed.source);
+    assert!(source.contains("pub fn new(supply: u128, name: String) -> Self"));
+    assert!(source.contains("Self { data: supply, unnamed: name.is_empty(), name: Some(name), icon_uri: None, }"));
+}
+
+#[test]
+fn invalid_initializer_arguments_are_rejected() {
+    for init in ["None, params(name: String)", "None, args(self)", "None, args(name: String) name"] {
+        let template = format!(
+            "#[smart_beaver::extension] pub mod broken {{ #[smart_beaver::storage] pub struct Token {{ #[smart_beaver::init({init})] name: Option<String>, }} }}"
+        );
+        let result = Extension::parse("broken", Path::new("broken.trs"), &template);
+        assert!(matches!(result, Err(ComposerError::Parse(..))), "{init} is accepted");
+    }
+}
+
+#[test]
+fn append_without_target_is_rejected() {
+    let template = r#"
+        #[smart_beaver::extension]
+        pub mod pausable_extension {
+            impl PSP22Burnable for Token {
+                #[smart_beaver::append(line = 0)]
+                fn burn(&mut self, value: u128) -> Result<(), PSP22Error> {
+                    assert!(!self.data.paused);
+                }
+            }
+        }
+    "#;
+    let result = compose_lib(Path::new("lib.rs"), BASE, &[parse("pausable", template)]);
+    assert!(matches!(result, Err(ComposerError::Incompatible(message)) if message.contains("PSP22Burnable::burn")));
+}
+
+#[test]
+fn append_with_different_signature_is_rejected() {
+    let template = r#"
+        #[smart_beaver::extension]
+        pub mod fees_extension {
+            impl PSP22 for Token {
+                #[smart_beaver::append(line = 0)]
+                fn transfer(&mut self, value: u128) -> Result<(), PSP22Error> {
+                    self.data += 1;
+                }
+            }
+        }
+    "#;
+    let result = compose_lib(Path::new("lib.rs"), BASE, &[parse("fees", template)]);
+    assert!(matches!(result, Err(ComposerError::Incompatible(message)) if message.contains("signature")));
+}
+
+#[test]
+fn storage_of_undeclared_dependency_is_rejected() {
+    let ownable = r#"
+        #[smart_beaver::extension]
+        pub mod ownable_extension {
+            #[smart_beaver::storage]
+            pub struct Token {
+                #[smart_beaver::init(None)]
+                owner: Option<AccountId>,
+            }
+        }
+    "#;
+    let pausable = r#"
+        #[smart_beaver::extension]
+        pub mod pausable_extension {
+            impl PSP22Pausable for Token {
+                #[ink(message)]
+                fn pause(&mut self) -> Result<(), PSP22Error> {
+                    assert_eq!(Some(self.env().caller()), self.owner);
+                    Ok(())
+                }
+            }
+        }
+    "#;
+    let extensions = [parse("pausable", pausable), parse("ownable", ownable)];
+    let result = compose_lib(Path::new("lib.rs"), BASE, &extensions);
+    assert!(matches!(result, Err(ComposerError::Incompatible(message)) if message.contains("`ownable`, which is not among its dependencies")));
+
+    let pausable = pausable.replace("#[smart_beaver::extension]", r#"#[smart_beaver::extension(dependencies = ["ownable"])]"#);
+    let extensions = [parse("pausable", &pausable), parse("ownable", ownable)];
+    assert!(compose_lib(Path::new("lib.rs"), BASE, &extensions).is_ok());
+}
+
+#[test]
+fn conflicting_extensions_are_rejected() {
+    let native = r#"
+        #[smart_beaver::extension(conflicts = ["capped"])]
+        pub mod native_extension {}
+    "#;
+    let native = parse("native", native);
+    assert_eq!(native.conflicts, vec!["capped".to_string()]);
+    let result = compose_lib(Path::new("lib.rs"), BASE, &[parse("capped", CAPPED), native]);
+    assert!(matches!(result, Err(ComposerError::Incompatible(message)) if message.contains("cannot be used together")));
+
+    let duplicate = CAPPED.replace("capped_extension", "duplicate_extension");
+    let result = compose_lib(Path::new("lib.rs"), BASE, &[parse("capped", CAPPED), parse("duplicate", &duplicate)]);
+    assert!(matches!(result, Err(ComposerError::Incompatible(message)) if message.contains("storage field `cap`")));
+}
+
+#[test]
+fn unused_imports_are_reported() {
+    let template = CAPPED.replace("use crate::traits::PSP22Capped;", "use crate::traits::PSP22Capped;\n    use crate::traits::PSP22Mintable;");
+    let composed = compose_lib(Path::new("lib.rs"), BASE, &[parse("capped", &template)])
+        .expect("composition succeeds");
+    assert_eq!(composed.warnings.len(), 1);
+    assert!(composed.warnings[0].contains("PSP22Mintable"));
+}
+
+#[test]
+fn every_combination_composes() {
+    for base in ["PSP22", "PSP34"] {
+        let base = workspace_root().join(base);
+        let checked = check_combinations(&base).unwrap_or_else(|err| panic!("{}", err));
+        assert!(checked > 1);
+        let combinations = combinations(&base).expect("templates are valid");
+        assert_eq!(combinations.len(), checked);
+        assert!(combinations.iter().any(Vec::is_empty));
+        let largest = combinations.iter().max_by_key(|c| c.len()).expect("not empty");
+        assert_eq!(resolve(&base, largest).expect("extensions exist").len(), largest.len());
+    }
+}
+
+const SIGNATURE: &str = "&mut self, from: AccountId, value: u128) -> Result<(), PSP22Error>";
+
+/// Returns an extension hooking into `before_transfer` with the given signature, written after `fn before_transfer(`.
+fn hook_template(name: &str, dependencies: &str, signature: &str, body: &str) -> Extension {
+    let source = format!(
+        r#"
+        #[smart_beaver::extension(dependencies = [{}])]
+        pub mod hook_extension {{
+            impl Token {{
+                #[smart_beaver::hook]
+                fn before_transfer({} {{
+                    {}
+                }}
+            }}
+        }}
+    "#,
+        dependencies, signature, body
+    );
+    parse(name, &source)
+}
+
+#[test]
+fn hooks_follow_dependencies() {
+    let extensions = vec![
+        hook_template("pausable", r#""capped""#, SIGNATURE, "self.ensure_not_paused()?; Ok(())"),
+        hook_template("capped", "", SIGNATURE, "self.ensure_below_cap(value)?; Ok(())"),
+    ];
+    assert_eq!(extensions[0].hooks.len(), 1);
+    assert!(extensions[0].impls.is_empty());
+    let composed =
+        compose_lib(Path::new("lib.rs"), BASE, &extensions).expect("composition succeeds");
+    let source = normalize(&composed.source);
+    assert!(source.contains(
+        "-> Result<(), PSP22Error> { self.ensure_below_cap(value)?; self.ensure_not_paused()?; Ok(()) }"
+    ));
+}
+
+#[test]
+fn invalid_hooks_are_rejected() {
+    let unknown = r#"
+        #[smart_beaver::extension]
+        pub mod unknown_extension {
+            impl Token {
+                #[smart_beaver::hook]
+                fn before_everything(&mut self) -> Result<(), PSP22Error> {
+                    Ok(())
+                }
+            }
+        }
+    "#;
+    let result = Extension::parse("unknown", Path::new("unknown.trs"), unknown);
+    assert!(matches!(result, Err(ComposerError::Parse(..))));
+
+    let hook_point = "the hook point is `fn before_transfer(& mut self , from : AccountId , value : u128) -> Result < () , PSP22Error >`";
+    for signature in [
+        "&mut self, sender: AccountId, value: u128) -> Result<(), PSP22Error>",
+        "&mut self, from: AccountId, value: u64) -> Result<(), PSP22Error>",
+        "&mut self, from: AccountId) -> Result<(), PSP22Error>",
+        "&self, from: AccountId, value: u128) -> Result<(), PSP22Error>",
+        "&mut self, from: AccountId, value: u128) -> Result<(), OwnableError>",
+    ] {
+        let mismatched = hook_template("mismatched", "", signature, "Ok(())");
+        let result = compose_lib(Path::new("lib.rs"), BASE, &[mismatched]);
+        assert!(
+            matches!(&result, Err(ComposerError::Template(message)) if message.contains(hook_point)),
+            "{}: {:?}",
+            signature,
+            result.err()
+        );
+    }
+
+    let without_ok = hook_template("without_ok", "", SIGNATURE, "self.check()");
+    let result = compose_lib(Path::new("lib.rs"), BASE, &[without_ok]);
+    assert!(matches!(result, Err(ComposerError::Template(message)) if message.contains("Ok(())")));
+}
+
+#[test]
+fn dependencies_are_resolved() {
+    let base = workspace_root().join("PSP22");
+    let names = |extensions: Vec<Extension>| -> Vec<String> {
+        extensions.into_iter().map(|extension| extension.name).collect()
+    };
+    let resolved = resolve(&base, &["pausable".to_string(), "mintable".to_string()])
+        .expect("extensions exist");
+    assert_eq!(names(resolved), vec!["pausable", "mintable", "ownable"]);
+
+    let result = resolve(&base, &["missing".to_string()]);
+    assert!(matches!(result, Err(ComposerError::MissingExtension(name)) if name == "missing"));
+}
+
+#[test]
+fn generated_crates_are_up_to_date() {
+    let crates = config::workspace_crates(workspace_root()).expect("valid workspace");
+    assert!(!crates.is_empty());
+    for generated in crates {
+        let stale = check_crate(&generated.base, &generated.extensions, &generated.src())
+            .expect("composition succeeds");
+        assert!(
+            stale.is_empty(),
+            "{:?} are out of date, run `cargo run -p smart-beaver-cli -- init`",
+            stale
+        );
+        let undeclared = undeclared_features(&generated).expect("composition succeeds");
+        assert!(
+            undeclared.is_empty(),
+            "{} does not declare the features {:?}",
+            generated.path.display(),
+            undeclared
+        );
+    }
+}
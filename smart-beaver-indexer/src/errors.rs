@@ -0,0 +1,51 @@
+//! Error types of the indexer.
+
+use std::fmt;
+
+use ink::primitives::AccountId;
+use psp34_full::Id;
+
+/// Errors raised when an event cannot follow the events indexed before it.
+///
+/// Contracts never emit such sequences, so they mean that events of the stream
+/// are missing, duplicated or out of order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexerError {
+    /// A PSP22 transfer or burn of `value` exceeds the indexed balance of `account`.
+    InsufficientBalance {
+        account: AccountId,
+        balance: u128,
+        value: u128,
+    },
+    /// A PSP22 mint or transfer makes the total supply or a balance exceed `u128::MAX`.
+    Overflow,
+    /// A PSP34 token is minted while it already exists.
+    TokenExists(Id),
+    /// A PSP34 token is transferred or burned from `from`, but is indexed as owned by `owner`.
+    NotOwner {
+        id: Id,
+        from: AccountId,
+        owner: Option<AccountId>,
+    },
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::InsufficientBalance { account, balance, value } => write!(
+                f,
+                "transfer of {} from {:?} exceeds its balance of {}",
+                value, account, balance
+            ),
+            IndexerError::Overflow => write!(f, "supply overflows u128"),
+            IndexerError::TokenExists(id) => write!(f, "token {:?} minted twice", id),
+            IndexerError::NotOwner { id, from, owner } => write!(
+                f,
+                "token {:?} moved from {:?} but owned by {:?}",
+                id, from, owner
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
@@ -0,0 +1,37 @@
+//! Conversion of indexed values to JSON.
+//!
+//! Accounts and byte strings are written as `0x`-prefixed hex strings, amounts as decimal
+//! strings, as JSON numbers cannot represent every `u128`.
+
+use ink::primitives::AccountId;
+use psp34_full::Id;
+use serde_json::{json, Value};
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len());
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+pub(crate) fn account(account: &AccountId) -> Value {
+    Value::String(hex(account.as_ref()))
+}
+
+pub(crate) fn amount(amount: u128) -> Value {
+    Value::String(amount.to_string())
+}
+
+/// Writes a token id as an object with its variant as the only key, e.g. `{"U8": "1"}`.
+pub(crate) fn id(id: &Id) -> Value {
+    match id {
+        Id::U8(value) => json!({ "U8": value.to_string() }),
+        Id::U16(value) => json!({ "U16": value.to_string() }),
+        Id::U32(value) => json!({ "U32": value.to_string() }),
+        Id::U64(value) => json!({ "U64": value.to_string() }),
+        Id::U128(value) => json!({ "U128": value.to_string() }),
+        Id::Bytes(value) => json!({ "Bytes": hex(value) }),
+    }
+}
@@ -0,0 +1,140 @@
+//! Indexer of Smart-Beaver token events.
+//!
+//! Decodes the `Transfer`, `Approval` and `AttributeSet` events emitted by
+//! PSP22 and PSP34 contracts built on `PSP22Data` and `PSP34Data` from their topics and SCALE
+//! payload, and rebuilds balances, allowances and ownership from an ordered event stream.
+//! The events are recognised by their signature topic (`PSP22::Transfer`, `PSP34::Transfer`, ...),
+//! which is the same for every contract emitting them with `psp22_events!` or `psp34_events!`,
+//! see `events.rs` of both crates.
+//!
+//! Feeding the `ink::env::test::recorded_events()` of a unit test to [`Indexer::apply_recorded`]
+//! rebuilds the state of the contract under test, ready to be compared with it.
+
+pub mod errors;
+mod export;
+pub mod psp22;
+pub mod psp34;
+
+use ink::env::test::EmittedEvent;
+use ink::primitives::AccountId;
+use psp22_full::PSP22Event;
+use psp34_full::PSP34Event;
+use serde_json::{json, Value};
+
+pub use errors::IndexerError;
+pub use psp22::PSP22Index;
+pub use psp34::PSP34Index;
+
+/// An event of a PSP22 or a PSP34 contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    PSP22(PSP22Event),
+    PSP34(PSP34Event),
+}
+
+/// Decodes an event from its `topics` and its payload `data`.
+///
+/// Returns `None` for events of other kinds, e.g. emitted by extensions, or malformed payloads.
+pub fn decode_event<T: AsRef<[u8]>>(topics: &[T], data: &[u8]) -> Option<Event> {
+    let signature = topics.first()?.as_ref();
+    psp22_full::events::decode_event(signature, data)
+        .map(Event::PSP22)
+        .or_else(|| psp34_full::events::decode_event(signature, data).map(Event::PSP34))
+}
+
+/// Tables of a PSP22 and a PSP34 token rebuilt from the events of a contract.
+///
+/// Events must be applied in the order they were emitted. A contract usually emits
+/// the events of a single standard, leaving the other tables empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Indexer {
+    psp22: PSP22Index,
+    psp34: PSP34Index,
+    skipped: usize,
+}
+
+impl Indexer {
+    /// Creates an indexer with empty tables.
+    pub fn new() -> Indexer {
+        Default::default()
+    }
+
+    /// Creates an indexer of a PSP22 token with `supply` balance held by the `creator` account.
+    ///
+    /// See [`PSP22Index::new`].
+    pub fn with_initial_supply(supply: u128, creator: AccountId) -> Indexer {
+        Indexer {
+            psp22: PSP22Index::new(supply, creator),
+            ..Indexer::new()
+        }
+    }
+
+    /// Applies a decoded `event` to the tables.
+    pub fn apply(&mut self, event: &Event) -> Result<(), IndexerError> {
+        match event {
+            Event::PSP22(event) => self.psp22.apply(event),
+            Event::PSP34(event) => self.psp34.apply(event),
+        }
+    }
+
+    /// Decodes an event from its `topics` and `data` and applies it to the tables.
+    ///
+    /// Returns the decoded event, or `None` if it is not a PSP22 or PSP34 event and was skipped.
+    pub fn apply_raw<T: AsRef<[u8]>>(
+        &mut self,
+        topics: &[T],
+        data: &[u8],
+    ) -> Result<Option<Event>, IndexerError> {
+        match decode_event(topics, data) {
+            Some(event) => {
+                self.apply(&event)?;
+                Ok(Some(event))
+            }
+            None => {
+                self.skipped += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Applies the events recorded by the off-chain environment of ink!,
+    /// as returned by `ink::env::test::recorded_events()`.
+    ///
+    /// Returns the number of applied events, stopping at the first inconsistent one.
+    pub fn apply_recorded(
+        &mut self,
+        events: impl IntoIterator<Item = EmittedEvent>,
+    ) -> Result<usize, IndexerError> {
+        let mut applied = 0;
+        for event in events {
+            if self.apply_raw(&event.topics, &event.data)?.is_some() {
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Returns the tables of the PSP22 token.
+    pub fn psp22(&self) -> &PSP22Index {
+        &self.psp22
+    }
+
+    /// Returns the tables of the PSP34 collection.
+    pub fn psp34(&self) -> &PSP34Index {
+        &self.psp34
+    }
+
+    /// Returns the number of skipped events, which were not PSP22 or PSP34 events.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Exports all tables as JSON.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "psp22": self.psp22.to_json(),
+            "psp34": self.psp34.to_json(),
+            "skipped": self.skipped,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Balances and allowances of a PSP22 token rebuilt from its events.
+
+use std::collections::BTreeMap;
+
+use ink::primitives::AccountId;
+use psp22_full::PSP22Event;
+use serde_json::{json, Value};
+
+use crate::errors::IndexerError;
+use crate::export;
+
+/// State of a PSP22 token rebuilt from `Transfer` and `Approval` events.
+///
+/// As in `PSP22Data`, zero balances and allowances are not stored, so the tables
+/// can be compared entry by entry with the state of the contract.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PSP22Index {
+    total_supply: u128,
+    balances: BTreeMap<AccountId, u128>,
+    allowances: BTreeMap<(AccountId, AccountId), u128>,
+}
+
+impl PSP22Index {
+    /// Creates the tables of a token with `supply` balance held by the `creator` account,
+    /// like `PSP22Data::new`.
+    ///
+    /// Contracts do not emit an event for the supply created by their constructor,
+    /// so the index of a token starts from it rather than from empty tables.
+    pub fn new(supply: u128, creator: AccountId) -> PSP22Index {
+        let mut index = PSP22Index {
+            total_supply: supply,
+            ..Default::default()
+        };
+        index.set_balance(creator, supply);
+        index
+    }
+
+    /// Applies `event` to the tables.
+    ///
+    /// Fails without changing the tables if the event contradicts them,
+    /// e.g. a transfer above the indexed balance.
+    pub fn apply(&mut self, event: &PSP22Event) -> Result<(), IndexerError> {
+        match event {
+            PSP22Event::Transfer { from, to, value } => self.transfer(*from, *to, *value)?,
+            PSP22Event::Approval {
+                owner,
+                spender,
+                amount,
+            } => {
+                if *amount == 0 {
+                    self.allowances.remove(&(*owner, *spender));
+                } else {
+                    self.allowances.insert((*owner, *spender), *amount);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        value: u128,
+    ) -> Result<(), IndexerError> {
+        let mut total_supply = self.total_supply;
+        match from {
+            Some(from) => {
+                let balance = self.balance_of(from);
+                if balance < value {
+                    return Err(IndexerError::InsufficientBalance {
+                        account: from,
+                        balance,
+                        value,
+                    });
+                }
+            }
+            None => total_supply = total_supply.checked_add(value).ok_or(IndexerError::Overflow)?,
+        }
+        match to {
+            Some(to) if from != Some(to) => {
+                self.balance_of(to).checked_add(value).ok_or(IndexerError::Overflow)?;
+            }
+            Some(_) => {}
+            // Balances add up to the total supply, so the burned balance is covered by it
+            None => total_supply -= value,
+        }
+
+        self.total_supply = total_supply;
+        if let Some(from) = from {
+            self.set_balance(from, self.balance_of(from) - value);
+        }
+        if let Some(to) = to {
+            self.set_balance(to, self.balance_of(to) + value);
+        }
+        Ok(())
+    }
+
+    fn set_balance(&mut self, account: AccountId, balance: u128) {
+        if balance == 0 {
+            self.balances.remove(&account);
+        } else {
+            self.balances.insert(account, balance);
+        }
+    }
+
+    /// Returns the total supply, minted minus burned tokens.
+    pub fn total_supply(&self) -> u128 {
+        self.total_supply
+    }
+
+    /// Returns the balance of `owner`.
+    pub fn balance_of(&self, owner: AccountId) -> u128 {
+        self.balances.get(&owner).copied().unwrap_or_default()
+    }
+
+    /// Returns the amount `spender` is allowed to withdraw from `owner`.
+    pub fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+        self.allowances.get(&(owner, spender)).copied().unwrap_or_default()
+    }
+
+    /// Returns the non-zero balances, ordered by account.
+    pub fn balances(&self) -> impl Iterator<Item = (AccountId, u128)> + '_ {
+        self.balances.iter().map(|(account, balance)| (*account, *balance))
+    }
+
+    /// Returns the non-zero allowances as `((owner, spender), amount)`, ordered by owner and spender.
+    pub fn allowances(&self) -> impl Iterator<Item = ((AccountId, AccountId), u128)> + '_ {
+        self.allowances.iter().map(|(key, amount)| (*key, *amount))
+    }
+
+    /// Exports the tables as JSON.
+    pub fn to_json(&self) -> Value {
+        let balances: serde_json::Map<_, _> = self
+            .balances
+            .iter()
+            .map(|(account, balance)| (export::hex(account.as_ref()), export::amount(*balance)))
+            .collect();
+        let allowances: Vec<_> = self
+            .allowances
+            .iter()
+            .map(|((owner, spender), amount)| {
+                json!({
+                    "owner": export::account(owner),
+                    "spender": export::account(spender),
+                    "amount": export::amount(*amount),
+                })
+            })
+            .collect();
+        json!({
+            "total_supply": export::amount(self.total_supply),
+            "balances": balances,
+            "allowances": allowances,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+//! Ownership, approvals and attributes of a PSP34 collection rebuilt from its events.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ink::primitives::AccountId;
+use psp34_full::{Id, PSP34Event};
+use serde_json::{json, Value};
+
+use crate::errors::IndexerError;
+use crate::export;
+
+/// State of a PSP34 collection rebuilt from `Transfer`, `Approval` and `AttributeSet` events.
+///
+/// A transfer or burn of a token removes the approval consumed by the caller in `PSP34Data`.
+/// The event does not say who the caller was, so the index drops every approval granted by
+/// the previous owner for that token. Approvals of other operators may therefore still be
+/// reported by the contract while they are gone from the index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PSP34Index {
+    owners: BTreeMap<Id, AccountId>,
+    approvals: BTreeSet<(AccountId, AccountId, Option<Id>)>,
+    attributes: BTreeMap<(Id, Vec<u8>), Vec<u8>>,
+}
+
+impl PSP34Index {
+    /// Applies `event` to the tables.
+    ///
+    /// Fails without changing the tables if the event contradicts them,
+    /// e.g. a transfer of a token from an account not owning it.
+    pub fn apply(&mut self, event: &PSP34Event) -> Result<(), IndexerError> {
+        match event {
+            PSP34Event::Transfer { from, to, id } => {
+                match from {
+                    None if self.owners.contains_key(id) => {
+                        return Err(IndexerError::TokenExists(id.clone()))
+                    }
+                    Some(from) if self.owner_of(id) != Some(*from) => {
+                        return Err(IndexerError::NotOwner {
+                            id: id.clone(),
+                            from: *from,
+                            owner: self.owner_of(id),
+                        })
+                    }
+                    _ => {}
+                }
+                if let Some(from) = from {
+                    self.approvals
+                        .retain(|(owner, _, token)| !(owner == from && token.as_ref() == Some(id)));
+                }
+                match to {
+                    Some(to) => self.owners.insert(id.clone(), *to),
+                    None => self.owners.remove(id),
+                };
+            }
+            PSP34Event::Approval {
+                owner,
+                operator,
+                id,
+                approved,
+            } => {
+                let key = (*owner, *operator, id.clone());
+                if *approved {
+                    self.approvals.insert(key);
+                } else {
+                    self.approvals.remove(&key);
+                }
+            }
+            PSP34Event::AttributeSet { id, key, data } => {
+                self.attributes.insert((id.clone(), key.clone()), data.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of existing tokens.
+    pub fn total_supply(&self) -> u128 {
+        self.owners.len() as u128
+    }
+
+    /// Returns the owner of token `id`, if it exists.
+    pub fn owner_of(&self, id: &Id) -> Option<AccountId> {
+        self.owners.get(id).copied()
+    }
+
+    /// Returns the number of tokens owned by `owner`.
+    pub fn balance_of(&self, owner: AccountId) -> u32 {
+        self.owners.values().filter(|account| **account == owner).count() as u32
+    }
+
+    /// Returns the tokens owned by `owner`, ordered by id.
+    pub fn tokens_of(&self, owner: AccountId) -> Vec<Id> {
+        self.owners
+            .iter()
+            .filter(|(_, account)| **account == owner)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Returns `true` if `operator` is approved by `owner` for token `id`,
+    /// or for all its tokens, like `PSP34Data::allowance`.
+    pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<&Id>) -> bool {
+        self.approvals.contains(&(owner, operator, None))
+            || id.is_some_and(|id| self.approvals.contains(&(owner, operator, Some(id.clone()))))
+    }
+
+    /// Returns the value of the attribute `key` of token `id`, if set.
+    pub fn get_attribute(&self, id: &Id, key: &[u8]) -> Option<&[u8]> {
+        self.attributes
+            .get(&(id.clone(), key.to_vec()))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the owners of all existing tokens, ordered by id.
+    pub fn owners(&self) -> impl Iterator<Item = (&Id, AccountId)> + '_ {
+        self.owners.iter().map(|(id, owner)| (id, *owner))
+    }
+
+    /// Exports the tables as JSON.
+    pub fn to_json(&self) -> Value {
+        let owners: Vec<_> = self
+            .owners
+            .iter()
+            .map(|(id, owner)| json!({ "id": export::id(id), "owner": export::account(owner) }))
+            .collect();
+        let approvals: Vec<_> = self
+            .approvals
+            .iter()
+            .map(|(owner, operator, id)| {
+                json!({
+                    "owner": export::account(owner),
+                    "operator": export::account(operator),
+                    "id": id.as_ref().map(export::id),
+                })
+            })
+            .collect();
+        let attributes: Vec<_> = self
+            .attributes
+            .iter()
+            .map(|((id, key), data)| {
+                json!({ "id": export::id(id), "key": export::hex(key), "data": export::hex(data) })
+            })
+            .collect();
+        json!({
+            "total_supply": export::amount(self.total_supply()),
+            "owners": owners,
+            "approvals": approvals,
+            "attributes": attributes,
+        })
+    }
+}
@@ -0,0 +1,299 @@
+use ink::env::test::{run_test, DefaultAccounts, EmittedEvent};
+use ink::env::DefaultEnvironment;
+use ink::primitives::AccountId;
+use psp22_full::{PSP22Data, PSP22Event};
+use psp34_full::{Id, PSP34Data, PSP34Event};
+use scale::Encode;
+use serde_json::json;
+use smart_beaver_indexer::{decode_event, Event, Indexer, IndexerError, PSP22Index};
+
+fn in_env(test: impl FnOnce(DefaultAccounts<DefaultEnvironment>)) {
+    run_test::<DefaultEnvironment, _>(|accounts| {
+        test(accounts);
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Returns `event` as recorded from a contract emitting the PSP22 events documented in
+/// `psp22_full::events`. The payload starts with the index of the event in the contract.
+fn psp22_recorded(event: PSP22Event) -> EmittedEvent {
+    use psp22_full::events::{field_topic, signature_topic};
+    let (topics, data) = match &event {
+        PSP22Event::Transfer { from, to, value } => (
+            vec![
+                signature_topic("Transfer"),
+                field_topic("Transfer", "from", from),
+                field_topic("Transfer", "to", to),
+            ],
+            (0u8, from, to, value).encode(),
+        ),
+        PSP22Event::Approval {
+            owner,
+            spender,
+            amount,
+        } => (
+            vec![
+                signature_topic("Approval"),
+                field_topic("Approval", "owner", owner),
+                field_topic("Approval", "spender", spender),
+            ],
+            (1u8, owner, spender, amount).encode(),
+        ),
+    };
+    EmittedEvent {
+        topics: topics.into_iter().map(Vec::from).collect(),
+        data,
+    }
+}
+
+/// Returns `event` as recorded from a contract emitting the PSP34 events documented in
+/// `psp34_full::events`. The payload starts with the index of the event in the contract.
+fn psp34_recorded(event: PSP34Event) -> EmittedEvent {
+    use psp34_full::events::{field_topic, signature_topic};
+    let (topics, data) = match &event {
+        PSP34Event::Transfer { from, to, id } => (
+            vec![
+                signature_topic("Transfer"),
+                field_topic("Transfer", "from", from),
+                field_topic("Transfer", "to", to),
+                field_topic("Transfer", "id", id),
+            ],
+            (0u8, from, to, id).encode(),
+        ),
+        PSP34Event::Approval {
+            owner,
+            operator,
+            id,
+            approved,
+        } => (
+            vec![
+                signature_topic("Approval"),
+                field_topic("Approval", "owner", owner),
+                field_topic("Approval", "operator", operator),
+                field_topic("Approval", "id", id),
+            ],
+            (1u8, owner, operator, id, approved).encode(),
+        ),
+        PSP34Event::AttributeSet { id, key, data } => (
+            vec![signature_topic("AttributeSet")],
+            (2u8, id, key, data).encode(),
+        ),
+    };
+    EmittedEvent {
+        topics: topics.into_iter().map(Vec::from).collect(),
+        data,
+    }
+}
+
+#[test]
+fn psp22_tables_match_data() {
+    in_env(|accounts| {
+        let mut data: PSP22Data = PSP22Data::new(1000, accounts.alice);
+        let mut events = Vec::new();
+        events.extend(data.transfer(accounts.alice, accounts.bob, 300).unwrap());
+        events.extend(data.approve(accounts.bob, accounts.charlie, 200).unwrap());
+        events.extend(data.transfer_from(accounts.charlie, accounts.bob, accounts.django, 150).unwrap());
+        events.extend(data.mint(accounts.eve, 50).unwrap());
+        events.extend(data.burn(accounts.alice, 700).unwrap());
+        events.extend(data.approve(accounts.alice, accounts.bob, 10).unwrap());
+        events.extend(data.approve(accounts.alice, accounts.bob, 0).unwrap());
+        let count = events.len();
+
+        let mut indexer = Indexer::with_initial_supply(1000, accounts.alice);
+        let applied = indexer.apply_recorded(events.into_iter().map(psp22_recorded)).unwrap();
+        assert_eq!(applied, count);
+        assert_eq!(indexer.skipped(), 0);
+
+        let index = indexer.psp22();
+        assert_eq!(index.total_supply(), data.total_supply());
+        let accounts = [accounts.alice, accounts.bob, accounts.charlie, accounts.django, accounts.eve];
+        for owner in accounts {
+            assert_eq!(index.balance_of(owner), data.balance_of(owner));
+            for spender in accounts {
+                assert_eq!(index.allowance(owner, spender), data.allowance(owner, spender));
+            }
+        }
+        // Emptied balances and allowances are removed like in the contract storage
+        assert_eq!(index.balances().count(), 3);
+        assert_eq!(index.allowances().count(), 1);
+    });
+}
+
+#[test]
+fn psp34_tables_match_data() {
+    in_env(|accounts| {
+        let mut data: PSP34Data = PSP34Data::new();
+        let mut events = Vec::new();
+        events.extend(data.mint(accounts.alice, Id::U8(1)).unwrap());
+        events.extend(data.mint(accounts.alice, Id::U8(2)).unwrap());
+        events.extend(data.mint(accounts.bob, Id::Bytes(vec![1, 2])).unwrap());
+        events.extend(data.approve(accounts.alice, accounts.charlie, None, true).unwrap());
+        events.extend(data.approve(accounts.bob, accounts.charlie, Some(Id::Bytes(vec![1, 2])), true).unwrap());
+        events.extend(data.transfer(accounts.charlie, accounts.django, Id::U8(1), vec![]).unwrap());
+        events.extend(data.transfer(accounts.charlie, accounts.django, Id::Bytes(vec![1, 2]), vec![]).unwrap());
+        events.extend(data.burn(accounts.alice, accounts.alice, Id::U8(2)).unwrap());
+
+        let mut indexer = Indexer::new();
+        indexer.apply_recorded(events.into_iter().map(psp34_recorded)).unwrap();
+
+        let index = indexer.psp34();
+        assert_eq!(index.total_supply(), data.total_supply());
+        let ids = [Id::U8(1), Id::U8(2), Id::Bytes(vec![1, 2])];
+        for id in &ids {
+            assert_eq!(index.owner_of(id), data.owner_of(id));
+        }
+        let accounts = [accounts.alice, accounts.bob, accounts.charlie, accounts.django];
+        for owner in accounts {
+            assert_eq!(index.balance_of(owner), data.balance_of(owner));
+            for operator in accounts {
+                for id in ids.iter().map(Some).chain([None]) {
+                    assert_eq!(
+                        index.allowance(owner, operator, id),
+                        data.allowance(owner, operator, id)
+                    );
+                }
+            }
+        }
+        assert_eq!(index.tokens_of(accounts[3]), vec![Id::U8(1), Id::Bytes(vec![1, 2])]);
+    });
+}
+
+#[test]
+fn attributes_are_indexed() {
+    let attribute = psp34_recorded(PSP34Event::AttributeSet {
+        id: Id::U8(1),
+        key: b"name".to_vec(),
+        data: b"Beaver".to_vec(),
+    });
+    let mut indexer = Indexer::new();
+    indexer.apply_recorded([attribute]).unwrap();
+    assert_eq!(
+        indexer.psp34().get_attribute(&Id::U8(1), b"name"),
+        Some(&b"Beaver"[..])
+    );
+}
+
+#[test]
+fn unknown_events_are_skipped() {
+    let mut indexer = Indexer::new();
+    assert_eq!(indexer.apply_raw(&[[0u8; 32]], &[]), Ok(None));
+    assert_eq!(indexer.apply_raw::<[u8; 32]>(&[], &[]), Ok(None));
+    // An event of an extension, with the signature topic ink! derives from the contract name:
+    // the empty prefix followed by `Token::MetadataUpdated`, zero-padded
+    let mut signature = [0u8; 32];
+    signature[1..23].copy_from_slice(b"Token::MetadataUpdated");
+    assert_eq!(indexer.apply_raw(&[signature], &[0]), Ok(None));
+    assert_eq!(indexer.skipped(), 3);
+}
+
+#[test]
+fn events_are_decoded_from_topics_and_payload() {
+    let bob = AccountId::from([2; 32]);
+    let transfer = PSP22Event::Transfer {
+        from: None,
+        to: Some(bob),
+        value: 5,
+    };
+    let event = psp22_recorded(transfer.clone());
+    assert_eq!(decode_event(&event.topics, &event.data), Some(Event::PSP22(transfer)));
+    assert_eq!(decode_event(&event.topics, &[]), None);
+    // A PSP34 transfer of the same name has its own signature topic
+    let nft_transfer = PSP34Event::Transfer {
+        from: None,
+        to: Some(bob),
+        id: Id::Bytes(vec![7; 14]),
+    };
+    let event = psp34_recorded(nft_transfer.clone());
+    assert_eq!(decode_event(&event.topics, &event.data), Some(Event::PSP34(nft_transfer)));
+}
+
+#[test]
+fn inconsistent_events_are_rejected() {
+    let alice = AccountId::from([1; 32]);
+    let bob = AccountId::from([2; 32]);
+    let mut index = PSP22Index::new(10, alice);
+    let overdraft = PSP22Event::Transfer {
+        from: Some(bob),
+        to: Some(alice),
+        value: 1,
+    };
+    assert_eq!(
+        index.apply(&overdraft),
+        Err(IndexerError::InsufficientBalance {
+            account: bob,
+            balance: 0,
+            value: 1
+        })
+    );
+    let overflow = PSP22Event::Transfer {
+        from: None,
+        to: Some(alice),
+        value: u128::MAX,
+    };
+    assert_eq!(index.apply(&overflow), Err(IndexerError::Overflow));
+    assert_eq!(index, PSP22Index::new(10, alice));
+
+    let mut indexer = Indexer::new();
+    let mint = Event::PSP34(PSP34Event::Transfer {
+        from: None,
+        to: Some(alice),
+        id: Id::U8(1),
+    });
+    assert_eq!(indexer.apply(&mint), Ok(()));
+    assert_eq!(indexer.apply(&mint), Err(IndexerError::TokenExists(Id::U8(1))));
+    let stolen = Event::PSP34(PSP34Event::Transfer {
+        from: Some(bob),
+        to: Some(bob),
+        id: Id::U8(1),
+    });
+    assert_eq!(
+        indexer.apply(&stolen),
+        Err(IndexerError::NotOwner {
+            id: Id::U8(1),
+            from: bob,
+            owner: Some(alice)
+        })
+    );
+}
+
+#[test]
+fn tables_are_exported_as_json() {
+    let alice = AccountId::from([1; 32]);
+    let bob = AccountId::from([2; 32]);
+    let mut indexer = Indexer::with_initial_supply(u128::MAX, alice);
+    indexer
+        .apply(&Event::PSP22(PSP22Event::Approval {
+            owner: alice,
+            spender: bob,
+            amount: 7,
+        }))
+        .unwrap();
+    indexer
+        .apply(&Event::PSP34(PSP34Event::Transfer {
+            from: None,
+            to: Some(bob),
+            id: Id::Bytes(vec![0xab]),
+        }))
+        .unwrap();
+
+    let alice_hex = format!("0x{}", "01".repeat(32));
+    let bob_hex = format!("0x{}", "02".repeat(32));
+    assert_eq!(
+        indexer.to_json(),
+        json!({
+            "psp22": {
+                "total_supply": u128::MAX.to_string(),
+                "balances": { alice_hex.clone(): u128::MAX.to_string() },
+                "allowances": [{ "owner": alice_hex, "spender": bob_hex, "amount": "7" }],
+            },
+            "psp34": {
+                "total_supply": "1",
+                "owners": [{ "id": { "Bytes": "0xab" }, "owner": bob_hex }],
+                "approvals": [],
+                "attributes": [],
+            },
+            "skipped": 0,
+        })
+    );
+}
@@ -0,0 +1,238 @@
+//! Module keeping the stakes and rewards of the staking pool.
+
+use ink::{primitives::AccountId, storage::Mapping};
+use primitive_types::U256;
+
+use crate::StakingError;
+
+/// Scale of the reward per staked token, which is a fraction.
+pub const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Returns `a * b / c` rounded down, without intermediate overflow.
+/// Results above `u128::MAX` are saturated.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    u128::try_from(result).unwrap_or(u128::MAX)
+}
+
+/// Stakes and rewards of a staking pool, using the reward-per-token accumulator.
+///
+/// During a reward period `reward_rate` tokens are paid out per millisecond, shared among
+/// the stakers in proportion to their stakes. `reward_per_token` accumulates the reward paid
+/// to a single staked token (scaled by `PRECISION`) since the pool was created. An account
+/// earns its stake times the growth of the accumulator since its last update, so every
+/// operation costs the same whatever the number of stakers.
+///
+/// Methods take the current block timestamp `now`, the token transfers are done by the contract.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct StakingData {
+    total_staked: u128,
+    stakes: Mapping<AccountId, u128>,
+    rewards_duration: u64,
+    period_finish: u64,
+    reward_rate: u128,
+    last_update_time: u64,
+    reward_per_token_stored: u128,
+    reward_per_token_paid: Mapping<AccountId, u128>,
+    rewards: Mapping<AccountId, u128>,
+    /// Rewards paid out to the stakers until `last_update_time` and not yet taken by them.
+    unpaid_rewards: u128,
+}
+
+impl StakingData {
+    /// Creates a pool without stakes, whose reward periods last `rewards_duration` milliseconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidDuration` if `rewards_duration` is zero.
+    pub fn new(rewards_duration: u64) -> Result<StakingData, StakingError> {
+        if rewards_duration == 0 {
+            return Err(StakingError::InvalidDuration);
+        }
+        Ok(StakingData {
+            rewards_duration,
+            ..Default::default()
+        })
+    }
+
+    pub fn total_staked(&self) -> u128 {
+        self.total_staked
+    }
+
+    /// Returns the amount staked by `account`.
+    pub fn stake_of(&self, account: AccountId) -> u128 {
+        self.stakes.get(account).unwrap_or_default()
+    }
+
+    pub fn rewards_duration(&self) -> u64 {
+        self.rewards_duration
+    }
+
+    /// Returns the timestamp at which the current reward period finishes.
+    pub fn period_finish(&self) -> u64 {
+        self.period_finish
+    }
+
+    /// Returns the reward paid out per millisecond during the reward period.
+    pub fn reward_rate(&self) -> u128 {
+        self.reward_rate
+    }
+
+    /// Returns the reward paid out over a whole reward period at the current rate.
+    pub fn reward_for_duration(&self) -> u128 {
+        self.reward_rate.saturating_mul(u128::from(self.rewards_duration))
+    }
+
+    /// Returns the last moment rewards were paid out: `now`, or the end of the reward period.
+    pub fn last_time_reward_applicable(&self, now: u64) -> u64 {
+        now.min(self.period_finish)
+    }
+
+    /// Returns the reward paid out since the last update until `now`, to nobody if nothing is staked.
+    fn reward_since_update(&self, now: u64) -> u128 {
+        let elapsed = self
+            .last_time_reward_applicable(now)
+            .saturating_sub(self.last_update_time);
+        u128::from(elapsed).saturating_mul(self.reward_rate)
+    }
+
+    /// Returns the reward accumulated by a single staked token until `now`, scaled by `PRECISION`.
+    pub fn reward_per_token(&self, now: u64) -> u128 {
+        if self.total_staked == 0 {
+            return self.reward_per_token_stored;
+        }
+        self.reward_per_token_stored
+            .saturating_add(mul_div(self.reward_since_update(now), PRECISION, self.total_staked))
+    }
+
+    /// Returns the rewards the pool owes at `now`: those earned by the stakers and not taken yet,
+    /// and those still to be paid out in the current reward period.
+    ///
+    /// Rewards paid out while nothing was staked are not owed to anyone.
+    pub fn committed_rewards(&self, now: u64) -> u128 {
+        let earned = if self.total_staked == 0 {
+            self.unpaid_rewards
+        } else {
+            self.unpaid_rewards.saturating_add(self.reward_since_update(now))
+        };
+        let remaining = u128::from(self.period_finish.saturating_sub(now)).saturating_mul(self.reward_rate);
+        earned.saturating_add(remaining)
+    }
+
+    /// Returns the reward earned by `account` until `now` and not yet paid to it.
+    pub fn earned(&self, account: AccountId, now: u64) -> u128 {
+        let paid = self.reward_per_token_paid.get(account).unwrap_or_default();
+        let growth = self.reward_per_token(now).saturating_sub(paid);
+        mul_div(self.stake_of(account), growth, PRECISION)
+            .saturating_add(self.rewards.get(account).unwrap_or_default())
+    }
+
+    /// Brings the accumulator and, if given, the reward of `account` up to `now`.
+    ///
+    /// Must be called before any change of the stakes or of the reward rate.
+    fn update_reward(&mut self, account: Option<AccountId>, now: u64) {
+        if self.total_staked > 0 {
+            self.unpaid_rewards = self.unpaid_rewards.saturating_add(self.reward_since_update(now));
+        }
+        self.reward_per_token_stored = self.reward_per_token(now);
+        self.last_update_time = self.last_time_reward_applicable(now);
+        if let Some(account) = account {
+            let earned = self.earned(account, now);
+            if earned == 0 {
+                self.rewards.remove(account);
+            } else {
+                self.rewards.insert(account, &earned);
+            }
+            self.reward_per_token_paid
+                .insert(account, &self.reward_per_token_stored);
+        }
+    }
+
+    /// Adds `amount` to the stake of `account`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZeroAmount` if `amount` is zero.
+    pub fn stake(&mut self, account: AccountId, amount: u128, now: u64) -> Result<(), StakingError> {
+        if amount == 0 {
+            return Err(StakingError::ZeroAmount);
+        }
+        self.update_reward(Some(account), now);
+        self.total_staked = self.total_staked.saturating_add(amount);
+        self.stakes
+            .insert(account, &self.stake_of(account).saturating_add(amount));
+        Ok(())
+    }
+
+    /// Subtracts `amount` from the stake of `account`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZeroAmount` if `amount` is zero and `InsufficientStake` if it exceeds the stake.
+    pub fn withdraw(&mut self, account: AccountId, amount: u128, now: u64) -> Result<(), StakingError> {
+        if amount == 0 {
+            return Err(StakingError::ZeroAmount);
+        }
+        let stake = self.stake_of(account);
+        if stake < amount {
+            return Err(StakingError::InsufficientStake);
+        }
+        self.update_reward(Some(account), now);
+        self.total_staked -= amount;
+        if stake == amount {
+            self.stakes.remove(account);
+        } else {
+            self.stakes.insert(account, &(stake - amount));
+        }
+        Ok(())
+    }
+
+    /// Returns the reward earned by `account` until `now` and marks it as paid.
+    pub fn take_reward(&mut self, account: AccountId, now: u64) -> u128 {
+        self.update_reward(Some(account), now);
+        let reward = self.rewards.get(account).unwrap_or_default();
+        self.rewards.remove(account);
+        self.unpaid_rewards = self.unpaid_rewards.saturating_sub(reward);
+        reward
+    }
+
+    /// Starts a reward period paying out `reward` until `now + rewards_duration`.
+    ///
+    /// The reward left over from a period which has not finished yet is added to `reward`.
+    /// Returns the new reward rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RewardTooSmall` if the reward gives a reward rate of zero.
+    pub fn notify_reward_amount(&mut self, reward: u128, now: u64) -> Result<u128, StakingError> {
+        let duration = u128::from(self.rewards_duration);
+        let leftover = u128::from(self.period_finish.saturating_sub(now)).saturating_mul(self.reward_rate);
+        let reward_rate = reward.saturating_add(leftover) / duration;
+        if reward_rate == 0 {
+            return Err(StakingError::RewardTooSmall);
+        }
+        self.update_reward(None, now);
+        self.reward_rate = reward_rate;
+        self.last_update_time = now;
+        self.period_finish = now.saturating_add(self.rewards_duration);
+        Ok(reward_rate)
+    }
+
+    /// Sets the duration of the following reward periods.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RewardPeriodActive` if the current reward period has not finished
+    /// and `InvalidDuration` if `rewards_duration` is zero.
+    pub fn set_rewards_duration(&mut self, rewards_duration: u64, now: u64) -> Result<(), StakingError> {
+        if now < self.period_finish {
+            return Err(StakingError::RewardPeriodActive);
+        }
+        if rewards_duration == 0 {
+            return Err(StakingError::InvalidDuration);
+        }
+        self.rewards_duration = rewards_duration;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+//! Error types for the staking pool.
+
+use psp22_full::PSP22Error;
+
+/// Errors that may occur during staking pool operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum StakingError {
+    /// The caller is not the owner of the pool.
+    NotOwner,
+    /// Nothing would be staked or withdrawn, e.g. a fee-on-transfer token delivered no tokens.
+    ZeroAmount,
+    /// The amount to withdraw exceeds the stake of the caller.
+    InsufficientStake,
+    /// The reward period must last longer than zero.
+    InvalidDuration,
+    /// The reward duration cannot be changed before the current reward period finishes.
+    RewardPeriodActive,
+    /// The reward is too small to give a non-zero reward rate over the reward period.
+    RewardTooSmall,
+    /// A staked or reward token returned an error.
+    PSP22(PSP22Error),
+}
+
+impl From<PSP22Error> for StakingError {
+    fn from(error: PSP22Error) -> Self {
+        StakingError::PSP22(error)
+    }
+}
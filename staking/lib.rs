@@ -0,0 +1,325 @@
+//! Staking pool paying PSP22 rewards to PSP22 stakers.
+//!
+//! Users stake one PSP22 token and earn another, or the same one. The owner funds reward
+//! periods, during which the rewards are paid out at a constant rate shared among the
+//! stakers in proportion to their stakes.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+
+pub use data::{StakingData, PRECISION};
+pub use errors::StakingError;
+
+/// Staking pool implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod staking {
+    use ink::prelude::vec::Vec;
+    use psp22_full::PSP22Ref;
+
+    use crate::{StakingData, StakingError};
+
+    #[ink(storage)]
+    pub struct Staking {
+        /// Account which funds the reward periods.
+        owner: AccountId,
+        staking_token: AccountId,
+        rewards_token: AccountId,
+        data: StakingData,
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct RewardPaid {
+        #[ink(topic)]
+        account: AccountId,
+        reward: u128,
+    }
+
+    #[ink(event)]
+    pub struct RewardAdded {
+        reward: u128,
+        reward_rate: u128,
+        period_finish: u64,
+    }
+
+    #[ink(event)]
+    pub struct Recovered {
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct RewardsDurationUpdated {
+        rewards_duration: u64,
+    }
+
+    impl Staking {
+        /// Creates a pool owned by the caller.
+        ///
+        /// # Arguments
+        ///
+        /// * `staking_token` - The PSP22 token staked by users.
+        /// * `rewards_token` - The PSP22 token paid as rewards, may be the `staking_token`.
+        /// * `rewards_duration` - Length of the reward periods, in milliseconds.
+        #[ink(constructor)]
+        pub fn new(
+            staking_token: AccountId,
+            rewards_token: AccountId,
+            rewards_duration: u64,
+        ) -> Result<Self, StakingError> {
+            Ok(Self {
+                owner: Self::env().caller(),
+                staking_token,
+                rewards_token,
+                data: StakingData::new(rewards_duration)?,
+            })
+        }
+
+        /// Returns the account which funds the reward periods.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn staking_token(&self) -> AccountId {
+            self.staking_token
+        }
+
+        #[ink(message)]
+        pub fn rewards_token(&self) -> AccountId {
+            self.rewards_token
+        }
+
+        #[ink(message)]
+        pub fn total_staked(&self) -> u128 {
+            self.data.total_staked()
+        }
+
+        /// Returns the amount staked by `account`.
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> u128 {
+            self.data.stake_of(account)
+        }
+
+        /// Returns the reward earned by `account` and not yet paid to it.
+        #[ink(message)]
+        pub fn earned(&self, account: AccountId) -> u128 {
+            self.data.earned(account, self.env().block_timestamp())
+        }
+
+        /// Returns the reward accumulated by a single staked token, scaled by `PRECISION`.
+        #[ink(message)]
+        pub fn reward_per_token(&self) -> u128 {
+            self.data.reward_per_token(self.env().block_timestamp())
+        }
+
+        /// Returns the reward paid out per millisecond during the reward period.
+        #[ink(message)]
+        pub fn reward_rate(&self) -> u128 {
+            self.data.reward_rate()
+        }
+
+        /// Returns the reward paid out over a whole reward period at the current rate.
+        #[ink(message)]
+        pub fn reward_for_duration(&self) -> u128 {
+            self.data.reward_for_duration()
+        }
+
+        /// Returns the timestamp at which the current reward period finishes.
+        #[ink(message)]
+        pub fn period_finish(&self) -> u64 {
+            self.data.period_finish()
+        }
+
+        #[ink(message)]
+        pub fn rewards_duration(&self) -> u64 {
+            self.data.rewards_duration()
+        }
+
+        /// Stakes `amount` of the staking token, transferred from the caller.
+        ///
+        /// The caller must have approved the pool to spend `amount`. The stake is the amount
+        /// actually received by the pool, which is lower for fee-on-transfer tokens.
+        ///
+        /// # Events
+        ///
+        /// A `Staked` event is emitted with the received amount.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` if nothing was received.
+        #[ink(message)]
+        pub fn stake(&mut self, amount: u128) -> Result<(), StakingError> {
+            if amount == 0 {
+                return Err(StakingError::ZeroAmount);
+            }
+            let account = self.env().caller();
+            let received = self.receive(self.staking_token, account, amount)?;
+            self.data.stake(account, received, self.env().block_timestamp())?;
+            self.env().emit_event(Staked {
+                account,
+                amount: received,
+            });
+            Ok(())
+        }
+
+        /// Transfers `amount` of the stake of the caller back to it.
+        ///
+        /// # Events
+        ///
+        /// A `Withdrawn` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ZeroAmount` if `amount` is zero and `InsufficientStake` if it exceeds the stake.
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: u128) -> Result<(), StakingError> {
+            let account = self.env().caller();
+            self.data.withdraw(account, amount, self.env().block_timestamp())?;
+            Self::psp22(self.staking_token).transfer(account, amount, Vec::new())?;
+            self.env().emit_event(Withdrawn { account, amount });
+            Ok(())
+        }
+
+        /// Transfers the reward earned by the caller to it. No-op if nothing was earned.
+        ///
+        /// # Events
+        ///
+        /// A `RewardPaid` event is emitted if any reward was paid.
+        #[ink(message)]
+        pub fn get_reward(&mut self) -> Result<(), StakingError> {
+            let account = self.env().caller();
+            let reward = self.data.take_reward(account, self.env().block_timestamp());
+            if reward == 0 {
+                return Ok(());
+            }
+            Self::psp22(self.rewards_token).transfer(account, reward, Vec::new())?;
+            self.env().emit_event(RewardPaid { account, reward });
+            Ok(())
+        }
+
+        /// Withdraws the whole stake of the caller and pays out its reward.
+        #[ink(message)]
+        pub fn exit(&mut self) -> Result<(), StakingError> {
+            let stake = self.data.stake_of(self.env().caller());
+            if stake > 0 {
+                self.withdraw(stake)?;
+            }
+            self.get_reward()
+        }
+
+        /// Funds a reward period with `reward` of the rewards token, transferred from the caller.
+        ///
+        /// The period starts now and lasts `rewards_duration`. The reward left over from the
+        /// current period, if it has not finished yet, is paid out over the new one as well.
+        /// The caller must have approved the pool to spend `reward`, only the amount actually
+        /// received by the pool is paid out.
+        ///
+        /// # Events
+        ///
+        /// A `RewardAdded` event is emitted with the received reward.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner
+        /// and `RewardTooSmall` if the reward rate would be zero.
+        #[ink(message)]
+        pub fn notify_reward_amount(&mut self, reward: u128) -> Result<(), StakingError> {
+            self.ensure_owner()?;
+            let received = self.receive(self.rewards_token, self.owner, reward)?;
+            let now = self.env().block_timestamp();
+            let reward_rate = self.data.notify_reward_amount(received, now)?;
+            self.env().emit_event(RewardAdded {
+                reward: received,
+                reward_rate,
+                period_finish: self.data.period_finish(),
+            });
+            Ok(())
+        }
+
+        /// Sets the length of the following reward periods, in milliseconds.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner,
+        /// `RewardPeriodActive` if the current reward period has not finished
+        /// and `InvalidDuration` if `rewards_duration` is zero.
+        #[ink(message)]
+        pub fn set_rewards_duration(&mut self, rewards_duration: u64) -> Result<(), StakingError> {
+            self.ensure_owner()?;
+            self.data
+                .set_rewards_duration(rewards_duration, self.env().block_timestamp())?;
+            self.env().emit_event(RewardsDurationUpdated { rewards_duration });
+            Ok(())
+        }
+
+        /// Transfers the rewards token held by the pool beyond what it owes to `to`.
+        ///
+        /// The pool keeps the rewards earned by the stakers and not paid yet, the rewards still
+        /// to be paid out in the current reward period and, if the rewards token is the staking
+        /// token, the stakes. The rest, e.g. rewards accrued while nothing was staked or tokens
+        /// transferred to the pool directly, is recovered. No-op if nothing is left.
+        ///
+        /// # Events
+        ///
+        /// A `Recovered` event is emitted if any tokens were recovered.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner.
+        #[ink(message)]
+        pub fn recover_undistributed(&mut self, to: AccountId) -> Result<(), StakingError> {
+            self.ensure_owner()?;
+            let mut owed = self.data.committed_rewards(self.env().block_timestamp());
+            if self.rewards_token == self.staking_token {
+                owed = owed.saturating_add(self.data.total_staked());
+            }
+            let mut token = Self::psp22(self.rewards_token);
+            let amount = token.balance_of(self.env().account_id())?.saturating_sub(owed);
+            if amount == 0 {
+                return Ok(());
+            }
+            token.transfer(to, amount, Vec::new())?;
+            self.env().emit_event(Recovered { to, amount });
+            Ok(())
+        }
+
+        /// Transfers `amount` of `token` from `from` to the pool and returns the amount received,
+        /// measured as the change of the balance of the pool.
+        fn receive(&self, token: AccountId, from: AccountId, amount: u128) -> Result<u128, StakingError> {
+            let pool = self.env().account_id();
+            let mut token = Self::psp22(token);
+            let before = token.balance_of(pool)?;
+            token.transfer_from(from, pool, amount, Vec::new())?;
+            Ok(token.balance_of(pool)?.saturating_sub(before))
+        }
+
+        fn ensure_owner(&self) -> Result<(), StakingError> {
+            if self.env().caller() != self.owner {
+                return Err(StakingError::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn psp22(token: AccountId) -> PSP22Ref {
+            PSP22Ref::new(token)
+        }
+    }
+}
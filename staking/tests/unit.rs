@@ -0,0 +1,337 @@
+use staking::staking::*;
+use psp22_full::mock;
+use staking::{StakingData, StakingError};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{set_block_timestamp, set_callee, set_caller}};
+    use psp22_full::PSP22Error;
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+
+    fn owner() -> AccountId {
+        AccountId::from([0x1; 32])
+    }
+
+    fn alice() -> AccountId {
+        AccountId::from([0x2; 32])
+    }
+
+    fn bob() -> AccountId {
+        AccountId::from([0x3; 32])
+    }
+
+    fn pool_account() -> AccountId {
+        AccountId::from([0x10; 32])
+    }
+
+    fn staked_token() -> AccountId {
+        AccountId::from([0x20; 32])
+    }
+
+    fn reward_token() -> AccountId {
+        AccountId::from([0x30; 32])
+    }
+
+    /// Deploys the mocked tokens, with `staked_fee` and `reward_fee` basis points burned on transfers,
+    /// and a pool with reward periods of 100 milliseconds funded with 1000 tokens by the owner.
+    fn setup(staked_fee: u128, reward_fee: u128) -> Staking {
+        mock::deploy(staked_token(), 1_000_000, alice());
+        mock::set_fee(staked_token(), staked_fee);
+        mock::deploy(reward_token(), 1_000_000, owner());
+        mock::set_fee(reward_token(), reward_fee);
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(pool_account());
+        set_caller::<DefaultEnvironment>(owner());
+        let mut pool = Staking::new(staked_token(), reward_token(), 100).expect("Valid duration");
+        mock::approve(reward_token(), owner(), pool_account(), 1000);
+        pool.notify_reward_amount(1000).expect("Reward funded");
+        pool
+    }
+
+    fn stake(pool: &mut Staking, account: AccountId, amount: u128) -> Result<(), StakingError> {
+        set_caller::<DefaultEnvironment>(account);
+        mock::approve(pool.staking_token(), account, pool_account(), amount);
+        pool.stake(amount)
+    }
+
+    #[ink::test]
+    fn single_staker_earns_whole_reward() {
+        let mut data = StakingData::new(100).expect("Valid duration");
+        data.stake(alice(), 10, 0).expect("Staked");
+        assert_eq!(data.notify_reward_amount(1000, 0), Ok(10));
+        assert_eq!(data.period_finish(), 100);
+        assert_eq!(data.reward_for_duration(), 1000);
+
+        assert_eq!(data.earned(alice(), 0), 0);
+        assert_eq!(data.earned(alice(), 50), 500);
+        assert_eq!(data.earned(alice(), 100), 1000);
+        // Nothing is paid out after the reward period
+        assert_eq!(data.earned(alice(), 200), 1000);
+
+        assert_eq!(data.take_reward(alice(), 200), 1000);
+        assert_eq!(data.earned(alice(), 300), 0);
+        assert_eq!(data.take_reward(alice(), 300), 0);
+    }
+
+    #[ink::test]
+    fn rewards_are_shared_in_proportion_to_stakes() {
+        let mut data = StakingData::new(100).expect("Valid duration");
+        data.stake(alice(), 10, 0).expect("Staked");
+        data.notify_reward_amount(1000, 0).expect("Reward funded");
+
+        data.stake(bob(), 30, 50).expect("Staked");
+        // Alice earns everything until Bob stakes, a quarter of the reward afterwards
+        assert_eq!(data.earned(alice(), 100), 500 + 125);
+        assert_eq!(data.earned(bob(), 100), 375);
+
+        data.withdraw(bob(), 30, 100).expect("Withdrawn");
+        assert_eq!(data.stake_of(bob()), 0);
+        assert_eq!(data.total_staked(), 10);
+        assert_eq!(data.earned(bob(), 200), 375);
+    }
+
+    #[ink::test]
+    fn new_reward_adds_leftover_of_current_period() {
+        let mut data = StakingData::new(100).expect("Valid duration");
+        data.stake(alice(), 10, 0).expect("Staked");
+        data.notify_reward_amount(1000, 0).expect("Reward funded");
+
+        // 500 left over from the first period and 500 new tokens, paid out over 100 milliseconds
+        assert_eq!(data.notify_reward_amount(500, 50), Ok(10));
+        assert_eq!(data.period_finish(), 150);
+        assert_eq!(data.earned(alice(), 150), 1500);
+    }
+
+    #[ink::test]
+    fn reward_is_not_paid_without_stakers() {
+        let mut data = StakingData::new(100).expect("Valid duration");
+        data.notify_reward_amount(1000, 0).expect("Reward funded");
+        assert_eq!(data.reward_per_token(50), 0);
+
+        data.stake(alice(), 10, 50).expect("Staked");
+        assert_eq!(data.earned(alice(), 100), 500);
+    }
+
+    #[ink::test]
+    fn committed_rewards_exclude_rewards_without_stakers() {
+        let mut data = StakingData::new(100).expect("Valid duration");
+        data.notify_reward_amount(1000, 0).expect("Reward funded");
+        assert_eq!(data.committed_rewards(0), 1000);
+        assert_eq!(data.committed_rewards(20), 800);
+
+        data.stake(alice(), 10, 20).expect("Staked");
+        assert_eq!(data.committed_rewards(50), 800);
+        data.stake(bob(), 30, 50).expect("Staked");
+        assert_eq!(data.committed_rewards(50), 800);
+        assert_eq!(data.take_reward(alice(), 60), 300 + 25);
+        assert_eq!(data.committed_rewards(60), 800 - 325);
+
+        // Paid out rewards stay committed until taken
+        assert_eq!(data.committed_rewards(200), 800 - 325);
+        assert_eq!(data.take_reward(alice(), 200), 100);
+        assert_eq!(data.take_reward(bob(), 200), 375);
+        assert_eq!(data.committed_rewards(200), 0);
+    }
+
+    #[ink::test]
+    fn invalid_operations_are_rejected() {
+        assert_eq!(StakingData::new(0).err(), Some(StakingError::InvalidDuration));
+
+        let mut data = StakingData::new(100).expect("Valid duration");
+        assert_eq!(data.stake(alice(), 0, 0), Err(StakingError::ZeroAmount));
+        assert_eq!(data.notify_reward_amount(99, 0), Err(StakingError::RewardTooSmall));
+
+        data.stake(alice(), 10, 0).expect("Staked");
+        assert_eq!(data.withdraw(alice(), 0, 0), Err(StakingError::ZeroAmount));
+        assert_eq!(data.withdraw(alice(), 11, 0), Err(StakingError::InsufficientStake));
+        assert_eq!(data.withdraw(bob(), 1, 0), Err(StakingError::InsufficientStake));
+
+        data.notify_reward_amount(1000, 0).expect("Reward funded");
+        assert_eq!(data.set_rewards_duration(200, 99), Err(StakingError::RewardPeriodActive));
+        assert_eq!(data.set_rewards_duration(0, 100), Err(StakingError::InvalidDuration));
+        assert_eq!(data.set_rewards_duration(200, 100), Ok(()));
+        assert_eq!(data.rewards_duration(), 200);
+    }
+
+    #[ink::test]
+    fn large_amounts_do_not_overflow() {
+        let reward = u128::MAX / 2;
+        let mut data = StakingData::new(365 * DAY).expect("Valid duration");
+        data.stake(alice(), 1, 0).expect("Staked");
+        data.stake(bob(), u64::MAX.into(), 0).expect("Staked");
+        data.notify_reward_amount(reward, 0).expect("Reward funded");
+
+        let earned = data.earned(alice(), 365 * DAY) + data.earned(bob(), 365 * DAY);
+        // Rounding only ever keeps dust in the pool
+        assert!(earned <= reward);
+        assert!(earned > reward - reward / 1_000_000);
+    }
+
+    #[ink::test]
+    fn stake_earn_and_exit_work() {
+        let mut pool = setup(0, 0);
+        assert_eq!(pool.owner(), owner());
+        assert_eq!(pool.reward_rate(), 10);
+        assert_eq!(pool.period_finish(), 100);
+        assert_eq!(pool.rewards_duration(), 100);
+
+        assert_eq!(stake(&mut pool, alice(), 100), Ok(()));
+        assert_eq!(pool.balance_of(alice()), 100);
+        assert_eq!(pool.total_staked(), 100);
+        assert_eq!(mock::balance_of(staked_token(), pool_account()), 100);
+
+        set_block_timestamp::<DefaultEnvironment>(50);
+        assert_eq!(pool.earned(alice()), 500);
+        assert_eq!(pool.get_reward(), Ok(()));
+        assert_eq!(mock::balance_of(reward_token(), alice()), 500);
+        assert_eq!(pool.earned(alice()), 0);
+
+        set_block_timestamp::<DefaultEnvironment>(100);
+        assert_eq!(pool.exit(), Ok(()));
+        assert_eq!(pool.balance_of(alice()), 0);
+        assert_eq!(pool.total_staked(), 0);
+        assert_eq!(mock::balance_of(staked_token(), alice()), 1_000_000);
+        assert_eq!(mock::balance_of(reward_token(), alice()), 1000);
+        assert_eq!(mock::balance_of(reward_token(), pool_account()), 0);
+    }
+
+    #[ink::test]
+    fn withdraw_returns_part_of_stake() {
+        let mut pool = setup(0, 0);
+        stake(&mut pool, alice(), 100).expect("Staked");
+        assert_eq!(pool.withdraw(40), Ok(()));
+        assert_eq!(pool.balance_of(alice()), 60);
+        assert_eq!(mock::balance_of(staked_token(), alice()), 1_000_000 - 60);
+        assert_eq!(pool.withdraw(61), Err(StakingError::InsufficientStake));
+        assert_eq!(pool.withdraw(0), Err(StakingError::ZeroAmount));
+    }
+
+    #[ink::test]
+    fn fee_on_transfer_stake_is_measured() {
+        // 1% of every transfer of the staked token is burned
+        let mut pool = setup(100, 0);
+        assert_eq!(stake(&mut pool, alice(), 1000), Ok(()));
+        assert_eq!(pool.balance_of(alice()), 990);
+        assert_eq!(pool.total_staked(), 990);
+        assert_eq!(mock::balance_of(staked_token(), pool_account()), 990);
+
+        assert_eq!(pool.exit(), Ok(()));
+        assert_eq!(mock::balance_of(staked_token(), pool_account()), 0);
+        assert_eq!(pool.balance_of(alice()), 0);
+    }
+
+    #[ink::test]
+    fn fee_on_transfer_reward_is_measured() {
+        // 10% of the reward is burned on its way to the pool
+        let pool = setup(0, 1000);
+        assert_eq!(mock::balance_of(reward_token(), pool_account()), 900);
+        assert_eq!(pool.reward_rate(), 9);
+    }
+
+    #[ink::test]
+    fn stake_without_allowance_fails() {
+        let mut pool = setup(0, 0);
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(
+            pool.stake(100),
+            Err(StakingError::PSP22(PSP22Error::InsufficientAllowance))
+        );
+        assert_eq!(pool.stake(0), Err(StakingError::ZeroAmount));
+        assert_eq!(pool.total_staked(), 0);
+    }
+
+    #[ink::test]
+    fn trapping_token_fails_the_call() {
+        let mut pool = setup(0, 0);
+        stake(&mut pool, alice(), 100).expect("Staked");
+        set_block_timestamp::<DefaultEnvironment>(50);
+        mock::set_trapping(reward_token(), true);
+        assert_eq!(
+            pool.get_reward(),
+            Err(StakingError::PSP22(PSP22Error::Custom("Call failed: CalleeTrapped".into())))
+        );
+    }
+
+    #[ink::test]
+    fn same_token_can_be_staked_and_paid() {
+        mock::deploy(staked_token(), 1_000_000, owner());
+        mock::transfer(staked_token(), owner(), alice(), 100).expect("Transferred");
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(pool_account());
+        set_caller::<DefaultEnvironment>(owner());
+        let mut pool = Staking::new(staked_token(), staked_token(), 100).expect("Valid duration");
+        mock::approve(staked_token(), owner(), pool_account(), 1000);
+        pool.notify_reward_amount(1000).expect("Reward funded");
+
+        stake(&mut pool, alice(), 100).expect("Staked");
+        set_block_timestamp::<DefaultEnvironment>(100);
+        assert_eq!(pool.exit(), Ok(()));
+        assert_eq!(mock::balance_of(staked_token(), alice()), 1100);
+        assert_eq!(mock::balance_of(staked_token(), pool_account()), 0);
+    }
+
+    #[ink::test]
+    fn owner_recovers_undistributed_rewards() {
+        let mut pool = setup(0, 0);
+        set_block_timestamp::<DefaultEnvironment>(20);
+        stake(&mut pool, alice(), 100).expect("Staked");
+        // Tokens transferred to the pool directly are not owed to anyone either
+        mock::transfer(reward_token(), owner(), pool_account(), 50).expect("Transferred");
+
+        set_block_timestamp::<DefaultEnvironment>(50);
+        assert_eq!(pool.recover_undistributed(alice()), Err(StakingError::NotOwner));
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(pool.recover_undistributed(bob()), Ok(()));
+        assert_eq!(mock::balance_of(reward_token(), bob()), 200 + 50);
+        assert_eq!(pool.recover_undistributed(bob()), Ok(()));
+        assert_eq!(mock::balance_of(reward_token(), bob()), 250);
+
+        // The stakers are paid in full
+        set_block_timestamp::<DefaultEnvironment>(100);
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(pool.exit(), Ok(()));
+        assert_eq!(mock::balance_of(reward_token(), alice()), 800);
+        assert_eq!(mock::balance_of(reward_token(), pool_account()), 0);
+    }
+
+    #[ink::test]
+    fn recovery_keeps_stakes_of_rewards_token() {
+        mock::deploy(staked_token(), 1_000_000, owner());
+        mock::transfer(staked_token(), owner(), alice(), 100).expect("Transferred");
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(pool_account());
+        set_caller::<DefaultEnvironment>(owner());
+        let mut pool = Staking::new(staked_token(), staked_token(), 100).expect("Valid duration");
+        mock::approve(staked_token(), owner(), pool_account(), 1000);
+        pool.notify_reward_amount(1000).expect("Reward funded");
+
+        set_block_timestamp::<DefaultEnvironment>(50);
+        stake(&mut pool, alice(), 100).expect("Staked");
+        set_block_timestamp::<DefaultEnvironment>(100);
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(pool.recover_undistributed(bob()), Ok(()));
+        assert_eq!(mock::balance_of(staked_token(), bob()), 500);
+
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(pool.exit(), Ok(()));
+        assert_eq!(mock::balance_of(staked_token(), alice()), 100 + 500);
+        assert_eq!(mock::balance_of(staked_token(), pool_account()), 0);
+    }
+
+    #[ink::test]
+    fn only_owner_manages_rewards() {
+        let mut pool = setup(0, 0);
+        set_caller::<DefaultEnvironment>(alice());
+        assert_eq!(pool.notify_reward_amount(1000), Err(StakingError::NotOwner));
+        assert_eq!(pool.set_rewards_duration(200), Err(StakingError::NotOwner));
+
+        set_caller::<DefaultEnvironment>(owner());
+        assert_eq!(pool.set_rewards_duration(200), Err(StakingError::RewardPeriodActive));
+        set_block_timestamp::<DefaultEnvironment>(100);
+        assert_eq!(pool.set_rewards_duration(200), Ok(()));
+        assert_eq!(pool.rewards_duration(), 200);
+    }
+}
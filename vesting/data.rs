@@ -0,0 +1,97 @@
+//! Module describing vesting schedules.
+
+use crate::VestingError;
+
+/// Length of a month used by graded schedules, in milliseconds (30 days).
+pub const MONTH: u64 = 30 * 24 * 60 * 60 * 1000;
+
+/// Describes how a grant is released over time.
+///
+/// Nothing is vested before `start + cliff`. Afterwards the vested amount grows
+/// in steps of `period` until the whole grant is vested at `start + duration`.
+/// A `period` of one millisecond gives a linear schedule, a `period` of `MONTH`
+/// a graded monthly one. All timestamps are in milliseconds, like block timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct VestingSchedule {
+    start: u64,
+    cliff: u64,
+    duration: u64,
+    period: u64,
+}
+
+impl VestingSchedule {
+    /// Creates a schedule releasing the grant linearly between `start` and `start + duration`,
+    /// with nothing released before `start + cliff`.
+    pub fn linear(start: u64, cliff: u64, duration: u64) -> Result<VestingSchedule, VestingError> {
+        Self::new(start, cliff, duration, 1)
+    }
+
+    /// Creates a schedule releasing an equal part of the grant at the end of each of `months`
+    /// months after `start`, with nothing released during the first `cliff_months` months.
+    pub fn graded(start: u64, cliff_months: u32, months: u32) -> Result<VestingSchedule, VestingError> {
+        let duration = MONTH
+            .checked_mul(u64::from(months))
+            .ok_or(VestingError::InvalidSchedule)?;
+        Self::new(start, MONTH.saturating_mul(u64::from(cliff_months)), duration, MONTH)
+    }
+
+    /// Creates a schedule vesting in steps of `period`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSchedule` if `duration` or `period` is zero, `cliff` exceeds `duration`
+    /// or `period` does not divide `duration`.
+    pub fn new(start: u64, cliff: u64, duration: u64, period: u64) -> Result<VestingSchedule, VestingError> {
+        if duration == 0 || period == 0 || cliff > duration || !duration.is_multiple_of(period) {
+            return Err(VestingError::InvalidSchedule);
+        }
+        Ok(VestingSchedule {
+            start,
+            cliff,
+            duration,
+            period,
+        })
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn cliff(&self) -> u64 {
+        self.cliff
+    }
+
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
+
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Returns the part of a grant of `total` tokens vested at `now`.
+    ///
+    /// The result is rounded down and never exceeds `total`. The calculation cannot overflow.
+    pub fn vested_amount(&self, total: u128, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.start);
+        if now < self.start || elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return total;
+        }
+        let elapsed = u128::from(elapsed - elapsed % self.period);
+        let duration = u128::from(self.duration);
+        // `total` is split into `quotient * duration + remainder`, so that
+        // neither of the products below can exceed `total` or `duration^2`.
+        let quotient = total / duration;
+        let remainder = total % duration;
+        quotient
+            .saturating_mul(elapsed)
+            .saturating_add(remainder.saturating_mul(elapsed) / duration)
+    }
+}
@@ -0,0 +1,26 @@
+//! Error types for the vesting wallet.
+
+use psp22_full::PSP22Error;
+
+/// Errors that may occur during vesting wallet operations.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VestingError {
+    /// The schedule has a zero duration, a cliff longer than the duration
+    /// or a period which does not divide the duration.
+    InvalidSchedule,
+    /// The caller is not the grantor of the vesting wallet.
+    NotOwner,
+    /// The grant was created as irrevocable.
+    NotRevocable,
+    AlreadyRevoked,
+    NotRevoked,
+    /// The vested token returned an error.
+    PSP22(PSP22Error),
+}
+
+impl From<PSP22Error> for VestingError {
+    fn from(error: PSP22Error) -> Self {
+        VestingError::PSP22(error)
+    }
+}
@@ -0,0 +1,253 @@
+//! Vesting wallet releasing PSP22 tokens to a beneficiary.
+//!
+//! The wallet vests every PSP22 token it holds according to a single `VestingSchedule`.
+//! Tokens are granted by simply transferring them to the wallet.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod data;
+pub mod errors;
+
+pub use data::{VestingSchedule, MONTH};
+pub use errors::VestingError;
+
+/// Vesting wallet implementation.
+#[cfg(feature = "contract")]
+#[ink::contract]
+pub mod vesting {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use psp22_full::PSP22Ref;
+
+    use crate::{VestingError, VestingSchedule};
+
+    #[ink(storage)]
+    pub struct Vesting {
+        /// Account which created the grant and may revoke it.
+        owner: AccountId,
+        beneficiary: AccountId,
+        schedule: VestingSchedule,
+        revocable: bool,
+        /// Timestamp at which the grant was revoked. Vesting stops at this moment.
+        revoked_at: Option<u64>,
+        /// Amounts of each token released to the beneficiary.
+        released: Mapping<AccountId, u128>,
+        /// Amounts of each token returned to the owner after revocation.
+        reclaimed: Mapping<AccountId, u128>,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        token: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct Revoked {
+        timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct Reclaimed {
+        #[ink(topic)]
+        token: AccountId,
+        amount: u128,
+    }
+
+    impl Vesting {
+        /// Creates a wallet releasing tokens linearly to `beneficiary`.
+        ///
+        /// # Arguments
+        ///
+        /// * `beneficiary` - The account receiving the released tokens.
+        /// * `start` - Timestamp at which vesting starts.
+        /// * `cliff` - Time after `start` before which nothing is released.
+        /// * `duration` - Time after `start` at which everything is released.
+        /// * `revocable` - Whether the caller may stop the vesting and reclaim unvested tokens.
+        #[ink(constructor)]
+        pub fn new_linear(
+            beneficiary: AccountId,
+            start: u64,
+            cliff: u64,
+            duration: u64,
+            revocable: bool,
+        ) -> Result<Self, VestingError> {
+            let schedule = VestingSchedule::linear(start, cliff, duration)?;
+            Ok(Self::new(beneficiary, schedule, revocable))
+        }
+
+        /// Creates a wallet releasing an equal part of the tokens to `beneficiary` every month.
+        ///
+        /// # Arguments
+        ///
+        /// * `beneficiary` - The account receiving the released tokens.
+        /// * `start` - Timestamp at which vesting starts.
+        /// * `cliff_months` - Number of months after `start` during which nothing is released.
+        /// * `months` - Number of monthly installments.
+        /// * `revocable` - Whether the caller may stop the vesting and reclaim unvested tokens.
+        #[ink(constructor)]
+        pub fn new_graded(
+            beneficiary: AccountId,
+            start: u64,
+            cliff_months: u32,
+            months: u32,
+            revocable: bool,
+        ) -> Result<Self, VestingError> {
+            let schedule = VestingSchedule::graded(start, cliff_months, months)?;
+            Ok(Self::new(beneficiary, schedule, revocable))
+        }
+
+        fn new(beneficiary: AccountId, schedule: VestingSchedule, revocable: bool) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                beneficiary,
+                schedule,
+                revocable,
+                revoked_at: None,
+                released: Default::default(),
+                reclaimed: Default::default(),
+            }
+        }
+
+        /// Returns the account receiving the released tokens.
+        #[ink(message)]
+        pub fn beneficiary(&self) -> AccountId {
+            self.beneficiary
+        }
+
+        /// Returns the account which created the grant.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn schedule(&self) -> VestingSchedule {
+            self.schedule
+        }
+
+        #[ink(message)]
+        pub fn revocable(&self) -> bool {
+            self.revocable
+        }
+
+        /// Returns the timestamp at which the grant was revoked, if it was.
+        #[ink(message)]
+        pub fn revoked_at(&self) -> Option<u64> {
+            self.revoked_at
+        }
+
+        /// Returns the amount of `token` already released to the beneficiary.
+        #[ink(message)]
+        pub fn released(&self, token: AccountId) -> u128 {
+            self.released.get(token).unwrap_or_default()
+        }
+
+        /// Returns the amount of `token` which can be released to the beneficiary now.
+        ///
+        /// # Errors
+        ///
+        /// Returns `PSP22` if the balance of the wallet could not be read from `token`.
+        #[ink(message)]
+        pub fn releasable(&self, token: AccountId) -> Result<u128, VestingError> {
+            let total = self.total_allocation(token)?;
+            Ok(self.vested_amount(total).saturating_sub(self.released(token)))
+        }
+
+        /// Transfers the releasable amount of `token` to the beneficiary.
+        ///
+        /// Can be called by anyone. No-op if nothing is releasable.
+        ///
+        /// # Events
+        ///
+        /// A `Released` event is emitted if any tokens were released.
+        #[ink(message)]
+        pub fn release(&mut self, token: AccountId) -> Result<(), VestingError> {
+            let amount = self.releasable(token)?;
+            if amount == 0 {
+                return Ok(());
+            }
+            self.released
+                .insert(token, &self.released(token).saturating_add(amount));
+            Self::psp22(token).transfer(self.beneficiary, amount, Vec::new())?;
+            self.env().emit_event(Released { token, amount });
+            Ok(())
+        }
+
+        /// Stops the vesting at the current block timestamp.
+        ///
+        /// Tokens vested until now can still be released to the beneficiary,
+        /// the rest can be returned to the owner with `reclaim`.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner,
+        /// `NotRevocable` if the grant is irrevocable and `AlreadyRevoked` if it was already revoked.
+        #[ink(message)]
+        pub fn revoke(&mut self) -> Result<(), VestingError> {
+            self.ensure_owner()?;
+            if !self.revocable {
+                return Err(VestingError::NotRevocable);
+            }
+            if self.revoked_at.is_some() {
+                return Err(VestingError::AlreadyRevoked);
+            }
+            let timestamp = self.env().block_timestamp();
+            self.revoked_at = Some(timestamp);
+            self.env().emit_event(Revoked { timestamp });
+            Ok(())
+        }
+
+        /// Transfers the unvested amount of `token` back to the owner of a revoked grant.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotOwner` if called by an account other than the owner
+        /// and `NotRevoked` if the grant was not revoked.
+        #[ink(message)]
+        pub fn reclaim(&mut self, token: AccountId) -> Result<(), VestingError> {
+            self.ensure_owner()?;
+            if self.revoked_at.is_none() {
+                return Err(VestingError::NotRevoked);
+            }
+            let total = self.total_allocation(token)?;
+            let reclaimed = self.reclaimed.get(token).unwrap_or_default();
+            let amount = total
+                .saturating_sub(self.vested_amount(total))
+                .saturating_sub(reclaimed);
+            if amount == 0 {
+                return Ok(());
+            }
+            self.reclaimed.insert(token, &reclaimed.saturating_add(amount));
+            Self::psp22(token).transfer(self.owner, amount, Vec::new())?;
+            self.env().emit_event(Reclaimed { token, amount });
+            Ok(())
+        }
+
+        /// Returns the part of `total` vested now, or at the moment of revocation.
+        fn vested_amount(&self, total: u128) -> u128 {
+            let now = self.env().block_timestamp();
+            let until = self.revoked_at.map_or(now, |revoked_at| revoked_at.min(now));
+            self.schedule.vested_amount(total, until)
+        }
+
+        /// Returns the amount of `token` ever granted: the current balance and everything paid out.
+        fn total_allocation(&self, token: AccountId) -> Result<u128, VestingError> {
+            Ok(Self::psp22(token)
+                .balance_of(self.env().account_id())?
+                .saturating_add(self.released(token))
+                .saturating_add(self.reclaimed.get(token).unwrap_or_default()))
+        }
+
+        fn ensure_owner(&self) -> Result<(), VestingError> {
+            if self.env().caller() != self.owner {
+                return Err(VestingError::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn psp22(token: AccountId) -> PSP22Ref {
+            PSP22Ref::new(token)
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use psp22_full::mock;
+use vesting::vesting::*;
+use vesting::{VestingError, VestingSchedule, MONTH};
+
+#[cfg(test)]
+mod tests {
+    use ink::primitives::AccountId;
+    use ink::env::{DefaultEnvironment, test::{set_block_timestamp, set_callee, set_caller}};
+    use psp22_full::PSP22Error;
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+
+    fn owner() -> AccountId {
+        AccountId::from([0x1; 32])
+    }
+
+    fn beneficiary() -> AccountId {
+        AccountId::from([0x2; 32])
+    }
+
+    fn token() -> AccountId {
+        AccountId::from([0x3; 32])
+    }
+
+    fn wallet() -> AccountId {
+        AccountId::from([0x4; 32])
+    }
+
+    /// Deploys a mocked token and a revocable wallet vesting linearly over 100 milliseconds,
+    /// granted 1000 tokens by the owner.
+    fn setup() -> Vesting {
+        mock::deploy(token(), 1_000_000, owner());
+        mock::transfer(token(), owner(), wallet(), 1000).expect("Granted");
+        set_block_timestamp::<DefaultEnvironment>(0);
+        set_callee::<DefaultEnvironment>(wallet());
+        set_caller::<DefaultEnvironment>(owner());
+        Vesting::new_linear(beneficiary(), 0, 0, 100, true).expect("Valid schedule")
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        assert_eq!(VestingSchedule::linear(0, 0, 0), Err(VestingError::InvalidSchedule));
+        assert_eq!(VestingSchedule::linear(0, 11, 10), Err(VestingError::InvalidSchedule));
+        assert_eq!(VestingSchedule::new(0, 0, 10, 0), Err(VestingError::InvalidSchedule));
+        assert_eq!(VestingSchedule::new(0, 0, 10, 3), Err(VestingError::InvalidSchedule));
+        assert_eq!(VestingSchedule::graded(0, 0, 0), Err(VestingError::InvalidSchedule));
+        assert_eq!(VestingSchedule::graded(0, 13, 12), Err(VestingError::InvalidSchedule));
+        assert!(VestingSchedule::linear(0, 10, 10).is_ok());
+    }
+
+    #[test]
+    fn linear_schedule_with_cliff_works() {
+        let start = 1_000;
+        let schedule = VestingSchedule::linear(start, 100 * DAY, 400 * DAY).expect("Valid schedule");
+
+        assert_eq!(schedule.vested_amount(4_000, 0), 0);
+        assert_eq!(schedule.vested_amount(4_000, start), 0);
+        assert_eq!(schedule.vested_amount(4_000, start + 100 * DAY - 1), 0);
+        // Everything vested until the cliff is released at once
+        assert_eq!(schedule.vested_amount(4_000, start + 100 * DAY), 1_000);
+        assert_eq!(schedule.vested_amount(4_000, start + 200 * DAY), 2_000);
+        assert_eq!(schedule.vested_amount(4_000, start + 400 * DAY - 1), 3_999);
+        assert_eq!(schedule.vested_amount(4_000, start + 400 * DAY), 4_000);
+        assert_eq!(schedule.vested_amount(4_000, u64::MAX), 4_000);
+    }
+
+    #[test]
+    fn graded_schedule_releases_monthly() {
+        let start = 1_000;
+        let schedule = VestingSchedule::graded(start, 3, 12).expect("Valid schedule");
+        assert_eq!(schedule.period(), MONTH);
+        assert_eq!(schedule.duration(), 12 * MONTH);
+
+        assert_eq!(schedule.vested_amount(1_200, start + 3 * MONTH - 1), 0);
+        assert_eq!(schedule.vested_amount(1_200, start + 3 * MONTH), 300);
+        assert_eq!(schedule.vested_amount(1_200, start + 4 * MONTH - 1), 300);
+        assert_eq!(schedule.vested_amount(1_200, start + 4 * MONTH), 400);
+        assert_eq!(schedule.vested_amount(1_200, start + 12 * MONTH - 1), 1_100);
+        assert_eq!(schedule.vested_amount(1_200, start + 12 * MONTH), 1_200);
+    }
+
+    #[test]
+    fn vested_amount_does_not_overflow() {
+        let schedule = VestingSchedule::linear(0, 0, u64::MAX - 1).expect("Valid schedule");
+        assert_eq!(schedule.vested_amount(u128::MAX, u64::MAX / 2), u128::MAX / 2);
+        assert_eq!(schedule.vested_amount(u128::MAX, u64::MAX), u128::MAX);
+
+        // Vested amounts are rounded down and never decrease
+        let schedule = VestingSchedule::linear(0, 0, 7).expect("Valid schedule");
+        let vested: Vec<u128> = (0..=7).map(|now| schedule.vested_amount(3, now)).collect();
+        assert_eq!(vested, vec![0, 0, 0, 1, 1, 2, 2, 3]);
+    }
+
+    #[ink::test]
+    fn constructors_validate_schedule() {
+        let beneficiary = AccountId::from([0x2; 32]);
+        assert!(matches!(
+            Vesting::new_linear(beneficiary, 0, 2, 1, true),
+            Err(VestingError::InvalidSchedule)
+        ));
+        assert!(matches!(
+            Vesting::new_graded(beneficiary, 0, 0, 0, true),
+            Err(VestingError::InvalidSchedule)
+        ));
+
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let vesting = Vesting::new_graded(beneficiary, 5, 1, 24, false).expect("Valid schedule");
+        assert_eq!(vesting.owner(), owner);
+        assert_eq!(vesting.beneficiary(), beneficiary);
+        assert_eq!(vesting.schedule(), VestingSchedule::graded(5, 1, 24).expect("Valid schedule"));
+        assert!(!vesting.revocable());
+        assert_eq!(vesting.revoked_at(), None);
+        assert_eq!(vesting.released(AccountId::from([0x3; 32])), 0);
+    }
+
+    #[ink::test]
+    fn revoke_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let beneficiary = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut vesting = Vesting::new_linear(beneficiary, 0, 0, 100, true).expect("Valid schedule");
+
+        set_block_timestamp::<DefaultEnvironment>(40);
+        assert_eq!(vesting.reclaim(AccountId::from([0x3; 32])), Err(VestingError::NotRevoked));
+        assert_eq!(vesting.revoke(), Ok(()));
+        assert_eq!(vesting.revoked_at(), Some(40));
+        assert_eq!(vesting.revoke(), Err(VestingError::AlreadyRevoked));
+    }
+
+    #[ink::test]
+    fn revoke_by_non_owner_fails() {
+        let owner = AccountId::from([0x1; 32]);
+        let beneficiary = AccountId::from([0x2; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut vesting = Vesting::new_linear(beneficiary, 0, 0, 100, true).expect("Valid schedule");
+
+        set_caller::<DefaultEnvironment>(beneficiary);
+        assert_eq!(vesting.revoke(), Err(VestingError::NotOwner));
+        assert_eq!(vesting.reclaim(AccountId::from([0x3; 32])), Err(VestingError::NotOwner));
+        assert_eq!(vesting.revoked_at(), None);
+    }
+
+    #[ink::test]
+    fn irrevocable_grant_cannot_be_revoked() {
+        let owner = AccountId::from([0x1; 32]);
+        set_caller::<DefaultEnvironment>(owner);
+        let mut vesting = Vesting::new_linear(AccountId::from([0x2; 32]), 0, 0, 100, false)
+            .expect("Valid schedule");
+        assert_eq!(vesting.revoke(), Err(VestingError::NotRevocable));
+    }
+
+    #[ink::test]
+    fn partial_release_follows_schedule() {
+        let mut vesting = setup();
+        set_caller::<DefaultEnvironment>(beneficiary());
+        assert_eq!(vesting.releasable(token()), Ok(0));
+        assert_eq!(vesting.release(token()), Ok(()));
+        assert_eq!(mock::balance_of(token(), beneficiary()), 0);
+
+        set_block_timestamp::<DefaultEnvironment>(25);
+        assert_eq!(vesting.releasable(token()), Ok(250));
+        assert_eq!(vesting.release(token()), Ok(()));
+        assert_eq!(vesting.released(token()), 250);
+        assert_eq!(vesting.releasable(token()), Ok(0));
+        assert_eq!(mock::balance_of(token(), beneficiary()), 250);
+        assert_eq!(mock::balance_of(token(), wallet()), 750);
+
+        // A later grant vests on the same schedule, including the time already elapsed
+        set_block_timestamp::<DefaultEnvironment>(60);
+        mock::transfer(token(), owner(), wallet(), 1000).expect("Granted");
+        assert_eq!(vesting.releasable(token()), Ok(1200 - 250));
+
+        set_block_timestamp::<DefaultEnvironment>(100);
+        assert_eq!(vesting.release(token()), Ok(()));
+        assert_eq!(vesting.released(token()), 2000);
+        assert_eq!(mock::balance_of(token(), beneficiary()), 2000);
+        assert_eq!(mock::balance_of(token(), wallet()), 0);
+    }
+
+    #[ink::test]
+    fn release_after_revoke_and_reclaim_work() {
+        let mut vesting = setup();
+        set_block_timestamp::<DefaultEnvironment>(30);
+        assert_eq!(vesting.release(token()), Ok(()));
+        set_block_timestamp::<DefaultEnvironment>(40);
+        assert_eq!(vesting.revoke(), Ok(()));
+
+        // Vesting stopped at the revocation
+        set_block_timestamp::<DefaultEnvironment>(80);
+        assert_eq!(vesting.releasable(token()), Ok(100));
+        assert_eq!(vesting.reclaim(token()), Ok(()));
+        assert_eq!(mock::balance_of(token(), owner()), 1_000_000 - 1000 + 600);
+        assert_eq!(vesting.reclaim(token()), Ok(()));
+        assert_eq!(mock::balance_of(token(), owner()), 1_000_000 - 1000 + 600);
+
+        assert_eq!(vesting.releasable(token()), Ok(100));
+        assert_eq!(vesting.release(token()), Ok(()));
+        assert_eq!(mock::balance_of(token(), beneficiary()), 400);
+        assert_eq!(mock::balance_of(token(), wallet()), 0);
+        assert_eq!(vesting.releasable(token()), Ok(0));
+    }
+
+    #[ink::test]
+    fn failing_token_returns_error() {
+        let mut vesting = setup();
+        set_block_timestamp::<DefaultEnvironment>(50);
+        mock::set_paused(token(), true);
+        assert_eq!(
+            vesting.release(token()),
+            Err(VestingError::PSP22(PSP22Error::Custom("Token is paused".into())))
+        );
+
+        mock::set_trapping(token(), true);
+        assert_eq!(
+            vesting.releasable(token()),
+            Err(VestingError::PSP22(PSP22Error::Custom("Call failed: CalleeTrapped".into())))
+        );
+        assert_eq!(vesting.revoke(), Ok(()));
+        assert_eq!(
+            vesting.reclaim(token()),
+            Err(VestingError::PSP22(PSP22Error::Custom("Call failed: CalleeTrapped".into())))
+        );
+    }
+}